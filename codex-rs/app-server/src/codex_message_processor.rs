@@ -7624,6 +7624,7 @@ fn with_thread_spawn_agent_metadata(
                 depth,
                 agent_nickname: existing_agent_nickname,
                 agent_role: existing_agent_role,
+                call_id,
             },
         ) => codex_protocol::protocol::SessionSource::SubAgent(
             codex_protocol::protocol::SubAgentSource::ThreadSpawn {
@@ -7631,6 +7632,7 @@ fn with_thread_spawn_agent_metadata(
                 depth,
                 agent_nickname: agent_nickname.or(existing_agent_nickname),
                 agent_role: agent_role.or(existing_agent_role),
+                call_id,
             },
         ),
         _ => source,
@@ -7968,6 +7970,7 @@ mod tests {
                 depth: 1,
                 agent_nickname: None,
                 agent_role: None,
+                call_id: None,
             }),
             agent_nickname: Some("atlas".to_string()),
             agent_role: Some("explorer".to_string()),
@@ -8060,6 +8063,7 @@ mod tests {
                 depth: 1,
                 agent_nickname: None,
                 agent_role: None,
+                call_id: None,
             }))?;
 
         let summary = summary_from_state_db_metadata(
@@ -135,6 +135,7 @@ mod tests {
             depth: 1,
             agent_nickname: None,
             agent_role: None,
+            call_id: None,
         });
 
         assert!(source_kind_matches(
@@ -13,7 +13,9 @@ use crate::shell_snapshot::ShellSnapshot;
 use crate::state_db;
 use crate::thread_manager::ThreadManagerState;
 use codex_protocol::ThreadId;
+use codex_protocol::models::ContentItem;
 use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::InitialHistory;
 use codex_protocol::protocol::Op;
@@ -22,6 +24,8 @@ use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::SubAgentSource;
 use codex_protocol::protocol::TokenUsage;
 use codex_protocol::user_input::UserInput;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Weak;
 use tokio::sync::watch;
@@ -32,6 +36,9 @@ const FORKED_SPAWN_AGENT_OUTPUT_MESSAGE: &str = "You are the newly spawned agent
 #[derive(Clone, Debug, Default)]
 pub(crate) struct SpawnAgentOptions {
     pub(crate) fork_parent_spawn_call_id: Option<String>,
+    /// Prior turns to start the thread from instead of an empty history. Ignored when forking
+    /// the parent.
+    pub(crate) seed_history: Option<Vec<ResponseItem>>,
 }
 
 fn default_agent_nickname_list() -> Vec<&'static str> {
@@ -110,18 +117,23 @@ impl AgentControl {
             Some(SessionSource::SubAgent(SubAgentSource::ThreadSpawn {
                 parent_thread_id,
                 depth,
+                agent_nickname: requested_nickname,
                 agent_role,
-                ..
+                call_id,
             })) => {
                 let candidate_names = agent_nickname_candidates(&config, agent_role.as_deref());
                 let candidate_name_refs: Vec<&str> =
                     candidate_names.iter().map(String::as_str).collect();
-                let agent_nickname = reservation.reserve_agent_nickname(&candidate_name_refs)?;
+                let agent_nickname = reservation.reserve_agent_nickname_with_preference(
+                    &candidate_name_refs,
+                    requested_nickname.as_deref(),
+                )?;
                 Some(SessionSource::SubAgent(SubAgentSource::ThreadSpawn {
                     parent_thread_id,
                     depth,
                     agent_nickname: Some(agent_nickname),
                     agent_role,
+                    call_id,
                 }))
             }
             other => other,
@@ -190,6 +202,23 @@ impl AgentControl {
                             inherited_shell_snapshot,
                         )
                         .await?
+                } else if let Some(seed_history) = options.seed_history {
+                    let initial_history = InitialHistory::Forked(
+                        seed_history
+                            .into_iter()
+                            .map(RolloutItem::ResponseItem)
+                            .collect(),
+                    );
+                    state
+                        .fork_thread_with_source(
+                            config,
+                            initial_history,
+                            self.clone(),
+                            session_source,
+                            false,
+                            inherited_shell_snapshot,
+                        )
+                        .await?
                 } else {
                     state
                         .spawn_new_thread_with_source(
@@ -239,18 +268,23 @@ impl AgentControl {
             Some(SessionSource::SubAgent(SubAgentSource::ThreadSpawn {
                 parent_thread_id,
                 depth,
+                agent_nickname: requested_nickname,
                 agent_role,
-                ..
+                call_id,
             })) => {
                 let candidate_names = agent_nickname_candidates(&config, agent_role.as_deref());
                 let candidate_name_refs: Vec<&str> =
                     candidate_names.iter().map(String::as_str).collect();
-                let agent_nickname = reservation.reserve_agent_nickname(&candidate_name_refs)?;
+                let agent_nickname = reservation.reserve_agent_nickname_with_preference(
+                    &candidate_name_refs,
+                    requested_nickname.as_deref(),
+                )?;
                 Some(SessionSource::SubAgent(SubAgentSource::ThreadSpawn {
                     parent_thread_id,
                     depth,
                     agent_nickname: Some(agent_nickname),
                     agent_role,
+                    call_id,
                 }))
             }
             other => other,
@@ -321,6 +355,23 @@ impl AgentControl {
                             inherited_shell_snapshot,
                         )
                         .await?
+                } else if let Some(seed_history) = options.seed_history {
+                    let initial_history = InitialHistory::Forked(
+                        seed_history
+                            .into_iter()
+                            .map(RolloutItem::ResponseItem)
+                            .collect(),
+                    );
+                    state
+                        .fork_thread_with_source(
+                            config,
+                            initial_history,
+                            self.clone(),
+                            session_source,
+                            false,
+                            inherited_shell_snapshot,
+                        )
+                        .await?
                 } else {
                     state
                         .spawn_new_thread_with_source(
@@ -365,6 +416,18 @@ impl AgentControl {
         Ok(())
     }
 
+    /// Export `env` to the shell commands `agent_id` runs from its next turn onward.
+    pub(crate) async fn merge_agent_shell_environment(
+        &self,
+        agent_id: ThreadId,
+        env: HashMap<String, String>,
+    ) -> CodexResult<()> {
+        let state = self.upgrade()?;
+        let thread = state.get_thread(agent_id).await?;
+        thread.codex.session.merge_shell_environment(env).await;
+        Ok(())
+    }
+
     pub(crate) fn spawned_thread_ids(&self) -> Vec<ThreadId> {
         self.state.spawned_thread_ids()
     }
@@ -382,6 +445,7 @@ impl AgentControl {
             SessionSource::SubAgent(SubAgentSource::ThreadSpawn {
                 parent_thread_id,
                 depth,
+                call_id,
                 ..
             }) => {
                 // Collab resume callers rebuild a placeholder ThreadSpawn source. Rehydrate the
@@ -413,6 +477,7 @@ impl AgentControl {
                     depth,
                     agent_nickname: reserved_agent_nickname,
                     agent_role: resumed_agent_role,
+                    call_id,
                 })
             }
             other => other,
@@ -449,6 +514,18 @@ impl AgentControl {
         &self,
         agent_id: ThreadId,
         items: Vec<UserInput>,
+    ) -> CodexResult<String> {
+        self.send_input_with_output_schema(agent_id, items, None)
+            .await
+    }
+
+    /// Send user input to an existing agent, optionally constraining its final answer to
+    /// `final_output_json_schema`.
+    pub(crate) async fn send_input_with_output_schema(
+        &self,
+        agent_id: ThreadId,
+        items: Vec<UserInput>,
+        final_output_json_schema: Option<serde_json::Value>,
     ) -> CodexResult<String> {
         let state = self.upgrade()?;
         let result = state
@@ -456,7 +533,7 @@ impl AgentControl {
                 agent_id,
                 Op::UserInput {
                     items,
-                    final_output_json_schema: None,
+                    final_output_json_schema,
                 },
             )
             .await;
@@ -471,8 +548,10 @@ impl AgentControl {
         &self,
         agent_id: ThreadId,
         items: Vec<UserInput>,
+        final_output_json_schema: Option<serde_json::Value>,
     ) -> CodexResult<String> {
-        self.send_input(agent_id, items).await
+        self.send_input_with_output_schema(agent_id, items, final_output_json_schema)
+            .await
     }
 
     /// Interrupt the current task for an existing agent thread.
@@ -481,6 +560,34 @@ impl AgentControl {
         state.send_op(agent_id, Op::Interrupt).await
     }
 
+    /// Interrupt the current task for an existing agent thread, returning the user input that
+    /// was queued for that task so the caller can resend it instead of losing it.
+    pub(crate) async fn interrupt_agent_keeping_pending_input(
+        &self,
+        agent_id: ThreadId,
+    ) -> CodexResult<Vec<UserInput>> {
+        let state = self.upgrade()?;
+        let thread = state.get_thread(agent_id).await?;
+        let pending_input = thread.codex.session.get_pending_input().await;
+        state.send_op(agent_id, Op::Interrupt).await?;
+        Ok(pending_input
+            .into_iter()
+            .filter_map(|item| match item {
+                ResponseInputItem::Message { role, content } if role == "user" => Some(content),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|content| match content {
+                ContentItem::InputText { text } => Some(UserInput::Text {
+                    text,
+                    text_elements: Vec::new(),
+                }),
+                ContentItem::InputImage { image_url } => Some(UserInput::Image { image_url }),
+                ContentItem::OutputText { .. } => None,
+            })
+            .collect())
+    }
+
     /// Submit a shutdown request to an existing agent thread.
     pub(crate) async fn shutdown_agent(&self, agent_id: ThreadId) -> CodexResult<String> {
         let state = self.upgrade()?;
@@ -529,6 +636,18 @@ impl AgentControl {
         Ok(thread.subscribe_status())
     }
 
+    /// Flush `agent_id`'s rollout and return its path while the thread is still loaded.
+    pub(crate) async fn flushed_rollout_path(&self, agent_id: ThreadId) -> Option<PathBuf> {
+        let Ok(state) = self.upgrade() else {
+            return None;
+        };
+        let Ok(thread) = state.get_thread(agent_id).await else {
+            return None;
+        };
+        thread.codex.session.flush_rollout().await;
+        thread.rollout_path()
+    }
+
     pub(crate) async fn get_total_token_usage(&self, agent_id: ThreadId) -> Option<TokenUsage> {
         let Ok(state) = self.upgrade() else {
             return None;
@@ -573,6 +692,29 @@ impl AgentControl {
         agents.join("\n")
     }
 
+    /// Live threads spawned by `parent_thread_id` through `ThreadSpawn`, in no particular order.
+    pub(crate) async fn list_child_thread_ids(&self, parent_thread_id: ThreadId) -> Vec<ThreadId> {
+        let Ok(state) = self.upgrade() else {
+            return Vec::new();
+        };
+
+        let mut children = Vec::new();
+        for thread_id in state.list_thread_ids().await {
+            let Ok(thread) = state.get_thread(thread_id).await else {
+                continue;
+            };
+            if let SessionSource::SubAgent(SubAgentSource::ThreadSpawn {
+                parent_thread_id: agent_parent_thread_id,
+                ..
+            }) = thread.config_snapshot().await.session_source
+                && agent_parent_thread_id == parent_thread_id
+            {
+                children.push(thread_id);
+            }
+        }
+        children
+    }
+
     /// Starts a detached watcher for sub-agents spawned from another thread.
     ///
     /// This is only enabled for `SubAgentSource::ThreadSpawn`, where a parent thread exists and
@@ -1062,9 +1204,11 @@ mod tests {
                     depth: 1,
                     agent_nickname: None,
                     agent_role: None,
+                    call_id: None,
                 })),
                 SpawnAgentOptions {
                     fork_parent_spawn_call_id: Some(parent_spawn_call_id),
+                    seed_history: None,
                 },
             )
             .await
@@ -1144,9 +1288,11 @@ mod tests {
                     depth: 1,
                     agent_nickname: None,
                     agent_role: None,
+                    call_id: None,
                 })),
                 SpawnAgentOptions {
                     fork_parent_spawn_call_id: Some(parent_spawn_call_id.clone()),
+                    seed_history: None,
                 },
             )
             .await
@@ -1213,9 +1359,11 @@ mod tests {
                     depth: 1,
                     agent_nickname: None,
                     agent_role: None,
+                    call_id: None,
                 })),
                 SpawnAgentOptions {
                     fork_parent_spawn_call_id: Some(parent_spawn_call_id.clone()),
+                    seed_history: None,
                 },
             )
             .await
@@ -1466,6 +1614,7 @@ mod tests {
                     depth: 1,
                     agent_nickname: None,
                     agent_role: Some("explorer".to_string()),
+                    call_id: None,
                 })),
             )
             .await
@@ -1497,6 +1646,7 @@ mod tests {
                 depth: 1,
                 agent_nickname: None,
                 agent_role: Some("explorer".to_string()),
+                call_id: None,
             })),
         );
 
@@ -1537,6 +1687,7 @@ mod tests {
                     depth: 1,
                     agent_nickname: None,
                     agent_role: Some("explorer".to_string()),
+                    call_id: None,
                 })),
             )
             .await
@@ -1554,6 +1705,7 @@ mod tests {
             depth,
             agent_nickname,
             agent_role,
+            ..
         }) = snapshot.session_source
         else {
             panic!("expected thread-spawn sub-agent source");
@@ -1587,6 +1739,7 @@ mod tests {
                     depth: 1,
                     agent_nickname: None,
                     agent_role: Some("researcher".to_string()),
+                    call_id: None,
                 })),
             )
             .await
@@ -1638,6 +1791,7 @@ mod tests {
                     depth: 1,
                     agent_nickname: None,
                     agent_role: Some("explorer".to_string()),
+                    call_id: None,
                 })),
             )
             .await
@@ -1706,6 +1860,7 @@ mod tests {
                     depth: 1,
                     agent_nickname: None,
                     agent_role: None,
+                    call_id: None,
                 }),
             )
             .await
@@ -1724,6 +1879,7 @@ mod tests {
             depth: resumed_depth,
             agent_nickname: resumed_nickname,
             agent_role: resumed_role,
+            ..
         }) = resumed_snapshot.session_source
         else {
             panic!("expected thread-spawn sub-agent source");
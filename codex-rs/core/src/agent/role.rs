@@ -13,10 +13,12 @@ use crate::config::deserialize_config_toml_with_base;
 use crate::config_loader::ConfigLayerEntry;
 use crate::config_loader::ConfigLayerStack;
 use crate::config_loader::ConfigLayerStackOrdering;
+use crate::config_loader::merge_toml_values;
 use crate::config_loader::resolve_relative_paths_in_config_toml;
 use codex_app_server_protocol::ConfigLayerSource;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::LazyLock;
 use toml::Value as TomlValue;
@@ -24,6 +26,8 @@ use toml::Value as TomlValue;
 /// The role name used when a caller omits `agent_type`.
 pub const DEFAULT_ROLE_NAME: &str = "default";
 const AGENT_TYPE_UNAVAILABLE_ERROR: &str = "agent type is currently not available";
+/// Role-file key naming a parent role file whose settings the role builds on.
+const ROLE_EXTENDS_KEY: &str = "extends";
 
 /// Applies a named role layer to `config` while preserving caller-owned model selection.
 ///
@@ -46,30 +50,15 @@ pub(crate) async fn apply_role_to_config(
         return Ok(());
     };
 
-    let (role_config_contents, role_config_base) = if is_built_in {
-        (
-            built_in::config_file_contents(config_file)
-                .map(str::to_owned)
-                .ok_or_else(|| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())?,
-            config.codex_home.as_path(),
-        )
+    let role_layer_toml = if is_built_in {
+        let role_config_contents = built_in::config_file_contents(config_file)
+            .ok_or_else(|| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())?;
+        let role_config_toml: TomlValue = toml::from_str(role_config_contents)
+            .map_err(|_| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())?;
+        role_layer_from_toml(role_config_toml, config.codex_home.as_path())?
     } else {
-        (
-            tokio::fs::read_to_string(config_file)
-                .await
-                .map_err(|_| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())?,
-            config_file
-                .parent()
-                .ok_or_else(|| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())?,
-        )
+        load_user_role_layer(config_file).await?
     };
-
-    let role_config_toml: TomlValue = toml::from_str(&role_config_contents)
-        .map_err(|_| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())?;
-    deserialize_config_toml_with_base(role_config_toml.clone(), role_config_base)
-        .map_err(|_| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())?;
-    let role_layer_toml = resolve_relative_paths_in_config_toml(role_config_toml, role_config_base)
-        .map_err(|_| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())?;
     let role_selects_provider = role_layer_toml.get("model_provider").is_some();
     let role_selects_profile = role_layer_toml.get("profile").is_some();
     let role_updates_active_profile_provider = config
@@ -133,6 +122,75 @@ pub(crate) async fn apply_role_to_config(
     Ok(())
 }
 
+/// Loads a user-defined role file together with the chain of role files it `extends`.
+///
+/// Parents are merged first so the extending role's keys win. An `extends` path is resolved
+/// against the directory of the file that names it (normally `agents/`), and each file's own
+/// relative paths against its own directory.
+async fn load_user_role_layer(config_file: &Path) -> Result<TomlValue, String> {
+    let mut chain = Vec::new();
+    let mut visited = HashSet::new();
+    let mut next_file = Some(config_file.to_path_buf());
+    while let Some(role_file) = next_file.take() {
+        let is_parent = !chain.is_empty();
+        let unreadable = |_| {
+            if is_parent {
+                format!(
+                    "agent role parent `{}` could not be loaded",
+                    role_file.display()
+                )
+            } else {
+                AGENT_TYPE_UNAVAILABLE_ERROR.to_string()
+            }
+        };
+        let canonical = tokio::fs::canonicalize(&role_file)
+            .await
+            .map_err(unreadable)?;
+        if !visited.insert(canonical) {
+            return Err(format!(
+                "agent role `extends` cycle detected at `{}`",
+                role_file.display()
+            ));
+        }
+        let contents = tokio::fs::read_to_string(&role_file)
+            .await
+            .map_err(unreadable)?;
+        let mut role_config_toml: TomlValue =
+            toml::from_str(&contents).map_err(|_| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())?;
+        let role_config_base = role_file
+            .parent()
+            .ok_or_else(|| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())?;
+        next_file = match role_config_toml
+            .as_table_mut()
+            .and_then(|table| table.remove(ROLE_EXTENDS_KEY))
+        {
+            None => None,
+            Some(TomlValue::String(parent)) => Some(role_config_base.join(parent)),
+            Some(_) => {
+                return Err(format!(
+                    "`{ROLE_EXTENDS_KEY}` in agent role `{}` must be a file path",
+                    role_file.display()
+                ));
+            }
+        };
+        chain.push(role_layer_from_toml(role_config_toml, role_config_base)?);
+    }
+
+    let mut merged = TomlValue::Table(toml::map::Map::new());
+    for layer in chain.iter().rev() {
+        merge_toml_values(&mut merged, layer);
+    }
+    Ok(merged)
+}
+
+/// Validates one role file's settings and rebases its relative paths onto `base`.
+fn role_layer_from_toml(role_config_toml: TomlValue, base: &Path) -> Result<TomlValue, String> {
+    deserialize_config_toml_with_base(role_config_toml.clone(), base)
+        .map_err(|_| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())?;
+    resolve_relative_paths_in_config_toml(role_config_toml, base)
+        .map_err(|_| AGENT_TYPE_UNAVAILABLE_ERROR.to_string())
+}
+
 pub(crate) fn resolve_role_config<'a>(
     config: &'a Config,
     role_name: &str,
@@ -447,6 +505,98 @@ mod tests {
         assert_eq!(err, AGENT_TYPE_UNAVAILABLE_ERROR);
     }
 
+    #[tokio::test]
+    async fn apply_role_merges_extended_parent_role_first() {
+        let (home, mut config) = test_config_with_cli_overrides(Vec::new()).await;
+        write_role_config(
+            &home,
+            "base.toml",
+            "model = \"base-model\"\nmodel_reasoning_effort = \"low\"\n",
+        )
+        .await;
+        let role_path = write_role_config(
+            &home,
+            "reviewer.toml",
+            "extends = \"base.toml\"\nmodel_reasoning_effort = \"high\"\n",
+        )
+        .await;
+        config.agent_roles.insert(
+            "reviewer".to_string(),
+            AgentRoleConfig {
+                description: None,
+                config_file: Some(role_path),
+                nickname_candidates: None,
+            },
+        );
+
+        apply_role_to_config(&mut config, Some("reviewer"))
+            .await
+            .expect("extending role should apply");
+
+        assert_eq!(config.model.as_deref(), Some("base-model"));
+        assert_eq!(config.model_reasoning_effort, Some(ReasoningEffort::High));
+    }
+
+    #[tokio::test]
+    async fn apply_role_rejects_extends_cycles() {
+        let (home, mut config) = test_config_with_cli_overrides(Vec::new()).await;
+        write_role_config(&home, "a.toml", "extends = \"b.toml\"\n").await;
+        let role_path = write_role_config(&home, "b.toml", "extends = \"a.toml\"\n").await;
+        config.agent_roles.insert(
+            "looping".to_string(),
+            AgentRoleConfig {
+                description: None,
+                config_file: Some(role_path),
+                nickname_candidates: None,
+            },
+        );
+
+        let err = apply_role_to_config(&mut config, Some("looping"))
+            .await
+            .expect_err("extends cycle should fail");
+
+        assert_eq!(
+            err,
+            format!(
+                "agent role `extends` cycle detected at `{}`",
+                home.path().join("b.toml").display()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn apply_role_sets_tool_allow_and_deny_lists() {
+        let (home, mut config) = test_config_with_cli_overrides(Vec::new()).await;
+        let role_path = write_role_config(
+            &home,
+            "read-only-role.toml",
+            "allowed_tools = [\"shell\", \"read_file\", \"apply_patch\"]\ndenied_tools = [\"apply_patch\"]\n",
+        )
+        .await;
+        config.agent_roles.insert(
+            "reader".to_string(),
+            AgentRoleConfig {
+                description: None,
+                config_file: Some(role_path),
+                nickname_candidates: None,
+            },
+        );
+
+        apply_role_to_config(&mut config, Some("reader"))
+            .await
+            .expect("custom role should apply");
+
+        assert_eq!(
+            config.allowed_tools,
+            Some(vec![
+                "shell".to_string(),
+                "read_file".to_string(),
+                "apply_patch".to_string(),
+            ])
+        );
+        assert_eq!(config.denied_tools, vec!["apply_patch".to_string()]);
+    }
+
     #[tokio::test]
     async fn apply_role_preserves_unspecified_keys() {
         let (home, mut config) = test_config_with_cli_overrides(vec![(
@@ -17,3 +17,9 @@ pub(crate) fn agent_status_from_event(msg: &EventMsg) -> Option<AgentStatus> {
 pub(crate) fn is_final(status: &AgentStatus) -> bool {
     !matches!(status, AgentStatus::PendingInit | AgentStatus::Running)
 }
+
+/// Statuses that `wait` in `first_error` mode treats as a failure. Only `Errored` counts:
+/// `Shutdown` is a deliberate close and `NotFound` is reported separately.
+pub(crate) fn is_failure(status: &AgentStatus) -> bool {
+    matches!(status, AgentStatus::Errored(_))
+}
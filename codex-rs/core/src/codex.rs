@@ -67,6 +67,7 @@ use codex_artifact_presentation::PresentationArtifactResponse;
 use codex_artifact_spreadsheet::SpreadsheetArtifactError;
 use codex_artifact_spreadsheet::SpreadsheetArtifactRequest;
 use codex_artifact_spreadsheet::SpreadsheetArtifactResponse;
+use codex_hooks::CallbackHook;
 use codex_hooks::CommandHooksConfig;
 use codex_hooks::HookEvent;
 use codex_hooks::HookPayload;
@@ -392,6 +393,7 @@ impl Codex {
         persist_extended_history: bool,
         metrics_service_name: Option<String>,
         inherited_shell_snapshot: Option<Arc<ShellSnapshot>>,
+        callback_hooks: Vec<CallbackHook>,
     ) -> CodexResult<CodexSpawnOk> {
         let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
         let (tx_event, rx_event) = async_channel::unbounded();
@@ -556,6 +558,7 @@ impl Codex {
             mcp_manager.clone(),
             file_watcher,
             agent_control,
+            callback_hooks,
         )
         .instrument(session_init_span)
         .await
@@ -784,7 +787,8 @@ impl TurnContext {
         })
         .with_web_search_config(self.tools_config.web_search_config.clone())
         .with_allow_login_shell(self.tools_config.allow_login_shell)
-        .with_agent_roles(config.agent_roles.clone());
+        .with_agent_roles(config.agent_roles.clone())
+        .with_tool_filter(config.allowed_tools.clone(), config.denied_tools.clone());
 
         Self {
             sub_id: self.sub_id.clone(),
@@ -1272,7 +1276,11 @@ impl Session {
         })
         .with_web_search_config(per_turn_config.web_search_config.clone())
         .with_allow_login_shell(per_turn_config.permissions.allow_login_shell)
-        .with_agent_roles(per_turn_config.agent_roles.clone());
+        .with_agent_roles(per_turn_config.agent_roles.clone())
+        .with_tool_filter(
+            per_turn_config.allowed_tools.clone(),
+            per_turn_config.denied_tools.clone(),
+        );
 
         let cwd = session_configuration.cwd.clone();
         let turn_metadata_state = Arc::new(TurnMetadataState::new(
@@ -1344,6 +1352,7 @@ impl Session {
         mcp_manager: Arc<McpManager>,
         file_watcher: Arc<FileWatcher>,
         agent_control: AgentControl,
+        callback_hooks: Vec<CallbackHook>,
     ) -> anyhow::Result<Arc<Self>> {
         debug!(
             "Configuring session: model={}; provider={:?}",
@@ -1683,6 +1692,9 @@ impl Session {
             config: Arc::clone(&config),
             default_model: session_configuration.collaboration_mode.model().to_string(),
         }));
+        for hook in callback_hooks {
+            hooks.add_callback_hook(hook);
+        }
 
         let services = SessionServices {
             // Initialize the MCP connection manager with an uninitialized
@@ -2119,6 +2131,22 @@ impl Session {
                         .await;
                 }
 
+                // Remind a resumed lead of the agents it spawned before the restart. Kept out of
+                // the rollout so repeated resumes do not stack copies of the note.
+                if turn_context.tools_config.collab_tools
+                    && !is_subagent
+                    && let Some(note) =
+                        crate::tools::handlers::multi_agents::spawned_agents_resume_note(
+                            turn_context.config.codex_home.as_path(),
+                            self.conversation_id,
+                        )
+                        .await
+                {
+                    let message: ResponseItem = DeveloperInstructions::new(note).into();
+                    self.record_into_history(std::slice::from_ref(&message), &turn_context)
+                        .await;
+                }
+
                 // Seed usage info from the recorded rollout so UIs can show token counts
                 // immediately on resume/fork.
                 if let Some(info) = Self::last_token_info_from_rollout(&rollout_items) {
@@ -2295,6 +2323,22 @@ impl Session {
         );
     }
 
+    /// Adds `env` to the `set` map of the session's shell environment policy so that
+    /// subsequent turns export it to shell commands. Keys in `env` replace existing values.
+    pub(crate) async fn merge_shell_environment(&self, env: HashMap<String, String>) {
+        if env.is_empty() {
+            return;
+        }
+        let mut state = self.state.lock().await;
+        let mut config = (*state.session_configuration.original_config_do_not_use).clone();
+        config
+            .permissions
+            .shell_environment_policy
+            .r#set
+            .extend(env);
+        state.session_configuration.original_config_do_not_use = Arc::new(config);
+    }
+
     pub(crate) async fn update_settings(
         &self,
         updates: SessionSettingsUpdate,
@@ -4279,11 +4323,13 @@ impl Session {
         })
     }
 
+    /// Runs `pre_compact` hooks and returns the first blocking reason, if any. Only automatic
+    /// compaction honors a block; callers decide what to do with it.
     pub(crate) async fn dispatch_pre_compact_hook(
         &self,
         turn_context: &TurnContext,
         trigger: &str,
-    ) {
+    ) -> Option<String> {
         let hook_outcomes = self
             .hooks()
             .dispatch(HookPayload {
@@ -4299,6 +4345,7 @@ impl Session {
             .await;
 
         let mut additional_context = Vec::new();
+        let mut blocked = None;
         for hook_outcome in hook_outcomes {
             let hook_name = hook_outcome.hook_name;
             let result = hook_outcome.result;
@@ -4310,12 +4357,51 @@ impl Session {
                     "pre_compact hook failed; continuing"
                 );
             }
-            if let HookResultControl::Block { reason } = result.control {
+            if let HookResultControl::Block { reason } = result.control
+                && blocked.is_none()
+            {
+                blocked = Some(format!("pre_compact hook '{hook_name}' blocked: {reason}"));
+            }
+            additional_context.extend(result.additional_context);
+        }
+
+        self.record_hook_context(turn_context, &additional_context)
+            .await;
+        blocked
+    }
+
+    pub(crate) async fn dispatch_compact_hook(
+        &self,
+        turn_context: &TurnContext,
+        trigger: &str,
+        before_tokens: i64,
+        after_tokens: i64,
+    ) {
+        let hook_outcomes = self
+            .hooks()
+            .dispatch(HookPayload {
+                session_id: self.conversation_id,
+                transcript_path: self.transcript_path().await,
+                cwd: turn_context.cwd.clone(),
+                permission_mode: turn_context.approval_policy.value().to_string(),
+                hook_event: HookEvent::Compact {
+                    trigger: trigger.to_string(),
+                    before_tokens,
+                    after_tokens,
+                },
+            })
+            .await;
+
+        let mut additional_context = Vec::new();
+        for hook_outcome in hook_outcomes {
+            let hook_name = hook_outcome.hook_name;
+            let result = hook_outcome.result;
+            if let Some(error) = result.error.as_deref() {
                 warn!(
                     turn_id = %turn_context.sub_id,
                     hook_name = %hook_name,
-                    reason,
-                    "pre_compact hook returned a blocking decision; ignoring"
+                    error,
+                    "compact hook failed; continuing"
                 );
             }
             additional_context.extend(result.additional_context);
@@ -5271,8 +5357,16 @@ mod handlers {
     pub async fn compact(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
 
-        sess.dispatch_pre_compact_hook(turn_context.as_ref(), "manual_compact")
-            .await;
+        if let Some(reason) = sess
+            .dispatch_pre_compact_hook(turn_context.as_ref(), "manual_compact")
+            .await
+        {
+            warn!(
+                turn_id = %turn_context.sub_id,
+                reason,
+                "pre_compact hook cannot block manual compaction; ignoring"
+            );
+        }
         sess.spawn_task(
             Arc::clone(&turn_context),
             vec![UserInput::Text {
@@ -5645,7 +5739,8 @@ async fn spawn_review_thread(
     })
     .with_web_search_config(None)
     .with_allow_login_shell(config.permissions.allow_login_shell)
-    .with_agent_roles(config.agent_roles.clone());
+    .with_agent_roles(config.agent_roles.clone())
+    .with_tool_filter(config.allowed_tools.clone(), config.denied_tools.clone());
 
     let review_prompt = resolved.prompt.clone();
     let provider = parent_turn_context.provider.clone();
@@ -5875,13 +5970,15 @@ pub(crate) async fn run_turn(
     // new user message are recorded. Estimate pending incoming items (context
     // diffs/full reinjection + user input) and trigger compaction preemptively
     // when they would push the thread over the compaction threshold.
-    if run_pre_sampling_compact(&sess, &turn_context)
-        .await
-        .is_err()
-    {
-        error!("Failed to run pre-sampling compact");
-        return None;
-    }
+    // A `pre_compact` veto holds for the rest of the turn, so the hook is not asked again on
+    // every sampling round that still exceeds the limit.
+    let mut auto_compact_vetoed = match run_pre_sampling_compact(&sess, &turn_context).await {
+        Ok(outcome) => outcome == Some(AutoCompactOutcome::Vetoed),
+        Err(_) => {
+            error!("Failed to run pre-sampling compact");
+            return None;
+        }
+    };
 
     let skills_outcome = Some(turn_context.turn_skills.outcome.as_ref());
 
@@ -6146,16 +6243,16 @@ pub(crate) async fn run_turn(
                 );
 
                 // as long as compaction works well in getting us way below the token limit, we shouldn't worry about being in an infinite loop.
-                if token_limit_reached && needs_follow_up {
-                    if run_auto_compact(
+                if token_limit_reached && needs_follow_up && !auto_compact_vetoed {
+                    match run_auto_compact(
                         &sess,
                         &turn_context,
                         InitialContextInjection::BeforeLastUserMessage,
                     )
                     .await
-                    .is_err()
                     {
-                        return None;
+                        Ok(outcome) => auto_compact_vetoed = outcome == AutoCompactOutcome::Vetoed,
+                        Err(_) => return None,
                     }
                     continue;
                 }
@@ -6268,16 +6365,19 @@ pub(crate) async fn run_turn(
                         }
                         sess.record_hook_context(&turn_context, &additional_context)
                             .await;
-                        if token_limit_reached
-                            && run_auto_compact(
+                        if token_limit_reached && !auto_compact_vetoed {
+                            match run_auto_compact(
                                 &sess,
                                 &turn_context,
                                 InitialContextInjection::BeforeLastUserMessage,
                             )
                             .await
-                            .is_err()
-                        {
-                            return None;
+                            {
+                                Ok(outcome) => {
+                                    auto_compact_vetoed = outcome == AutoCompactOutcome::Vetoed;
+                                }
+                                Err(_) => return None,
+                            }
                         }
                         continue;
                     }
@@ -6320,17 +6420,22 @@ pub(crate) async fn run_turn(
     last_agent_message
 }
 
+/// Returns the outcome of the last compaction attempted before sampling, or `None` when none was
+/// needed.
 async fn run_pre_sampling_compact(
     sess: &Arc<Session>,
     turn_context: &Arc<TurnContext>,
-) -> CodexResult<()> {
+) -> CodexResult<Option<AutoCompactOutcome>> {
     let total_usage_tokens_before_compaction = sess.get_total_token_usage().await;
-    maybe_run_previous_model_inline_compact(
+    let previous_model_outcome = maybe_run_previous_model_inline_compact(
         sess,
         turn_context,
         total_usage_tokens_before_compaction,
     )
     .await?;
+    if previous_model_outcome == Some(AutoCompactOutcome::Vetoed) {
+        return Ok(previous_model_outcome);
+    }
     let total_usage_tokens = sess.get_total_token_usage().await;
     let auto_compact_limit = turn_context
         .model_info
@@ -6338,24 +6443,26 @@ async fn run_pre_sampling_compact(
         .unwrap_or(i64::MAX);
     // Compact if the total usage tokens are greater than the auto compact limit
     if total_usage_tokens >= auto_compact_limit {
-        run_auto_compact(sess, turn_context, InitialContextInjection::DoNotInject).await?;
+        let outcome =
+            run_auto_compact(sess, turn_context, InitialContextInjection::DoNotInject).await?;
+        return Ok(Some(outcome));
     }
-    Ok(())
+    Ok(previous_model_outcome)
 }
 
 /// Runs pre-sampling compaction against the previous model when switching to a smaller
 /// context-window model.
 ///
-/// Returns `Ok(true)` when compaction ran successfully, `Ok(false)` when compaction was skipped
-/// because the model/context-window preconditions were not met, and `Err(_)` only when compaction
-/// was attempted and failed.
+/// Returns `Ok(Some(_))` with the outcome when compaction was attempted, `Ok(None)` when it was
+/// skipped because the model/context-window preconditions were not met, and `Err(_)` only when
+/// compaction was attempted and failed.
 async fn maybe_run_previous_model_inline_compact(
     sess: &Arc<Session>,
     turn_context: &Arc<TurnContext>,
     total_usage_tokens: i64,
-) -> CodexResult<bool> {
+) -> CodexResult<Option<AutoCompactOutcome>> {
     let Some(previous_turn_settings) = sess.previous_turn_settings().await else {
-        return Ok(false);
+        return Ok(None);
     };
     let previous_model_turn_context = Arc::new(
         turn_context
@@ -6364,10 +6471,10 @@ async fn maybe_run_previous_model_inline_compact(
     );
 
     let Some(old_context_window) = previous_model_turn_context.model_context_window() else {
-        return Ok(false);
+        return Ok(None);
     };
     let Some(new_context_window) = turn_context.model_context_window() else {
-        return Ok(false);
+        return Ok(None);
     };
     let new_auto_compact_limit = turn_context
         .model_info
@@ -6377,22 +6484,50 @@ async fn maybe_run_previous_model_inline_compact(
         && previous_model_turn_context.model_info.slug != turn_context.model_info.slug
         && old_context_window > new_context_window;
     if should_run {
-        run_auto_compact(
+        let outcome = run_auto_compact(
             sess,
             &previous_model_turn_context,
             InitialContextInjection::DoNotInject,
         )
         .await?;
-        return Ok(true);
+        return Ok(Some(outcome));
     }
-    Ok(false)
+    Ok(None)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AutoCompactOutcome {
+    Compacted,
+    /// A blocking `pre_compact` hook vetoed the compaction; history was left untouched.
+    Vetoed,
+}
+
+/// Compacts history once the context limit is reached. A blocking `pre_compact` hook vetoes the
+/// compaction, in which case the turn continues with the full history.
 async fn run_auto_compact(
     sess: &Arc<Session>,
     turn_context: &Arc<TurnContext>,
     initial_context_injection: InitialContextInjection,
-) -> CodexResult<()> {
+) -> CodexResult<AutoCompactOutcome> {
+    let before_tokens = sess.get_total_token_usage().await;
+    if let Some(reason) = sess
+        .dispatch_pre_compact_hook(turn_context.as_ref(), "auto_compact")
+        .await
+    {
+        warn!(
+            turn_id = %turn_context.sub_id,
+            reason,
+            "skipping automatic compaction"
+        );
+        sess.send_event(
+            turn_context.as_ref(),
+            EventMsg::Warning(WarningEvent {
+                message: format!("Automatic compaction skipped: {reason}"),
+            }),
+        )
+        .await;
+        return Ok(AutoCompactOutcome::Vetoed);
+    }
     if should_use_remote_compact_task(&turn_context.provider) {
         run_inline_remote_auto_compact_task(
             Arc::clone(sess),
@@ -6408,7 +6543,15 @@ async fn run_auto_compact(
         )
         .await?;
     }
-    Ok(())
+    let after_tokens = sess.get_total_token_usage().await;
+    sess.dispatch_compact_hook(
+        turn_context.as_ref(),
+        "auto_compact",
+        before_tokens,
+        after_tokens,
+    )
+    .await;
+    Ok(AutoCompactOutcome::Compacted)
 }
 
 fn collect_explicit_app_ids_from_skill_items(
@@ -9439,6 +9582,7 @@ mod tests {
             mcp_manager,
             Arc::new(FileWatcher::noop()),
             AgentControl::default(),
+            Vec::new(),
         )
         .await;
 
@@ -11283,4 +11427,92 @@ mod tests {
 
         pretty_assertions::assert_eq!(output, expected);
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn pre_compact_block_vetoes_auto_compact() {
+        let (mut session, turn_context) = make_session_and_context().await;
+        let (tx_event, rx_event) = async_channel::unbounded();
+        session.tx_event = tx_event;
+        session.services.hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                pre_compact: vec![codex_hooks::CommandHookConfig {
+                    name: Some("archive".to_string()),
+                    command: vec![
+                        "sh".to_string(),
+                        "-c".to_string(),
+                        "echo 'archive first' >&2; exit 2".to_string(),
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        });
+        let session = Arc::new(session);
+        let turn_context = Arc::new(turn_context);
+        let history_before = session.clone_history().await.raw_items().to_vec();
+
+        let outcome = run_auto_compact(
+            &session,
+            &turn_context,
+            InitialContextInjection::DoNotInject,
+        )
+        .await
+        .expect("a vetoed compaction is not an error");
+
+        assert_eq!(outcome, AutoCompactOutcome::Vetoed);
+        assert_eq!(
+            session.clone_history().await.raw_items().to_vec(),
+            history_before
+        );
+        let warnings = std::iter::from_fn(|| rx_event.try_recv().ok())
+            .filter_map(|event| match event.msg {
+                EventMsg::Warning(WarningEvent { message }) => Some(message),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            vec![
+                "Automatic compaction skipped: pre_compact hook 'archive' blocked: archive first"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn dispatch_compact_hook_reports_trigger_and_token_counts() {
+        let (mut session, turn_context) = make_session_and_context().await;
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let payload_path = dir.path().join("compact.json");
+        session.services.hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                compact: vec![codex_hooks::CommandHookConfig {
+                    command: vec![
+                        "sh".to_string(),
+                        "-c".to_string(),
+                        "cat > \"$1\"".to_string(),
+                        "sh".to_string(),
+                        payload_path.display().to_string(),
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        });
+
+        session
+            .dispatch_compact_hook(&turn_context, "auto_compact", 120_000, 8_000)
+            .await;
+
+        let payload: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(&payload_path).expect("compact hook should have run"),
+        )
+        .expect("hook payload should be json");
+        assert_eq!(payload["hook_event_name"], "Compact");
+        assert_eq!(payload["trigger"], "auto_compact");
+        assert_eq!(payload["before_tokens"], 120_000);
+        assert_eq!(payload["after_tokens"], 8_000);
+    }
 }
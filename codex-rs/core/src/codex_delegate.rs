@@ -70,6 +70,7 @@ pub(crate) async fn run_codex_thread_interactive(
         false,
         None,
         None,
+        parent_session.services.hooks.callback_hooks().to_vec(),
     )
     .await?;
     let codex = Arc::new(codex);
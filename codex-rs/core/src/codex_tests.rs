@@ -1964,6 +1964,7 @@ async fn session_new_fails_when_zsh_fork_enabled_without_zsh_path() {
         mcp_manager,
         Arc::new(FileWatcher::noop()),
         AgentControl::default(),
+        Vec::new(),
     )
     .await;
 
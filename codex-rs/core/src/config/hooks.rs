@@ -4,7 +4,9 @@ use codex_hooks::CommandHookConfig;
 use codex_hooks::CommandHooksConfig;
 use codex_hooks::HookHandlerType;
 use codex_hooks::HookMatcherConfig;
+use codex_hooks::HookOutputMode;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::io;
 use toml::Value as TomlValue;
 use tracing::warn;
@@ -22,24 +24,57 @@ struct HookMatcherToml {
     tool_name: Option<String>,
     tool_name_regex: Option<String>,
     prompt_regex: Option<String>,
+    command_regex: Option<String>,
+    path_regex: Option<String>,
     matcher: Option<String>,
+    #[serde(flatten)]
+    unknown_keys: BTreeMap<String, TomlValue>,
 }
 
+/// Keys of [`HookMatcherToml`], used to point misplaced matcher keys at the `matcher` table.
+const MATCHER_KEYS: [&str; 6] = [
+    "tool_name",
+    "tool_name_regex",
+    "prompt_regex",
+    "command_regex",
+    "path_regex",
+    "matcher",
+];
+
 #[derive(Deserialize)]
 struct HookEntryToml {
     #[serde(default)]
     name: Option<String>,
-    command: HookCommandToml,
+    #[serde(default)]
+    command: Option<HookCommandToml>,
+    /// When set, the hook POSTs its payload to this URL instead of running a command.
+    #[serde(default)]
+    url: Option<String>,
+    /// Environment variable holding the shared secret sent with webhook requests.
+    #[serde(default)]
+    secret_env_var: Option<String>,
     #[serde(default, rename = "async")]
     async_: bool,
     #[serde(default)]
     timeout: Option<u64>,
     #[serde(default)]
+    timeout_ms: Option<u64>,
+    #[serde(default)]
     status_message: Option<String>,
     #[serde(default)]
     once: bool,
     #[serde(default)]
     matcher: HookMatcherToml,
+    /// `ignore` (default), `lenient` or `strict` interpretation of the hook's stdout.
+    #[serde(default)]
+    output_mode: HookOutputMode,
+    /// Keys this entry does not recognize. Collected rather than rejected so a typo flags only
+    /// this entry instead of failing the whole layer.
+    #[serde(flatten)]
+    unknown_keys: BTreeMap<String, TomlValue>,
+    /// Why this entry is invalid, filled in by [`parse_layer_hooks`].
+    #[serde(skip)]
+    config_error: Option<String>,
 }
 
 #[derive(Deserialize, Default)]
@@ -60,8 +95,38 @@ struct HooksToml {
     subagent_start: Vec<HookEntryToml>,
     subagent_stop: Vec<HookEntryToml>,
     pre_compact: Vec<HookEntryToml>,
+    compact: Vec<HookEntryToml>,
     worktree_create: Vec<HookEntryToml>,
     worktree_remove: Vec<HookEntryToml>,
+    max_concurrency: Option<usize>,
+}
+
+impl HooksToml {
+    /// Every configured entry, paired with the name of the event it is registered for.
+    fn entries_mut(&mut self) -> impl Iterator<Item = (&'static str, &mut HookEntryToml)> {
+        [
+            ("session_start", &mut self.session_start),
+            ("session_end", &mut self.session_end),
+            ("user_prompt_submit", &mut self.user_prompt_submit),
+            ("pre_tool_use", &mut self.pre_tool_use),
+            ("permission_request", &mut self.permission_request),
+            ("notification", &mut self.notification),
+            ("post_tool_use", &mut self.post_tool_use),
+            ("post_tool_use_failure", &mut self.post_tool_use_failure),
+            ("stop", &mut self.stop),
+            ("teammate_idle", &mut self.teammate_idle),
+            ("task_completed", &mut self.task_completed),
+            ("config_change", &mut self.config_change),
+            ("subagent_start", &mut self.subagent_start),
+            ("subagent_stop", &mut self.subagent_stop),
+            ("pre_compact", &mut self.pre_compact),
+            ("compact", &mut self.compact),
+            ("worktree_create", &mut self.worktree_create),
+            ("worktree_remove", &mut self.worktree_remove),
+        ]
+        .into_iter()
+        .flat_map(|(event, entries)| entries.iter_mut().map(move |entry| (event, entry)))
+    }
 }
 
 #[derive(Deserialize, Default)]
@@ -99,16 +164,52 @@ fn parse_layer_hooks(
     config: &TomlValue,
     layer_name: &impl std::fmt::Debug,
 ) -> io::Result<Option<HooksToml>> {
-    let parsed: HooksLayerToml = config.clone().try_into().map_err(|err| {
+    let mut parsed: HooksLayerToml = config.clone().try_into().map_err(|err| {
         io::Error::new(
             io::ErrorKind::InvalidData,
             format!("failed to parse hooks config for {layer_name:?}: {err}"),
         )
     })?;
+    if let Some(hooks) = parsed.hooks.as_mut() {
+        for (event, entry) in hooks.entries_mut() {
+            if let Err(err) = validate_hook_entry(entry) {
+                warn!(
+                    layer = ?layer_name,
+                    event,
+                    hook = entry.name.as_deref().unwrap_or("<unnamed>"),
+                    error = %err,
+                    "invalid hook entry; it will report this error instead of running"
+                );
+                entry.config_error = Some(err);
+            }
+        }
+    }
     Ok(parsed.hooks)
 }
 
+/// Finds entries that would otherwise be silently reinterpreted at dispatch time. Such an entry is
+/// kept, so its siblings still load, but only reports the problem when dispatched.
+fn validate_hook_entry(entry: &HookEntryToml) -> Result<(), String> {
+    if let Some(key) = entry.unknown_keys.keys().next() {
+        return Err(if MATCHER_KEYS.contains(&key.as_str()) {
+            format!("unknown key `{key}`; matcher keys go in the entry's `matcher` table")
+        } else {
+            format!("unknown key `{key}`")
+        });
+    }
+    if let Some(key) = entry.matcher.unknown_keys.keys().next() {
+        return Err(format!("unknown matcher key `{key}`"));
+    }
+    if entry.url.is_some() && entry.command.is_some() {
+        return Err("set either `url` or `command`, not both".to_string());
+    }
+    matcher_from_toml(&entry.matcher).validate()
+}
+
 fn extend_command_hooks(dst: &mut CommandHooksConfig, src: HooksToml) {
+    if src.max_concurrency.is_some() {
+        dst.max_concurrency = src.max_concurrency;
+    }
     dst.session_start
         .extend(src.session_start.into_iter().map(command_hook_from_entry));
     dst.session_end
@@ -148,6 +249,8 @@ fn extend_command_hooks(dst: &mut CommandHooksConfig, src: HooksToml) {
         .extend(src.subagent_stop.into_iter().map(command_hook_from_entry));
     dst.pre_compact
         .extend(src.pre_compact.into_iter().map(command_hook_from_entry));
+    dst.compact
+        .extend(src.compact.into_iter().map(command_hook_from_entry));
     dst.worktree_create
         .extend(src.worktree_create.into_iter().map(command_hook_from_entry));
     dst.worktree_remove
@@ -155,26 +258,48 @@ fn extend_command_hooks(dst: &mut CommandHooksConfig, src: HooksToml) {
 }
 
 fn command_hook_from_entry(entry: HookEntryToml) -> CommandHookConfig {
+    let handler_type = if entry.url.is_some() {
+        HookHandlerType::HttpWebhook
+    } else {
+        HookHandlerType::Command
+    };
+    let secret = entry.secret_env_var.as_deref().and_then(|var| {
+        let secret = std::env::var(var).ok();
+        if secret.is_none() {
+            warn!(
+                var,
+                "hook secret_env_var is not set; sending webhook without a secret"
+            );
+        }
+        secret
+    });
     CommandHookConfig {
         name: entry.name,
-        handler_type: HookHandlerType::Command,
-        command: command_argv(entry.command),
+        handler_type,
+        command: entry.command.map(command_argv).unwrap_or_default(),
+        url: entry.url,
+        secret,
         async_: entry.async_,
         timeout: entry.timeout,
+        timeout_ms: entry.timeout_ms,
         status_message: entry.status_message,
         once: entry.once,
-        matcher: matcher_from_toml(entry.matcher),
+        matcher: matcher_from_toml(&entry.matcher),
+        output_mode: entry.output_mode,
+        config_error: entry.config_error,
         prompt: None,
         model: None,
     }
 }
 
-fn matcher_from_toml(toml: HookMatcherToml) -> HookMatcherConfig {
+fn matcher_from_toml(toml: &HookMatcherToml) -> HookMatcherConfig {
     HookMatcherConfig {
-        tool_name: toml.tool_name,
-        tool_name_regex: toml.tool_name_regex,
-        prompt_regex: toml.prompt_regex,
-        matcher: toml.matcher,
+        tool_name: toml.tool_name.clone(),
+        tool_name_regex: toml.tool_name_regex.clone(),
+        prompt_regex: toml.prompt_regex.clone(),
+        command_regex: toml.command_regex.clone(),
+        path_regex: toml.path_regex.clone(),
+        matcher: toml.matcher.clone(),
     }
 }
 
@@ -236,6 +361,7 @@ tool_name_regex = "^shell$"
 
 [[hooks.stop]]
 command = ["echo", "u-stop"]
+output_mode = "strict"
 "#,
                 ),
                 layer(
@@ -266,6 +392,8 @@ command = "echo p-stop"
             hooks.stop[0].command,
             vec!["echo".to_string(), "u-stop".to_string()]
         );
+        assert_eq!(hooks.stop[0].output_mode, HookOutputMode::Strict);
+        assert_eq!(hooks.stop[1].output_mode, HookOutputMode::Ignore);
 
         #[cfg(windows)]
         assert_eq!(
@@ -326,4 +454,144 @@ command = 123
             vec!["echo".to_string(), "ok".to_string()]
         );
     }
+
+    #[test]
+    fn hook_entry_with_url_and_command_is_flagged_at_load() {
+        let user_file = test_absolute_path("/tmp/codex-user/config.toml");
+        let project_folder = test_absolute_path("/tmp/codex-project/.codex");
+        let stack = ConfigLayerStack::new(
+            vec![
+                layer(
+                    ConfigLayerSource::User { file: user_file },
+                    r#"
+[hooks]
+
+[[hooks.stop]]
+url = "https://hooks.example.com/stop"
+"#,
+                ),
+                layer(
+                    ConfigLayerSource::Project {
+                        dot_codex_folder: project_folder,
+                    },
+                    r#"
+[hooks]
+
+[[hooks.stop]]
+name = "ambiguous"
+url = "https://hooks.example.com/stop"
+command = ["echo", "ambiguous"]
+
+[[hooks.stop]]
+name = "sibling"
+command = ["echo", "sibling"]
+
+[[hooks.pre_tool_use]]
+name = "policy"
+command = ["echo", "policy"]
+"#,
+                ),
+            ],
+            ConfigRequirements::default(),
+            ConfigRequirementsToml::default(),
+        )
+        .expect("layer stack");
+
+        let hooks = command_hooks_from_layer_stack(&stack).expect("hooks config");
+        assert_eq!(hooks.stop.len(), 3);
+        assert_eq!(hooks.stop[0].handler_type, HookHandlerType::HttpWebhook);
+        assert_eq!(hooks.stop[0].config_error, None);
+        assert_eq!(hooks.stop[1].name.as_deref(), Some("ambiguous"));
+        assert_eq!(
+            hooks.stop[1].config_error.as_deref(),
+            Some("set either `url` or `command`, not both")
+        );
+        assert_eq!(hooks.stop[2].name.as_deref(), Some("sibling"));
+        assert_eq!(hooks.stop[2].config_error, None);
+        assert_eq!(hooks.pre_tool_use.len(), 1);
+        assert_eq!(hooks.pre_tool_use[0].config_error, None);
+    }
+
+    #[test]
+    fn hook_entry_with_invalid_regex_is_flagged_at_load() {
+        let hooks = parse_layer_hooks(
+            &toml::from_str::<TomlValue>(
+                r#"
+[[hooks.pre_tool_use]]
+name = "broken"
+command = "echo blocked"
+
+[hooks.pre_tool_use.matcher]
+command_regex = "("
+
+[[hooks.pre_tool_use]]
+name = "policy"
+command = "echo policy"
+
+[hooks.pre_tool_use.matcher]
+command_regex = "^git push"
+"#,
+            )
+            .expect("parse toml"),
+            &"project",
+        )
+        .expect("layer still loads")
+        .expect("hooks table");
+
+        assert_eq!(hooks.pre_tool_use.len(), 2);
+        let error = hooks.pre_tool_use[0]
+            .config_error
+            .as_deref()
+            .expect("invalid command_regex should be flagged");
+        assert!(
+            error.starts_with("invalid command_regex:"),
+            "unexpected error: {error}"
+        );
+        assert_eq!(hooks.pre_tool_use[1].config_error, None);
+    }
+
+    #[test]
+    fn hook_entry_with_unknown_keys_is_flagged_at_load() {
+        let hooks = parse_layer_hooks(
+            &toml::from_str::<TomlValue>(
+                r#"
+[[hooks.pre_tool_use]]
+command = "echo misplaced"
+command_regex = "^rm"
+
+[[hooks.pre_tool_use]]
+command = "echo typo"
+timout = 5
+
+[[hooks.pre_tool_use]]
+command = "echo matcher typo"
+
+[hooks.pre_tool_use.matcher]
+tool_nam = "shell"
+
+[[hooks.pre_tool_use]]
+command = "echo ok"
+"#,
+            )
+            .expect("parse toml"),
+            &"project",
+        )
+        .expect("layer still loads")
+        .expect("hooks table");
+
+        let errors: Vec<Option<&str>> = hooks
+            .pre_tool_use
+            .iter()
+            .map(|entry| entry.config_error.as_deref())
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                Some("unknown key `command_regex`; matcher keys go in the entry's `matcher` table"),
+                Some("unknown key `timout`"),
+                Some("unknown matcher key `tool_nam`"),
+                None,
+            ]
+        );
+    }
 }
@@ -134,6 +134,7 @@ pub(crate) const PROJECT_DOC_MAX_BYTES: usize = 32 * 1024; // 32 KiB
 pub(crate) const DEFAULT_AGENT_MAX_THREADS: Option<usize> = Some(6);
 pub(crate) const DEFAULT_AGENT_MAX_DEPTH: i32 = 1;
 pub(crate) const DEFAULT_AGENT_JOB_MAX_RUNTIME_SECONDS: Option<u64> = None;
+pub(crate) const DEFAULT_AGENT_INPUT_PREVIEW_MAX_CHARS: usize = 2_000;
 
 pub const CONFIG_TOML_FILE: &str = "config.toml";
 
@@ -373,6 +374,11 @@ pub struct Config {
     pub agent_max_threads: Option<usize>,
     /// Maximum runtime in seconds for agent job workers before they are failed.
     pub agent_job_max_runtime_seconds: Option<u64>,
+    /// When set, closing an agent first interrupts it and waits up to this many milliseconds for
+    /// a final status before shutting it down.
+    pub agent_shutdown_grace_ms: Option<u64>,
+    /// Maximum number of characters of an agent input echoed into collab events as its preview.
+    pub agent_input_preview_max_chars: usize,
 
     /// Maximum nesting depth allowed for spawned agent threads.
     pub agent_max_depth: i32,
@@ -425,6 +431,14 @@ pub struct Config {
     /// Optional absolute path to patched zsh used by zsh-exec-bridge-backed shell execution.
     pub zsh_path: Option<PathBuf>,
 
+    /// Optional path to the git executable used to manage agent worktrees. `CODEX_GIT_PATH`
+    /// takes precedence; falls back to `git` on `PATH`.
+    pub git_path: Option<PathBuf>,
+
+    /// Directory under which agent worktrees are created (namespaced by conversation id).
+    /// Defaults to `<codex_home>/worktrees`.
+    pub worktree_root: Option<PathBuf>,
+
     /// Value to use for `reasoning.effort` when making a request using the
     /// Responses API.
     pub model_reasoning_effort: Option<ReasoningEffort>,
@@ -526,6 +540,12 @@ pub struct Config {
     /// When `true`, disables scheduled-task tools and the `/loop` command.
     pub disable_cron: bool,
 
+    /// When set, only these tools are exposed to the model.
+    pub allowed_tools: Option<Vec<String>>,
+
+    /// Tools hidden from the model even if `allowed_tools` would expose them.
+    pub denied_tools: Vec<String>,
+
     /// When true, disables burst-paste detection for typed input entirely.
     /// All characters are inserted as they are received, and no buffering
     /// or placeholder replacement will occur for fast keypress bursts.
@@ -1170,6 +1190,13 @@ pub struct ConfigToml {
     /// Optional absolute path to patched zsh used by zsh-exec-bridge-backed shell execution.
     pub zsh_path: Option<AbsolutePathBuf>,
 
+    /// Optional absolute path to the git executable used to manage agent worktrees.
+    pub git_path: Option<AbsolutePathBuf>,
+
+    /// Optional absolute directory for agent worktrees, used instead of
+    /// `<codex_home>/worktrees`.
+    pub worktree_root: Option<AbsolutePathBuf>,
+
     /// Profile to use from the `profiles` map.
     pub profile: Option<String>,
 
@@ -1296,6 +1323,13 @@ pub struct ConfigToml {
     /// When `true`, disables scheduled-task tools and the `/loop` command.
     pub disable_cron: Option<bool>,
 
+    /// Only expose these tools (by name) to the model. Intended for agent role files, e.g. to
+    /// keep an `explorer` from editing files.
+    pub allowed_tools: Option<Vec<String>>,
+
+    /// Hide these tools (by name) from the model. Applied after `allowed_tools`.
+    pub denied_tools: Option<Vec<String>>,
+
     /// When true, disables burst-paste detection for typed input entirely.
     /// All characters are inserted as they are received, and no buffering
     /// or placeholder replacement will occur for fast keypress bursts.
@@ -1417,6 +1451,14 @@ pub struct AgentsToml {
     /// Default maximum runtime in seconds for agent job workers.
     #[schemars(range(min = 1))]
     pub job_max_runtime_seconds: Option<u64>,
+    /// Grace period in milliseconds between interrupting an agent and shutting it down when it
+    /// is closed. When unset, agents are shut down immediately unless a close call asks to drain.
+    #[schemars(range(min = 1))]
+    pub shutdown_grace_ms: Option<u64>,
+    /// Maximum number of characters of an agent input shown as its preview in collab events.
+    /// Longer inputs are still delivered in full. Defaults to 2000.
+    #[schemars(range(min = 1))]
+    pub input_preview_max_chars: Option<usize>,
 
     /// User-defined role declarations keyed by role name.
     ///
@@ -1449,6 +1491,8 @@ pub struct AgentRoleToml {
 
     /// Path to a role-specific config layer.
     /// Relative paths are resolved relative to the `config.toml` that defines them.
+    /// The layer may set `extends = "base.toml"` to build on another role file, resolved
+    /// relative to the extending file.
     pub config_file: Option<AbsolutePathBuf>,
 
     /// Candidate nicknames for agents spawned with this role.
@@ -2148,6 +2192,27 @@ impl Config {
                 "agents.job_max_runtime_seconds must fit within a 64-bit signed integer",
             ));
         }
+        let agent_shutdown_grace_ms = cfg
+            .agents
+            .as_ref()
+            .and_then(|agents| agents.shutdown_grace_ms);
+        if agent_shutdown_grace_ms == Some(0) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "agents.shutdown_grace_ms must be at least 1",
+            ));
+        }
+        let agent_input_preview_max_chars = cfg
+            .agents
+            .as_ref()
+            .and_then(|agents| agents.input_preview_max_chars)
+            .unwrap_or(DEFAULT_AGENT_INPUT_PREVIEW_MAX_CHARS);
+        if agent_input_preview_max_chars == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "agents.input_preview_max_chars must be at least 1",
+            ));
+        }
         let background_terminal_max_timeout = cfg
             .background_terminal_max_timeout
             .unwrap_or(DEFAULT_MAX_BACKGROUND_TERMINAL_TIMEOUT_MS)
@@ -2413,6 +2478,8 @@ impl Config {
             agent_roles,
             memories: cfg.memories.unwrap_or_default().into(),
             agent_job_max_runtime_seconds,
+            agent_shutdown_grace_ms,
+            agent_input_preview_max_chars,
             codex_home,
             sqlite_home,
             log_dir,
@@ -2425,6 +2492,8 @@ impl Config {
             js_repl_node_path,
             js_repl_node_module_dirs,
             zsh_path,
+            git_path: cfg.git_path.map(Into::into),
+            worktree_root: cfg.worktree_root.map(Into::into),
 
             hide_agent_reasoning: cfg.hide_agent_reasoning.unwrap_or(false),
             show_raw_agent_reasoning: cfg
@@ -2475,6 +2544,8 @@ impl Config {
             notices: cfg.notice.unwrap_or_default(),
             check_for_update_on_startup,
             disable_cron,
+            allowed_tools: cfg.allowed_tools,
+            denied_tools: cfg.denied_tools.unwrap_or_default(),
             disable_paste_burst: cfg.disable_paste_burst.unwrap_or(false),
             analytics_enabled: config_profile
                 .analytics
@@ -4998,6 +5069,8 @@ model = "gpt-5.1-codex"
                 max_threads: None,
                 max_depth: None,
                 job_max_runtime_seconds: None,
+                shutdown_grace_ms: None,
+                input_preview_max_chars: None,
                 roles: BTreeMap::from([(
                     "researcher".to_string(),
                     AgentRoleToml {
@@ -5279,6 +5352,8 @@ model_verbosity = "high"
                 agent_roles: BTreeMap::new(),
                 memories: MemoriesConfig::default(),
                 agent_job_max_runtime_seconds: DEFAULT_AGENT_JOB_MAX_RUNTIME_SECONDS,
+                agent_shutdown_grace_ms: None,
+                agent_input_preview_max_chars: DEFAULT_AGENT_INPUT_PREVIEW_MAX_CHARS,
                 codex_home: fixture.codex_home(),
                 sqlite_home: fixture.codex_home(),
                 log_dir: fixture.codex_home().join("log"),
@@ -5292,6 +5367,8 @@ model_verbosity = "high"
                 js_repl_node_path: None,
                 js_repl_node_module_dirs: Vec::new(),
                 zsh_path: None,
+                git_path: None,
+                worktree_root: None,
                 hide_agent_reasoning: false,
                 show_raw_agent_reasoning: false,
                 model_reasoning_effort: Some(ReasoningEffort::High),
@@ -5327,6 +5404,8 @@ model_verbosity = "high"
                 notices: Default::default(),
                 check_for_update_on_startup: true,
                 disable_cron: false,
+                allowed_tools: None,
+                denied_tools: Vec::new(),
                 disable_paste_burst: false,
                 tui_notifications: Default::default(),
                 tui_notification_method: Default::default(),
@@ -5417,6 +5496,8 @@ model_verbosity = "high"
             agent_roles: BTreeMap::new(),
             memories: MemoriesConfig::default(),
             agent_job_max_runtime_seconds: DEFAULT_AGENT_JOB_MAX_RUNTIME_SECONDS,
+            agent_shutdown_grace_ms: None,
+            agent_input_preview_max_chars: DEFAULT_AGENT_INPUT_PREVIEW_MAX_CHARS,
             codex_home: fixture.codex_home(),
             sqlite_home: fixture.codex_home(),
             log_dir: fixture.codex_home().join("log"),
@@ -5430,6 +5511,8 @@ model_verbosity = "high"
             js_repl_node_path: None,
             js_repl_node_module_dirs: Vec::new(),
             zsh_path: None,
+            git_path: None,
+            worktree_root: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
             model_reasoning_effort: None,
@@ -5465,6 +5548,8 @@ model_verbosity = "high"
             notices: Default::default(),
             check_for_update_on_startup: true,
             disable_cron: false,
+            allowed_tools: None,
+            denied_tools: Vec::new(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
             tui_notification_method: Default::default(),
@@ -5553,6 +5638,8 @@ model_verbosity = "high"
             agent_roles: BTreeMap::new(),
             memories: MemoriesConfig::default(),
             agent_job_max_runtime_seconds: DEFAULT_AGENT_JOB_MAX_RUNTIME_SECONDS,
+            agent_shutdown_grace_ms: None,
+            agent_input_preview_max_chars: DEFAULT_AGENT_INPUT_PREVIEW_MAX_CHARS,
             codex_home: fixture.codex_home(),
             sqlite_home: fixture.codex_home(),
             log_dir: fixture.codex_home().join("log"),
@@ -5566,6 +5653,8 @@ model_verbosity = "high"
             js_repl_node_path: None,
             js_repl_node_module_dirs: Vec::new(),
             zsh_path: None,
+            git_path: None,
+            worktree_root: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
             model_reasoning_effort: None,
@@ -5601,6 +5690,8 @@ model_verbosity = "high"
             notices: Default::default(),
             check_for_update_on_startup: true,
             disable_cron: false,
+            allowed_tools: None,
+            denied_tools: Vec::new(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
             tui_notification_method: Default::default(),
@@ -5675,6 +5766,8 @@ model_verbosity = "high"
             agent_roles: BTreeMap::new(),
             memories: MemoriesConfig::default(),
             agent_job_max_runtime_seconds: DEFAULT_AGENT_JOB_MAX_RUNTIME_SECONDS,
+            agent_shutdown_grace_ms: None,
+            agent_input_preview_max_chars: DEFAULT_AGENT_INPUT_PREVIEW_MAX_CHARS,
             codex_home: fixture.codex_home(),
             sqlite_home: fixture.codex_home(),
             log_dir: fixture.codex_home().join("log"),
@@ -5688,6 +5781,8 @@ model_verbosity = "high"
             js_repl_node_path: None,
             js_repl_node_module_dirs: Vec::new(),
             zsh_path: None,
+            git_path: None,
+            worktree_root: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
             model_reasoning_effort: Some(ReasoningEffort::High),
@@ -5723,6 +5818,8 @@ model_verbosity = "high"
             notices: Default::default(),
             check_for_update_on_startup: true,
             disable_cron: false,
+            allowed_tools: None,
+            denied_tools: Vec::new(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
             tui_notification_method: Default::default(),
@@ -117,6 +117,7 @@ mod tools;
 pub mod turn_diff_tracker;
 mod turn_metadata;
 mod turn_timing;
+mod zsh_exec_bridge;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
 pub use rollout::INTERACTIVE_SESSION_SOURCES;
 pub use rollout::RolloutRecorder;
@@ -140,6 +141,10 @@ pub use rollout::list::read_session_meta_line;
 pub use rollout::policy::EventPersistenceMode;
 pub use rollout::rollout_date_parts;
 pub use rollout::session_index::find_thread_names_by_ids;
+pub use zsh_exec_bridge::maybe_run_bash_exec_wrapper_mode;
+pub use zsh_exec_bridge::maybe_run_exec_wrapper_mode;
+pub use zsh_exec_bridge::maybe_run_fish_exec_wrapper_mode;
+pub use zsh_exec_bridge::maybe_run_zsh_exec_wrapper_mode;
 mod function_tool;
 mod state;
 mod tasks;
@@ -168,6 +173,9 @@ pub use exec_policy::format_exec_policy_error_with_source;
 pub use exec_policy::load_exec_policy;
 pub use file_watcher::FileWatcherEvent;
 pub use safety::get_platform_sandbox;
+pub use tools::handlers::multi_agents::PersistedTeamConfig;
+pub use tools::handlers::multi_agents::PersistedTeamMember;
+pub use tools::handlers::multi_agents::list_persisted_teams;
 pub use tools::spec::parse_tool_input_schema;
 pub use turn_metadata::build_turn_metadata_header;
 pub mod compact;
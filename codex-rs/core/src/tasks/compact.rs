@@ -29,21 +29,28 @@ impl SessionTask for CompactTask {
         _cancellation_token: CancellationToken,
     ) -> Option<String> {
         let session = session.clone_session();
-        let _ = if crate::compact::should_use_remote_compact_task(&ctx.provider) {
+        let before_tokens = session.get_total_token_usage().await;
+        let result = if crate::compact::should_use_remote_compact_task(&ctx.provider) {
             let _ = session.services.session_telemetry.counter(
                 "codex.task.compact",
                 1,
                 &[("type", "remote")],
             );
-            crate::compact_remote::run_remote_compact_task(session.clone(), ctx).await
+            crate::compact_remote::run_remote_compact_task(session.clone(), Arc::clone(&ctx)).await
         } else {
             let _ = session.services.session_telemetry.counter(
                 "codex.task.compact",
                 1,
                 &[("type", "local")],
             );
-            crate::compact::run_compact_task(session.clone(), ctx, input).await
+            crate::compact::run_compact_task(session.clone(), Arc::clone(&ctx), input).await
         };
+        if result.is_ok() {
+            let after_tokens = session.get_total_token_usage().await;
+            session
+                .dispatch_compact_hook(ctx.as_ref(), "manual_compact", before_tokens, after_tokens)
+                .await;
+        }
         None
     }
 }
@@ -22,6 +22,7 @@ use crate::rollout::RolloutRecorder;
 use crate::rollout::truncation;
 use crate::shell_snapshot::ShellSnapshot;
 use crate::skills::SkillsManager;
+use codex_hooks::CallbackHook;
 use codex_protocol::ThreadId;
 use codex_protocol::config_types::CollaborationModeMask;
 use codex_protocol::openai_models::ModelPreset;
@@ -139,6 +140,7 @@ pub(crate) struct ThreadManagerState {
     mcp_manager: Arc<McpManager>,
     file_watcher: Arc<FileWatcher>,
     session_source: SessionSource,
+    callback_hooks: std::sync::RwLock<Vec<CallbackHook>>,
     // Captures submitted ops for testing purpose when test mode is enabled.
     ops_log: Option<SharedCapturedOps>,
 }
@@ -175,6 +177,7 @@ impl ThreadManager {
                 file_watcher,
                 auth_manager,
                 session_source,
+                callback_hooks: std::sync::RwLock::new(Vec::new()),
                 ops_log: should_use_test_thread_manager_behavior()
                     .then(|| Arc::new(std::sync::Mutex::new(Vec::new()))),
             }),
@@ -233,6 +236,7 @@ impl ThreadManager {
                 file_watcher,
                 auth_manager,
                 session_source: SessionSource::Exec,
+                callback_hooks: std::sync::RwLock::new(Vec::new()),
                 ops_log: should_use_test_thread_manager_behavior()
                     .then(|| Arc::new(std::sync::Mutex::new(Vec::new()))),
             }),
@@ -244,6 +248,16 @@ impl ThreadManager {
         self.state.session_source.clone()
     }
 
+    /// Registers an in-process hook handler for threads started after this call, including the
+    /// agents they spawn.
+    pub fn add_callback_hook(&self, hook: CallbackHook) {
+        self.state
+            .callback_hooks
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(hook);
+    }
+
     pub fn skills_manager(&self) -> Arc<SkillsManager> {
         self.state.skills_manager.clone()
     }
@@ -594,6 +608,11 @@ impl ThreadManagerState {
         let watch_registration = self
             .file_watcher
             .register_config(&config, self.skills_manager.as_ref());
+        let callback_hooks = self
+            .callback_hooks
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
         let CodexSpawnOk {
             codex, thread_id, ..
         } = Codex::spawn(
@@ -611,6 +630,7 @@ impl ThreadManagerState {
             persist_extended_history,
             metrics_service_name,
             inherited_shell_snapshot,
+            callback_hooks,
         )
         .await?;
         self.finalize_thread_spawn(codex, thread_id, watch_registration)
@@ -24,8 +24,12 @@ use codex_hooks::HookEvent;
 use codex_hooks::HookPayload;
 use codex_hooks::HookResultControl;
 use codex_protocol::ThreadId;
+use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::models::BaseInstructions;
 use codex_protocol::models::FunctionCallOutputBody;
+use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::permissions::FileSystemSandboxPolicy;
+use codex_protocol::permissions::NetworkSandboxPolicy;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::CollabAgentInteractionBeginEvent;
 use codex_protocol::protocol::CollabAgentInteractionEndEvent;
@@ -39,8 +43,11 @@ use codex_protocol::protocol::CollabResumeBeginEvent;
 use codex_protocol::protocol::CollabResumeEndEvent;
 use codex_protocol::protocol::CollabWaitingBeginEvent;
 use codex_protocol::protocol::CollabWaitingEndEvent;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::SandboxPolicy;
 use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::SubAgentSource;
+use codex_protocol::protocol::WarningEvent;
 use codex_protocol::user_input::UserInput;
 use futures::FutureExt;
 use futures::StreamExt;
@@ -71,16 +78,26 @@ pub struct MultiAgentHandler;
 pub(crate) const MIN_WAIT_TIMEOUT_MS: i64 = 10_000;
 pub(crate) const DEFAULT_WAIT_TIMEOUT_MS: i64 = 30_000;
 pub(crate) const MAX_WAIT_TIMEOUT_MS: i64 = 300_000;
+/// `timeout_ms` sentinel asking a wait to block until the agents finish, with no deadline.
+pub(crate) const UNBOUNDED_WAIT_TIMEOUT_MS: i64 = -1;
 pub(crate) const TEAM_SPAWN_CALL_PREFIX: &str = "team/spawn:";
 pub(crate) const TEAM_WAIT_CALL_PREFIX: &str = "team/wait:";
 pub(crate) const TEAM_CLOSE_CALL_PREFIX: &str = "team/close:";
 const TEAM_CONFIG_DIR: &str = "teams";
 const TEAM_TASKS_DIR: &str = "tasks";
+const TEAM_CONFIG_FILE: &str = "config.json";
 const WORKTREE_ROOT_DIR: &str = "worktrees";
+const WORKTREE_ADD_MAX_ATTEMPTS: u32 = 3;
+const WORKTREE_ADD_RETRY_BACKOFF: Duration = Duration::from_millis(200);
+/// How long a waiter lets non-final status changes coalesce before re-checking, so an agent
+/// whose status flaps does not keep waking the wait loop.
+const WAIT_STATUS_DEBOUNCE: Duration = Duration::from_millis(5);
+const SPAWNED_AGENTS_DIR: &str = "spawned_agents";
 
 #[derive(Debug, Deserialize)]
 struct CloseAgentArgs {
     id: String,
+    drain_timeout_ms: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -88,6 +105,7 @@ struct TeamMember {
     name: String,
     agent_id: ThreadId,
     agent_type: Option<String>,
+    spawned_at: i64,
 }
 
 #[derive(Debug, Clone)]
@@ -100,6 +118,8 @@ struct TeamRecord {
 enum WaitMode {
     Any,
     All,
+    /// Return as soon as one agent fails, otherwise wait until every agent is final.
+    FirstError,
 }
 
 type TeamRegistry = HashMap<ThreadId, HashMap<String, TeamRecord>>;
@@ -114,6 +134,8 @@ struct WorktreeLease {
     repo_root: Option<PathBuf>,
     worktree_path: PathBuf,
     created_via_hook: bool,
+    /// Environment exported by the `worktree_create` hooks that set up this worktree.
+    env: HashMap<String, String>,
 }
 
 type WorktreeLeaseRegistry = HashMap<ThreadId, WorktreeLease>;
@@ -123,21 +145,24 @@ fn worktree_leases() -> &'static Mutex<WorktreeLeaseRegistry> {
     REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+/// On-disk team config written to `<codex_home>/teams/<lead_thread_id>/<team_id>/config.json`.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct PersistedTeamConfig {
-    team_name: String,
-    lead_thread_id: String,
-    created_at: i64,
-    members: Vec<PersistedTeamMember>,
+pub struct PersistedTeamConfig {
+    pub team_name: String,
+    pub lead_thread_id: String,
+    pub created_at: i64,
+    pub members: Vec<PersistedTeamMember>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct PersistedTeamMember {
-    name: String,
-    agent_id: String,
-    agent_type: Option<String>,
+pub struct PersistedTeamMember {
+    pub name: String,
+    pub agent_id: String,
+    pub agent_type: Option<String>,
+    #[serde(default)]
+    pub spawned_at: i64,
 }
 
 fn now_unix_seconds() -> i64 {
@@ -147,33 +172,136 @@ fn now_unix_seconds() -> i64 {
         .map_or(0, |duration| duration.as_secs() as i64)
 }
 
-fn team_dir(codex_home: &Path, team_id: &str) -> PathBuf {
+fn team_dir(codex_home: &Path, lead_thread_id: ThreadId, team_id: &str) -> PathBuf {
+    codex_home
+        .join(TEAM_CONFIG_DIR)
+        .join(lead_thread_id.to_string())
+        .join(team_id)
+}
+
+fn team_config_path(codex_home: &Path, lead_thread_id: ThreadId, team_id: &str) -> PathBuf {
+    team_dir(codex_home, lead_thread_id, team_id).join(TEAM_CONFIG_FILE)
+}
+
+fn team_tasks_dir(codex_home: &Path, lead_thread_id: ThreadId, team_id: &str) -> PathBuf {
+    codex_home
+        .join(TEAM_TASKS_DIR)
+        .join(lead_thread_id.to_string())
+        .join(team_id)
+}
+
+/// Where teams lived before they were namespaced by lead: `<codex_home>/teams/<team_id>` (and
+/// `<codex_home>/tasks/<team_id>`). Still read so older teams are found, and moved to the
+/// namespaced layout the next time they are persisted.
+fn legacy_team_dir(codex_home: &Path, team_id: &str) -> PathBuf {
     codex_home.join(TEAM_CONFIG_DIR).join(team_id)
 }
 
-fn team_config_path(codex_home: &Path, team_id: &str) -> PathBuf {
-    team_dir(codex_home, team_id).join("config.json")
+fn legacy_team_tasks_dir(codex_home: &Path, team_id: &str) -> PathBuf {
+    codex_home.join(TEAM_TASKS_DIR).join(team_id)
 }
 
-async fn read_persisted_team_config(
-    codex_home: &Path,
+async fn read_team_config_file(
+    config_path: &Path,
     team_id: &str,
-) -> Result<PersistedTeamConfig, FunctionCallError> {
-    let config_path = team_config_path(codex_home, team_id);
-    let raw = match tokio::fs::read_to_string(&config_path).await {
+) -> Result<Option<PersistedTeamConfig>, FunctionCallError> {
+    let raw = match tokio::fs::read_to_string(config_path).await {
         Ok(raw) => raw,
-        Err(err) if err.kind() == ErrorKind::NotFound => {
-            return Err(FunctionCallError::RespondToModel(format!(
-                "team `{team_id}` not found"
-            )));
-        }
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
         Err(err) => return Err(team_persistence_error("read team config", team_id, err)),
     };
 
     serde_json::from_str::<PersistedTeamConfig>(&raw)
+        .map(Some)
         .map_err(|err| team_persistence_error("parse team config", team_id, err))
 }
 
+/// Reads a team from the legacy layout, but only when `lead_thread_id` is its lead.
+async fn read_legacy_team_config(
+    codex_home: &Path,
+    lead_thread_id: ThreadId,
+    team_id: &str,
+) -> Result<Option<PersistedTeamConfig>, FunctionCallError> {
+    let config_path = legacy_team_dir(codex_home, team_id).join(TEAM_CONFIG_FILE);
+    Ok(read_team_config_file(&config_path, team_id)
+        .await?
+        .filter(|config| config.lead_thread_id == lead_thread_id.to_string()))
+}
+
+async fn find_persisted_team_config(
+    codex_home: &Path,
+    lead_thread_id: ThreadId,
+    team_id: &str,
+) -> Result<Option<PersistedTeamConfig>, FunctionCallError> {
+    let config_path = team_config_path(codex_home, lead_thread_id, team_id);
+    match read_team_config_file(&config_path, team_id).await? {
+        Some(config) => Ok(Some(config)),
+        None => read_legacy_team_config(codex_home, lead_thread_id, team_id).await,
+    }
+}
+
+async fn read_persisted_team_config(
+    codex_home: &Path,
+    lead_thread_id: ThreadId,
+    team_id: &str,
+) -> Result<PersistedTeamConfig, FunctionCallError> {
+    find_persisted_team_config(codex_home, lead_thread_id, team_id)
+        .await?
+        .ok_or_else(|| FunctionCallError::RespondToModel(format!("team `{team_id}` not found")))
+}
+
+/// Lists every team persisted under `codex_home` (for every lead, in both the namespaced and
+/// the legacy layout), oldest first. Configs that cannot be read or parsed are skipped with a
+/// warning so one bad team does not hide the rest.
+pub async fn list_persisted_teams(codex_home: &Path) -> std::io::Result<Vec<PersistedTeamConfig>> {
+    let mut entries = match tokio::fs::read_dir(codex_home.join(TEAM_CONFIG_DIR)).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut config_paths = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        if !entry.file_type().await?.is_dir() {
+            continue;
+        }
+        let legacy_config_path = entry.path().join(TEAM_CONFIG_FILE);
+        if tokio::fs::try_exists(&legacy_config_path)
+            .await
+            .unwrap_or(false)
+        {
+            config_paths.push(legacy_config_path);
+            continue;
+        }
+        let mut lead_entries = tokio::fs::read_dir(entry.path()).await?;
+        while let Some(team_entry) = lead_entries.next_entry().await? {
+            if team_entry.file_type().await?.is_dir() {
+                config_paths.push(team_entry.path().join(TEAM_CONFIG_FILE));
+            }
+        }
+    }
+
+    let mut teams = Vec::new();
+    for config_path in config_paths {
+        let team_id = config_path
+            .parent()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        match read_team_config_file(&config_path, &team_id).await {
+            Ok(Some(config)) => teams.push(config),
+            Ok(None) => {}
+            Err(err) => warn!("skipping persisted team `{team_id}`: {err}"),
+        }
+    }
+    teams.sort_by(|left, right| {
+        left.created_at
+            .cmp(&right.created_at)
+            .then_with(|| left.team_name.cmp(&right.team_name))
+    });
+    Ok(teams)
+}
+
 fn team_persistence_error(
     action: impl std::fmt::Display,
     team_id: &str,
@@ -228,6 +356,7 @@ fn persisted_team_config(
                 name: member.name.clone(),
                 agent_id: member.agent_id.to_string(),
                 agent_type: member.agent_type.clone(),
+                spawned_at: member.spawned_at,
             })
             .collect(),
     }
@@ -240,28 +369,288 @@ async fn persist_team_state(
     team: &TeamRecord,
 ) -> Result<(), FunctionCallError> {
     let config = persisted_team_config(sender_thread_id, team_id, team);
-    let config_path = team_config_path(codex_home, team_id);
+    let config_path = team_config_path(codex_home, sender_thread_id, team_id);
     write_json_atomic(&config_path, &config)
         .await
         .map_err(|err| team_persistence_error("write team config", team_id, err))?;
+    migrate_legacy_team_persistence(codex_home, sender_thread_id, team_id).await;
 
     Ok(())
 }
 
+/// Moves this lead's legacy task directory under the namespaced layout and drops the legacy
+/// config, now superseded by the namespaced one. Best effort: failures are only logged.
+async fn migrate_legacy_team_persistence(
+    codex_home: &Path,
+    lead_thread_id: ThreadId,
+    team_id: &str,
+) {
+    match read_legacy_team_config(codex_home, lead_thread_id, team_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return,
+        Err(err) => {
+            warn!("not migrating legacy team `{team_id}`: {err}");
+            return;
+        }
+    }
+
+    let legacy_tasks_dir = legacy_team_tasks_dir(codex_home, team_id);
+    let tasks_dir = team_tasks_dir(codex_home, lead_thread_id, team_id);
+    if tokio::fs::try_exists(&legacy_tasks_dir)
+        .await
+        .unwrap_or(false)
+    {
+        let moved = match tasks_dir.parent() {
+            Some(parent) => tokio::fs::create_dir_all(parent).await,
+            None => Ok(()),
+        };
+        let moved = match moved {
+            Ok(()) => tokio::fs::rename(&legacy_tasks_dir, &tasks_dir).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = moved {
+            warn!("failed to migrate tasks for legacy team `{team_id}`: {err}");
+            return;
+        }
+    }
+    if let Err(err) = remove_dir_if_exists(&legacy_team_dir(codex_home, team_id)).await {
+        warn!("failed to remove legacy config for team `{team_id}`: {err}");
+    }
+}
+
 async fn remove_team_persistence(
     codex_home: &Path,
+    lead_thread_id: ThreadId,
     team_id: &str,
 ) -> Result<(), FunctionCallError> {
-    remove_dir_if_exists(&team_dir(codex_home, team_id))
+    let owns_legacy_team = read_legacy_team_config(codex_home, lead_thread_id, team_id)
         .await
-        .map_err(|err| team_persistence_error("remove team config directory", team_id, err))?;
-    let tasks_dir = codex_home.join(TEAM_TASKS_DIR).join(team_id);
-    remove_dir_if_exists(&tasks_dir)
-        .await
-        .map_err(|err| team_persistence_error("remove team tasks directory", team_id, err))?;
+        .ok()
+        .flatten()
+        .is_some();
+    let mut team_dirs = vec![team_dir(codex_home, lead_thread_id, team_id)];
+    let mut tasks_dirs = vec![team_tasks_dir(codex_home, lead_thread_id, team_id)];
+    if owns_legacy_team {
+        team_dirs.push(legacy_team_dir(codex_home, team_id));
+        tasks_dirs.push(legacy_team_tasks_dir(codex_home, team_id));
+    }
+    for dir in team_dirs {
+        remove_dir_if_exists(&dir)
+            .await
+            .map_err(|err| team_persistence_error("remove team config directory", team_id, err))?;
+    }
+    for dir in tasks_dirs {
+        remove_dir_if_exists(&dir)
+            .await
+            .map_err(|err| team_persistence_error("remove team tasks directory", team_id, err))?;
+    }
     Ok(())
 }
 
+/// What [`reconcile_persisted_team`] did with a team found on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum TeamReconcileOutcome {
+    /// The team is registered in memory; its persisted state is current.
+    Active,
+    /// Members are still alive under this lead, so the team was registered again.
+    Reclaimed,
+    /// The team belongs to another lead thread; left untouched.
+    Foreign,
+    /// No member agent is alive anymore, so the persisted state was removed.
+    Removed,
+}
+
+/// Brings a persisted team led by `session` back in line with the live agents. Teams left
+/// behind by a crashed process either get re-registered (when some members are still alive)
+/// or have their persisted state removed. Teams of other leads are never touched: their
+/// members may be running in another process, where this session cannot see them.
+async fn reconcile_persisted_team(
+    session: &Session,
+    codex_home: &Path,
+    config: PersistedTeamConfig,
+) -> Result<TeamReconcileOutcome, FunctionCallError> {
+    let team_id = config.team_name;
+    let Ok(lead_thread_id) = ThreadId::from_string(&config.lead_thread_id) else {
+        return Ok(TeamReconcileOutcome::Foreign);
+    };
+    if lead_thread_id != session.conversation_id {
+        return Ok(TeamReconcileOutcome::Foreign);
+    }
+    if get_team_record(lead_thread_id, &team_id).is_ok() {
+        return Ok(TeamReconcileOutcome::Active);
+    }
+
+    let mut live_members = Vec::new();
+    for member in config.members {
+        let Ok(agent_id) = ThreadId::from_string(&member.agent_id) else {
+            continue;
+        };
+        let status = session.services.agent_control.get_status(agent_id).await;
+        if matches!(status, AgentStatus::NotFound | AgentStatus::Shutdown) {
+            continue;
+        }
+        live_members.push(TeamMember {
+            name: member.name,
+            agent_id,
+            agent_type: member.agent_type,
+            spawned_at: member.spawned_at,
+        });
+    }
+
+    if live_members.is_empty() {
+        remove_team_persistence(codex_home, lead_thread_id, &team_id).await?;
+        return Ok(TeamReconcileOutcome::Removed);
+    }
+
+    let record = TeamRecord {
+        members: live_members,
+        created_at: config.created_at,
+    };
+    insert_team_record(session.conversation_id, team_id.clone(), record.clone())?;
+    persist_team_state(codex_home, session.conversation_id, &team_id, &record).await?;
+    Ok(TeamReconcileOutcome::Reclaimed)
+}
+
+/// A standalone `spawn_agent` child recorded under
+/// `<codex_home>/spawned_agents/<lead_thread_id>.json`, so a lead resumed after a restart can
+/// find its children again and `resume_agent` them.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct PersistedSpawnedAgent {
+    pub(crate) agent_id: String,
+    pub(crate) agent_type: Option<String>,
+    pub(crate) model: Option<String>,
+    pub(crate) spawned_at: i64,
+}
+
+fn spawned_agents_path(codex_home: &Path, lead_thread_id: ThreadId) -> PathBuf {
+    codex_home
+        .join(SPAWNED_AGENTS_DIR)
+        .join(format!("{lead_thread_id}.json"))
+}
+
+/// Serializes read-modify-write updates of the spawned agent registry within this process.
+fn spawned_agents_lock() -> &'static tokio::sync::Mutex<()> {
+    static LOCK: OnceLock<tokio::sync::Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| tokio::sync::Mutex::new(()))
+}
+
+async fn load_spawned_agents(path: &Path) -> Result<Vec<PersistedSpawnedAgent>, std::io::Error> {
+    let raw = match tokio::fs::read_to_string(path).await {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    serde_json::from_str(&raw).map_err(|err| std::io::Error::new(ErrorKind::InvalidData, err))
+}
+
+/// Returns the agents `lead_thread_id` spawned with `spawn_agent`, oldest first. A missing or
+/// unreadable registry yields an empty list.
+pub(crate) async fn read_spawned_agents(
+    codex_home: &Path,
+    lead_thread_id: ThreadId,
+) -> Vec<PersistedSpawnedAgent> {
+    let path = spawned_agents_path(codex_home, lead_thread_id);
+    load_spawned_agents(&path).await.unwrap_or_else(|err| {
+        warn!(
+            "failed to read spawned agents from {}: {err}",
+            path.display()
+        );
+        Vec::new()
+    })
+}
+
+/// Loads the registry for a read-modify-write update. A registry that fails to parse is moved
+/// aside to `<lead_thread_id>.json.corrupt` rather than overwritten, so its entries can still be
+/// recovered by hand; any other read error is returned.
+async fn load_spawned_agents_for_update(
+    path: &Path,
+) -> Result<Vec<PersistedSpawnedAgent>, std::io::Error> {
+    match load_spawned_agents(path).await {
+        Err(err) if err.kind() == ErrorKind::InvalidData => {
+            let mut backup = path.as_os_str().to_owned();
+            backup.push(".corrupt");
+            let backup = PathBuf::from(backup);
+            tokio::fs::rename(path, &backup).await?;
+            warn!(
+                "failed to parse spawned agents in {}: {err}; moved it to {}",
+                path.display(),
+                backup.display()
+            );
+            Ok(Vec::new())
+        }
+        result => result,
+    }
+}
+
+/// Builds the note recorded when a lead session is resumed, listing the agents it spawned
+/// before the restart so it can `resume_agent` them without having memorized their ids.
+pub(crate) async fn spawned_agents_resume_note(
+    codex_home: &Path,
+    lead_thread_id: ThreadId,
+) -> Option<String> {
+    let agents = read_spawned_agents(codex_home, lead_thread_id).await;
+    if agents.is_empty() {
+        return None;
+    }
+    let lines = agents
+        .iter()
+        .map(|agent| {
+            format!(
+                "- {} (role: {}, model: {})",
+                agent.agent_id,
+                agent.agent_type.as_deref().unwrap_or("default"),
+                agent.model.as_deref().unwrap_or("default"),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(format!(
+        "This session was resumed. Agents you spawned earlier (use resume_agent to restore one that is no longer running, or list_spawned_agents for their status):\n{lines}"
+    ))
+}
+
+async fn record_spawned_agent(
+    codex_home: &Path,
+    lead_thread_id: ThreadId,
+    agent: PersistedSpawnedAgent,
+) -> Result<(), std::io::Error> {
+    let _guard = spawned_agents_lock().lock().await;
+    let path = spawned_agents_path(codex_home, lead_thread_id);
+    let mut agents = load_spawned_agents_for_update(&path).await?;
+    agents.retain(|existing| existing.agent_id != agent.agent_id);
+    agents.push(agent);
+    write_json_atomic(&path, &agents).await
+}
+
+/// Drops closed agents from the spawned agent registry of `lead_thread_id`, removing the file
+/// once it no longer lists any agent.
+async fn forget_spawned_agents(
+    codex_home: &Path,
+    lead_thread_id: ThreadId,
+    agent_ids: &[ThreadId],
+) -> Result<(), std::io::Error> {
+    let _guard = spawned_agents_lock().lock().await;
+    let path = spawned_agents_path(codex_home, lead_thread_id);
+    let mut agents = load_spawned_agents_for_update(&path).await?;
+    let before = agents.len();
+    agents.retain(|agent| {
+        !agent_ids
+            .iter()
+            .any(|agent_id| agent.agent_id == agent_id.to_string())
+    });
+    if agents.len() == before {
+        return Ok(());
+    }
+    if agents.is_empty() {
+        return match tokio::fs::remove_file(&path).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        };
+    }
+    write_json_atomic(&path, &agents).await
+}
+
 fn required_non_empty<'a>(value: &'a str, field: &str) -> Result<&'a str, FunctionCallError> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -310,6 +699,39 @@ fn find_team_member(
         })
 }
 
+/// How a message treats the receiver's current task.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum InterruptMode {
+    /// Queue the message without interrupting the current task.
+    #[default]
+    None,
+    /// Interrupt the current task and resend any input still queued for it ahead of the message.
+    Soft,
+    /// Interrupt the current task and drop any input still queued for it.
+    Hard,
+}
+
+/// Accepts `interrupt_mode` as a mode name or as the legacy `interrupt` bool.
+fn deserialize_interrupt_mode<'de, D>(deserializer: D) -> Result<InterruptMode, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum InterruptModeArg {
+        Flag(bool),
+        Mode(InterruptMode),
+    }
+
+    Ok(match InterruptModeArg::deserialize(deserializer)? {
+        InterruptModeArg::Flag(true) => InterruptMode::Soft,
+        InterruptModeArg::Flag(false) => InterruptMode::None,
+        InterruptModeArg::Mode(mode) => mode,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn send_message_to_member(
     session: &std::sync::Arc<Session>,
     turn: &std::sync::Arc<TurnContext>,
@@ -317,16 +739,33 @@ async fn send_message_to_member(
     receiver_thread_id: ThreadId,
     input_items: Vec<UserInput>,
     prompt: String,
-    interrupt: bool,
+    interrupt_mode: InterruptMode,
+    response_schema: Option<serde_json::Value>,
 ) -> Result<String, FunctionCallError> {
-    if interrupt {
-        session
-            .services
-            .agent_control
-            .interrupt_agent(receiver_thread_id)
-            .await
-            .map_err(|err| collab_agent_error(receiver_thread_id, err))?;
+    if receiver_thread_id == session.conversation_id {
+        return Err(FunctionCallError::RespondToModel(
+            "an agent cannot send a message to itself".to_string(),
+        ));
     }
+    let agent_control = &session.services.agent_control;
+    let input_items = match interrupt_mode {
+        InterruptMode::None => input_items,
+        InterruptMode::Soft => {
+            let mut pending_input = agent_control
+                .interrupt_agent_keeping_pending_input(receiver_thread_id)
+                .await
+                .map_err(|err| collab_agent_error(receiver_thread_id, err))?;
+            pending_input.extend(input_items);
+            pending_input
+        }
+        InterruptMode::Hard => {
+            agent_control
+                .interrupt_agent(receiver_thread_id)
+                .await
+                .map_err(|err| collab_agent_error(receiver_thread_id, err))?;
+            input_items
+        }
+    };
     session
         .send_event(
             turn,
@@ -342,7 +781,7 @@ async fn send_message_to_member(
     let result = session
         .services
         .agent_control
-        .send_message(receiver_thread_id, input_items)
+        .send_message(receiver_thread_id, input_items, response_schema)
         .await
         .map_err(|err| collab_agent_error(receiver_thread_id, err));
     let status = session
@@ -411,6 +850,18 @@ impl ToolHandler for MultiAgentHandler {
             "close_agent" => close_agent::handle(session, turn, call_id, arguments).await,
             "create_team" => create_team::handle(session, turn, call_id, arguments).await,
             "delete_team" => delete_team::handle(session, turn, call_id, arguments).await,
+            "team_status" => team_status::handle(session, turn, call_id, arguments).await,
+            "team_gc" => team_gc::handle(session, turn, call_id, arguments).await,
+            "team_rename" => team_rename::handle(session, turn, call_id, arguments).await,
+            "close_all" => close_all::handle(session, turn, call_id, arguments).await,
+            "team_add_member" => team_add_member::handle(session, turn, call_id, arguments).await,
+            "team_message_wait" => {
+                team_message_wait::handle(session, turn, call_id, arguments).await
+            }
+            "get_agent_output" => get_agent_output::handle(session, turn, call_id, arguments).await,
+            "list_spawned_agents" => {
+                list_spawned_agents::handle(session, turn, call_id, arguments).await
+            }
             other => Err(FunctionCallError::RespondToModel(format!(
                 "unsupported collab tool {other}"
             ))),
@@ -430,46 +881,83 @@ mod wait;
 struct WaitForAgentsResult {
     statuses: Vec<(ThreadId, AgentStatus)>,
     timed_out: bool,
-}
-
-fn normalize_wait_timeout(timeout_ms: Option<i64>) -> Result<i64, FunctionCallError> {
+    timed_out_ids: Vec<ThreadId>,
+    /// Receivers that were already final (or missing) when the wait started.
+    already_final: Vec<ThreadId>,
+    elapsed_ms: u64,
+    /// The receiver whose final status ended the wait: the first to finish for `Any`, the one
+    /// that failed (or finished last) for `FirstError`, and the last to finish for `All`. `None`
+    /// when nothing finished during the wait or an `All` wait timed out.
+    triggered_by: Option<ThreadId>,
+}
+
+/// Resolves a requested wait timeout, returning `None` for [`UNBOUNDED_WAIT_TIMEOUT_MS`].
+/// Positive values are clamped to `[MIN_WAIT_TIMEOUT_MS, MAX_WAIT_TIMEOUT_MS]`.
+fn normalize_wait_timeout(timeout_ms: Option<i64>) -> Result<Option<i64>, FunctionCallError> {
     let timeout_ms = timeout_ms.unwrap_or(DEFAULT_WAIT_TIMEOUT_MS);
     match timeout_ms {
+        UNBOUNDED_WAIT_TIMEOUT_MS => Ok(None),
         ms if ms <= 0 => Err(FunctionCallError::RespondToModel(
             "timeout_ms must be greater than zero".to_owned(),
         )),
-        ms => Ok(ms.clamp(MIN_WAIT_TIMEOUT_MS, MAX_WAIT_TIMEOUT_MS)),
+        ms => Ok(Some(ms.clamp(MIN_WAIT_TIMEOUT_MS, MAX_WAIT_TIMEOUT_MS))),
     }
 }
 
 async fn wait_for_final_status(
+    session: std::sync::Arc<Session>,
+    thread_id: ThreadId,
+    status_rx: Receiver<AgentStatus>,
+) -> Option<(ThreadId, AgentStatus)> {
+    wait_for_status(session, thread_id, status_rx, None).await
+}
+
+/// Whether `status` ends a wait: any final status, or the variant named by `until_status`.
+/// Payloads are ignored, so `until_status = running` matches every running agent.
+fn wait_target_reached(status: &AgentStatus, until_status: Option<&AgentStatus>) -> bool {
+    crate::agent::status::is_final(status)
+        || until_status
+            .is_some_and(|target| std::mem::discriminant(target) == std::mem::discriminant(status))
+}
+
+/// Like [`wait_for_final_status`], but also resolves once the agent reaches `until_status`.
+/// A target the agent never reaches still leaves the caller to its own timeout.
+async fn wait_for_status(
     session: std::sync::Arc<Session>,
     thread_id: ThreadId,
     mut status_rx: Receiver<AgentStatus>,
+    until_status: Option<&AgentStatus>,
 ) -> Option<(ThreadId, AgentStatus)> {
     let mut status = status_rx.borrow().clone();
-    if crate::agent::status::is_final(&status) {
+    if wait_target_reached(&status, until_status) {
         return Some((thread_id, status));
     }
 
     loop {
         if status_rx.changed().await.is_err() {
             let latest = session.services.agent_control.get_status(thread_id).await;
-            return crate::agent::status::is_final(&latest).then_some((thread_id, latest));
+            return wait_target_reached(&latest, until_status).then_some((thread_id, latest));
         }
-        status = status_rx.borrow().clone();
-        if crate::agent::status::is_final(&status) {
+        status = status_rx.borrow_and_update().clone();
+        if wait_target_reached(&status, until_status) {
             return Some((thread_id, status));
         }
+        // Target statuses above are reported immediately; only intermediate churn is debounced.
+        // The watch channel keeps just the latest value, so changes sent during the sleep
+        // collapse into a single wakeup.
+        tokio::time::sleep(WAIT_STATUS_DEBOUNCE).await;
     }
 }
 
 async fn wait_for_agents(
     session: std::sync::Arc<Session>,
     receiver_thread_ids: &[ThreadId],
-    timeout_ms: i64,
+    timeout_ms: Option<i64>,
+    member_timeouts_ms: &HashMap<ThreadId, Option<i64>>,
     mode: WaitMode,
+    until_status: Option<&AgentStatus>,
 ) -> Result<WaitForAgentsResult, (ThreadId, CodexErr)> {
+    let started_at = Instant::now();
     let mut status_rxs = Vec::with_capacity(receiver_thread_ids.len());
     let mut final_statuses = HashMap::new();
 
@@ -477,7 +965,7 @@ async fn wait_for_agents(
         match session.services.agent_control.subscribe_status(*id).await {
             Ok(rx) => {
                 let status = rx.borrow().clone();
-                if crate::agent::status::is_final(&status) {
+                if wait_target_reached(&status, until_status) {
                     final_statuses.insert(*id, status);
                 } else {
                     status_rxs.push((*id, rx));
@@ -489,26 +977,39 @@ async fn wait_for_agents(
             Err(err) => return Err((*id, err)),
         }
     }
+    let already_final = receiver_thread_ids
+        .iter()
+        .filter(|id| final_statuses.contains_key(id))
+        .copied()
+        .collect::<Vec<_>>();
+
+    // Each receiver gets its own deadline so a long-running member does not force a short
+    // timeout on everyone else (and vice versa). Without overrides every deadline is the same.
+    // A `None` timeout means no deadline: the wait lasts until that agent is final.
+    let mut futures = FuturesUnordered::new();
+    for (id, rx) in status_rxs {
+        let member_timeout_ms = member_timeouts_ms.get(&id).copied().unwrap_or(timeout_ms);
+        let deadline = member_timeout_ms.map(|ms| started_at + Duration::from_millis(ms as u64));
+        let session = session.clone();
+        let until_status = until_status.cloned();
+        futures.push(async move {
+            let wait = wait_for_status(session, id, rx, until_status.as_ref());
+            match deadline {
+                Some(deadline) => timeout_at(deadline, wait).await.ok().flatten(),
+                None => wait.await,
+            }
+        });
+    }
 
-    let deadline = Instant::now() + Duration::from_millis(timeout_ms as u64);
+    let mut triggered_by = None;
     match mode {
         WaitMode::Any => {
             if final_statuses.is_empty() {
-                let mut futures = FuturesUnordered::new();
-                for (id, rx) in status_rxs {
-                    let session = session.clone();
-                    futures.push(wait_for_final_status(session, id, rx));
-                }
-
                 let mut results = Vec::new();
-                loop {
-                    match timeout_at(deadline, futures.next()).await {
-                        Ok(Some(Some(result))) => {
-                            results.push(result);
-                            break;
-                        }
-                        Ok(Some(None)) => continue,
-                        Ok(None) | Err(_) => break,
+                while let Some(result) = futures.next().await {
+                    if let Some(result) = result {
+                        results.push(result);
+                        break;
                     }
                 }
 
@@ -522,6 +1023,7 @@ async fn wait_for_agents(
                     }
                 }
 
+                triggered_by = results.first().map(|(id, _)| *id);
                 for (id, status) in results {
                     final_statuses.insert(id, status);
                 }
@@ -532,31 +1034,81 @@ async fn wait_for_agents(
                 .filter_map(|id| final_statuses.get(id).cloned().map(|status| (*id, status)))
                 .collect::<Vec<_>>();
             let timed_out = statuses.is_empty();
+            let timed_out_ids = if timed_out {
+                receiver_thread_ids.to_vec()
+            } else {
+                Vec::new()
+            };
             Ok(WaitForAgentsResult {
                 timed_out,
+                timed_out_ids,
                 statuses,
+                already_final,
+                elapsed_ms: elapsed_ms_since(started_at),
+                triggered_by,
             })
         }
-        WaitMode::All => {
-            if final_statuses.len() < receiver_thread_ids.len() {
-                let mut futures = FuturesUnordered::new();
-                for (id, rx) in status_rxs {
-                    let session = session.clone();
-                    futures.push(wait_for_final_status(session, id, rx));
+        WaitMode::FirstError => {
+            while final_statuses.len() < receiver_thread_ids.len()
+                && !final_statuses
+                    .values()
+                    .any(crate::agent::status::is_failure)
+            {
+                match futures.next().await {
+                    Some(Some((id, status))) => {
+                        triggered_by = Some(id);
+                        final_statuses.insert(id, status);
+                    }
+                    Some(None) => continue,
+                    None => break,
                 }
+            }
 
-                while final_statuses.len() < receiver_thread_ids.len() {
-                    match timeout_at(deadline, futures.next()).await {
-                        Ok(Some(Some((id, status)))) => {
-                            final_statuses.insert(id, status);
-                        }
-                        Ok(Some(None)) => continue,
-                        Ok(None) | Err(_) => break,
+            let failed = final_statuses
+                .values()
+                .any(crate::agent::status::is_failure);
+            let timed_out_ids = if failed {
+                Vec::new()
+            } else {
+                receiver_thread_ids
+                    .iter()
+                    .filter(|id| !final_statuses.contains_key(id))
+                    .copied()
+                    .collect::<Vec<_>>()
+            };
+            let timed_out = !timed_out_ids.is_empty();
+            let statuses = receiver_thread_ids
+                .iter()
+                .filter_map(|id| final_statuses.get(id).cloned().map(|status| (*id, status)))
+                .collect::<Vec<_>>();
+
+            Ok(WaitForAgentsResult {
+                statuses,
+                timed_out,
+                timed_out_ids,
+                already_final,
+                elapsed_ms: elapsed_ms_since(started_at),
+                triggered_by,
+            })
+        }
+        WaitMode::All => {
+            while final_statuses.len() < receiver_thread_ids.len() {
+                match futures.next().await {
+                    Some(Some((id, status))) => {
+                        triggered_by = Some(id);
+                        final_statuses.insert(id, status);
                     }
+                    Some(None) => continue,
+                    None => break,
                 }
             }
 
-            let timed_out = final_statuses.len() < receiver_thread_ids.len();
+            let timed_out_ids = receiver_thread_ids
+                .iter()
+                .filter(|id| !final_statuses.contains_key(id))
+                .copied()
+                .collect::<Vec<_>>();
+            let timed_out = !timed_out_ids.is_empty();
             let statuses = receiver_thread_ids
                 .iter()
                 .filter_map(|id| final_statuses.get(id).cloned().map(|status| (*id, status)))
@@ -565,11 +1117,30 @@ async fn wait_for_agents(
             Ok(WaitForAgentsResult {
                 statuses,
                 timed_out,
+                timed_out_ids,
+                already_final,
+                elapsed_ms: elapsed_ms_since(started_at),
+                triggered_by: triggered_by.filter(|_| !timed_out),
             })
         }
     }
 }
 
+fn elapsed_ms_since(started_at: Instant) -> u64 {
+    u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+fn validate_response_schema(
+    response_schema: Option<&serde_json::Value>,
+) -> Result<(), FunctionCallError> {
+    match response_schema {
+        Some(schema) if !schema.is_object() => Err(FunctionCallError::RespondToModel(
+            "response_schema must be a JSON object".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
 fn normalized_team_id(team_id: &str) -> Result<String, FunctionCallError> {
     Ok(required_path_segment(team_id, "team_id")?.to_string())
 }
@@ -593,21 +1164,45 @@ fn optional_non_empty<'a>(
     }
 }
 
+/// Applies per-member model selection. The provider is the first of `model_provider_id` and then
+/// `model_provider_fallbacks` that exists in `config.model_providers`, so teams spanning machines
+/// with different provider setups can list alternatives.
 fn apply_member_model_overrides(
     config: &mut Config,
     model_provider_id: Option<&str>,
+    model_provider_fallbacks: &[String],
     model: Option<&str>,
 ) -> Result<(), FunctionCallError> {
-    if let Some(provider_id) = model_provider_id {
-        let provider = config
-            .model_providers
-            .get(provider_id)
-            .cloned()
-            .ok_or_else(|| {
-                FunctionCallError::RespondToModel(format!(
-                    "model_provider `{provider_id}` not found"
-                ))
-            })?;
+    let candidates = model_provider_id
+        .into_iter()
+        .chain(
+            model_provider_fallbacks
+                .iter()
+                .map(|fallback| fallback.trim())
+                .filter(|fallback| !fallback.is_empty()),
+        )
+        .collect::<Vec<_>>();
+    if !candidates.is_empty() {
+        let Some((provider_id, provider)) = candidates.iter().find_map(|provider_id| {
+            config
+                .model_providers
+                .get(*provider_id)
+                .map(|provider| (*provider_id, provider.clone()))
+        }) else {
+            return Err(FunctionCallError::RespondToModel(
+                match candidates.as_slice() {
+                    [provider_id] => format!("model_provider `{provider_id}` not found"),
+                    _ => format!(
+                        "none of the model providers were found: {}",
+                        candidates
+                            .iter()
+                            .map(|provider_id| format!("`{provider_id}`"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                },
+            ));
+        };
         config.model_provider_id = provider_id.to_string();
         config.model_provider = provider;
     }
@@ -619,6 +1214,21 @@ fn apply_member_model_overrides(
     Ok(())
 }
 
+/// Applies per-spawn reasoning settings on top of what the child inherited from the turn and
+/// its role config.
+fn apply_member_reasoning_overrides(
+    config: &mut Config,
+    reasoning_effort: Option<ReasoningEffort>,
+    reasoning_summary: Option<ReasoningSummary>,
+) {
+    if let Some(reasoning_effort) = reasoning_effort {
+        config.model_reasoning_effort = Some(reasoning_effort);
+    }
+    if let Some(reasoning_summary) = reasoning_summary {
+        config.model_reasoning_summary = Some(reasoning_summary);
+    }
+}
+
 fn prefixed_team_call_id(prefix: &str, call_id: &str) -> String {
     format!("{prefix}{call_id}")
 }
@@ -629,19 +1239,21 @@ fn team_member_refs(members: &[TeamMember]) -> Vec<CollabAgentRef> {
         .map(|member| CollabAgentRef {
             thread_id: member.agent_id,
             agent_nickname: Some(member.name.clone()),
-            agent_role: Some(
-                member
-                    .agent_type
-                    .as_deref()
-                    .map(str::trim)
-                    .filter(|agent_type| !agent_type.is_empty())
-                    .unwrap_or("default")
-                    .to_string(),
-            ),
+            agent_role: Some(team_member_agent_type(member).to_string()),
         })
         .collect()
 }
 
+/// The member's trimmed `agent_type`, or `default` when it was spawned without one.
+fn team_member_agent_type(member: &TeamMember) -> &str {
+    member
+        .agent_type
+        .as_deref()
+        .map(str::trim)
+        .filter(|agent_type| !agent_type.is_empty())
+        .unwrap_or("default")
+}
+
 fn team_member_status_entries(
     members: &[TeamMember],
     statuses: &HashMap<ThreadId, AgentStatus>,
@@ -704,6 +1316,39 @@ fn find_team_for_member(member_thread_id: ThreadId) -> Result<Option<String>, Fu
     Ok(None)
 }
 
+/// Resolves the lead of `team_id` as seen from `thread_id` (the lead itself or one of its
+/// members): the live registry first, then the thread that spawned the caller.
+fn find_team_lead(
+    thread_id: ThreadId,
+    team_id: &str,
+    session_source: &SessionSource,
+) -> Result<Option<ThreadId>, FunctionCallError> {
+    let registry = team_registry()
+        .lock()
+        .map_err(|_| FunctionCallError::Fatal("team registry poisoned".to_string()))?;
+    let registered_lead = registry.iter().find_map(|(lead_thread_id, teams)| {
+        teams
+            .get(team_id)
+            .filter(|record| {
+                *lead_thread_id == thread_id
+                    || record
+                        .members
+                        .iter()
+                        .any(|member| member.agent_id == thread_id)
+            })
+            .map(|_| *lead_thread_id)
+    });
+    if registered_lead.is_some() {
+        return Ok(registered_lead);
+    }
+    Ok(match session_source {
+        SessionSource::SubAgent(SubAgentSource::ThreadSpawn {
+            parent_thread_id, ..
+        }) => Some(*parent_thread_id),
+        _ => None,
+    })
+}
+
 fn insert_team_record(
     sender_thread_id: ThreadId,
     team_id: String,
@@ -736,6 +1381,16 @@ fn remove_team_record(sender_thread_id: ThreadId, team_id: &str) -> Result<(), F
     Ok(())
 }
 
+/// Removes and returns every team led by `sender_thread_id`.
+fn take_team_records(
+    sender_thread_id: ThreadId,
+) -> Result<HashMap<String, TeamRecord>, FunctionCallError> {
+    let mut registry = team_registry()
+        .lock()
+        .map_err(|_| FunctionCallError::Fatal("team registry poisoned".to_string()))?;
+    Ok(registry.remove(&sender_thread_id).unwrap_or_default())
+}
+
 fn restore_team_record(
     sender_thread_id: ThreadId,
     team_id: &str,
@@ -749,6 +1404,65 @@ fn restore_team_record(
     Ok(())
 }
 
+/// Moves a team's record from `team_id` to `new_team_id` under one registry lock and returns it.
+/// Fails, leaving the registry untouched, when `team_id` is unknown or `new_team_id` is taken.
+fn rename_team_record(
+    sender_thread_id: ThreadId,
+    team_id: &str,
+    new_team_id: &str,
+) -> Result<TeamRecord, FunctionCallError> {
+    let mut registry = team_registry()
+        .lock()
+        .map_err(|_| FunctionCallError::Fatal("team registry poisoned".to_string()))?;
+    let Some(teams) = registry.get_mut(&sender_thread_id) else {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "team `{team_id}` not found"
+        )));
+    };
+    if teams.contains_key(new_team_id) {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "team `{new_team_id}` already exists"
+        )));
+    }
+    let record = teams
+        .remove(team_id)
+        .ok_or_else(|| FunctionCallError::RespondToModel(format!("team `{team_id}` not found")))?;
+    teams.insert(new_team_id.to_string(), record.clone());
+    Ok(record)
+}
+
+/// Appends `member` to an existing team and returns the updated record. The name check runs
+/// under the registry lock so concurrent additions cannot produce duplicate names.
+fn append_team_member(
+    sender_thread_id: ThreadId,
+    team_id: &str,
+    member: TeamMember,
+) -> Result<TeamRecord, FunctionCallError> {
+    let mut registry = team_registry()
+        .lock()
+        .map_err(|_| FunctionCallError::Fatal("team registry poisoned".to_string()))?;
+    let Some(record) = registry
+        .get_mut(&sender_thread_id)
+        .and_then(|teams| teams.get_mut(team_id))
+    else {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "team `{team_id}` not found"
+        )));
+    };
+    if record
+        .members
+        .iter()
+        .any(|existing| existing.name == member.name)
+    {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "duplicate member name `{}`",
+            member.name
+        )));
+    }
+    record.members.push(member);
+    Ok(record.clone())
+}
+
 fn register_worktree_lease(agent_id: ThreadId, lease: WorktreeLease) {
     let mut registry = match worktree_leases().lock() {
         Ok(registry) => registry,
@@ -757,12 +1471,25 @@ fn register_worktree_lease(agent_id: ThreadId, lease: WorktreeLease) {
     registry.insert(agent_id, lease);
 }
 
+fn has_worktree_lease(agent_id: ThreadId) -> bool {
+    let registry = match worktree_leases().lock() {
+        Ok(registry) => registry,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    registry.contains_key(&agent_id)
+}
+
+/// Removes `agent_id`'s lease, returning it only when no other agent still shares the worktree.
 fn take_worktree_lease(agent_id: ThreadId) -> Option<WorktreeLease> {
     let mut registry = match worktree_leases().lock() {
         Ok(registry) => registry,
         Err(poisoned) => poisoned.into_inner(),
     };
-    registry.remove(&agent_id)
+    let lease = registry.remove(&agent_id)?;
+    let still_shared = registry
+        .values()
+        .any(|other| other.worktree_path == lease.worktree_path);
+    (!still_shared).then_some(lease)
 }
 
 fn approval_policy_for_hooks(policy: AskForApproval) -> &'static str {
@@ -775,6 +1502,67 @@ fn approval_policy_for_hooks(policy: AskForApproval) -> &'static str {
     }
 }
 
+/// Whether a failed git command hit lock contention that goes away once the concurrent git
+/// process finishes, e.g. several team members adding worktrees at the same time.
+fn is_transient_git_error(err_text: &str) -> bool {
+    err_text.contains("index.lock")
+        || err_text.contains("could not lock")
+        || (err_text.contains("Unable to create") && err_text.contains(".lock"))
+        || err_text.contains("another git process seems to be running")
+}
+
+/// Directory holding a conversation's agent worktrees: the configured `worktree_root`, or
+/// `<codex_home>/worktrees`, namespaced by conversation id either way.
+fn agent_worktree_root(config: &Config, conversation_id: ThreadId) -> PathBuf {
+    config
+        .worktree_root
+        .clone()
+        .unwrap_or_else(|| config.codex_home.join(WORKTREE_ROOT_DIR))
+        .join(conversation_id.to_string())
+}
+
+/// Creates `root` and writes a probe file there, so an unwritable or full `worktree_root`
+/// fails with a clear message before `git worktree add` runs.
+async fn ensure_writable_worktree_root(root: &Path) -> Result<(), FunctionCallError> {
+    tokio::fs::create_dir_all(root).await.map_err(|err| {
+        FunctionCallError::RespondToModel(format!(
+            "failed to create worktree root `{}`: {err}",
+            root.display()
+        ))
+    })?;
+    let probe = root.join(format!(".codex-write-check-{}", ThreadId::new()));
+    tokio::fs::write(&probe, b"").await.map_err(|err| {
+        FunctionCallError::RespondToModel(format!(
+            "worktree root `{}` is not writable: {err}",
+            root.display()
+        ))
+    })?;
+    let _ = tokio::fs::remove_file(&probe).await;
+    Ok(())
+}
+
+/// Resolves the git executable used for agent worktrees: `CODEX_GIT_PATH`, then the
+/// `git_path` config setting, then `git` on `PATH`.
+fn resolve_git_executable(config: &Config) -> Result<PathBuf, String> {
+    let configured = std::env::var_os("CODEX_GIT_PATH")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .map(|path| (path, "CODEX_GIT_PATH"))
+        .or_else(|| config.git_path.clone().map(|path| (path, "git_path")));
+    if let Some((path, source)) = configured {
+        if path.is_file() {
+            return Ok(path);
+        }
+        return Err(format!(
+            "git executable `{}` from {source} does not exist",
+            path.display()
+        ));
+    }
+    which::which("git").map_err(|err| {
+        format!("git executable not found on PATH ({err}); set git_path or CODEX_GIT_PATH")
+    })
+}
+
 fn git_error_text(output: &Output) -> String {
     let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
     if !stderr.is_empty() {
@@ -792,7 +1580,7 @@ async fn dispatch_subagent_start_hook(
     turn: &TurnContext,
     agent_id: ThreadId,
     agent_type: &str,
-) -> Vec<String> {
+) -> (Vec<String>, HashMap<String, String>) {
     let outcomes = session
         .hooks()
         .dispatch(HookPayload {
@@ -808,6 +1596,7 @@ async fn dispatch_subagent_start_hook(
         .await;
 
     let mut additional_context = Vec::new();
+    let mut env = HashMap::new();
     for outcome in outcomes {
         let hook_name = outcome.hook_name;
         let result = outcome.result;
@@ -829,8 +1618,10 @@ async fn dispatch_subagent_start_hook(
         }
 
         additional_context.extend(result.additional_context);
+        // Outcomes arrive in match order, so the last matching hook wins on conflicts.
+        env.extend(result.env);
     }
-    additional_context
+    (additional_context, env)
 }
 
 async fn dispatch_teammate_idle_hook(
@@ -884,7 +1675,7 @@ async fn dispatch_worktree_create_hook(
     session: &Session,
     turn: &TurnContext,
     name: String,
-) -> Result<Option<(String, PathBuf)>, FunctionCallError> {
+) -> Result<Option<(String, PathBuf, HashMap<String, String>)>, FunctionCallError> {
     let outcomes = session
         .hooks()
         .dispatch(HookPayload {
@@ -902,6 +1693,7 @@ async fn dispatch_worktree_create_hook(
     let mut additional_context = Vec::new();
     let mut hook_names = Vec::new();
     let mut worktree_paths = Vec::new();
+    let mut env = HashMap::new();
 
     for outcome in outcomes {
         let hook_name = outcome.hook_name;
@@ -915,9 +1707,18 @@ async fn dispatch_worktree_create_hook(
         }
 
         if let HookResultControl::Block { reason } = result.control {
-            return Err(FunctionCallError::RespondToModel(format!(
-                "worktree_create hook '{hook_name}' blocked: {reason}"
-            )));
+            let message = format!("worktree_create hook '{hook_name}' blocked: {reason}");
+            // Surface the block to the user as well, not just the model, so a policy hook that
+            // stopped a worktree is visible in the UI.
+            session
+                .send_event(
+                    turn,
+                    EventMsg::Warning(WarningEvent {
+                        message: message.clone(),
+                    }),
+                )
+                .await;
+            return Err(FunctionCallError::RespondToModel(message));
         }
 
         if let Some(path) = result.worktree_path {
@@ -925,6 +1726,7 @@ async fn dispatch_worktree_create_hook(
         }
 
         additional_context.extend(result.additional_context);
+        env.extend(result.env);
     }
 
     session.record_hook_context(turn, &additional_context).await;
@@ -934,7 +1736,9 @@ async fn dispatch_worktree_create_hook(
             "worktree_create hooks ({}) did not print a worktree path on stdout",
             hook_names.join(", ")
         ))),
-        1 => Ok(worktree_paths.pop()),
+        1 => Ok(worktree_paths
+            .pop()
+            .map(|(hook_name, path)| (hook_name, path, env))),
         _ => Err(FunctionCallError::RespondToModel(format!(
             "worktree_create hooks ({}) printed multiple worktree paths on stdout",
             worktree_paths
@@ -985,12 +1789,36 @@ async fn dispatch_worktree_remove_hook(
     session.record_hook_context(turn, &additional_context).await;
 }
 
+/// The repository root and git executable [`create_agent_worktree`] needs when no
+/// `WorktreeCreate` hook provides the worktree.
+fn git_worktree_prerequisites(turn: &TurnContext) -> Result<(PathBuf, PathBuf), FunctionCallError> {
+    let Some(repo_root) = crate::git_info::resolve_root_git_project_for_trust(&turn.cwd) else {
+        return Err(FunctionCallError::RespondToModel(
+            "worktree=true requires running inside a git repository".to_string(),
+        ));
+    };
+    let git = resolve_git_executable(&turn.config).map_err(FunctionCallError::RespondToModel)?;
+    Ok((repo_root, git))
+}
+
+/// Reports the errors [`create_agent_worktree`] would hit up front, without creating anything.
+/// A `WorktreeCreate` hook decides for itself, so only the git fallback is checked.
+fn precheck_agent_worktree(session: &Session, turn: &TurnContext) -> Result<(), FunctionCallError> {
+    let hook_event = HookEvent::WorktreeCreate {
+        name: String::new(),
+    };
+    if session.hooks().has_hooks_for(&hook_event) {
+        return Ok(());
+    }
+    git_worktree_prerequisites(turn).map(|_| ())
+}
+
 async fn create_agent_worktree(
     session: &Session,
     turn: &TurnContext,
 ) -> Result<WorktreeLease, FunctionCallError> {
     let name = ThreadId::new().to_string();
-    if let Some((hook_name, worktree_path)) =
+    if let Some((hook_name, worktree_path, env)) =
         dispatch_worktree_create_hook(session, turn, name.clone()).await?
     {
         let metadata = tokio::fs::metadata(&worktree_path).await.map_err(|err| {
@@ -1009,49 +1837,53 @@ async fn create_agent_worktree(
             repo_root: None,
             worktree_path,
             created_via_hook: true,
+            env,
         });
     }
 
-    let Some(repo_root) = crate::git_info::resolve_root_git_project_for_trust(&turn.cwd) else {
-        return Err(FunctionCallError::RespondToModel(
-            "worktree=true requires running inside a git repository".to_string(),
-        ));
-    };
+    let (repo_root, git) = git_worktree_prerequisites(turn)?;
 
-    let root = turn
-        .config
-        .codex_home
-        .join(WORKTREE_ROOT_DIR)
-        .join(session.conversation_id.to_string());
-    tokio::fs::create_dir_all(&root).await.map_err(|err| {
-        FunctionCallError::RespondToModel(format!("failed to create worktree root: {err}"))
-    })?;
+    let root = agent_worktree_root(&turn.config, session.conversation_id);
+    ensure_writable_worktree_root(&root).await?;
 
     let worktree_path = root.join(name);
-    let output = Command::new("git")
-        .arg("-C")
-        .arg(&repo_root)
-        .args(["worktree", "add", "--detach"])
-        .arg(&worktree_path)
-        .arg("HEAD")
-        .output()
-        .await
-        .map_err(|err| {
-            FunctionCallError::RespondToModel(format!("failed to run git worktree add: {err}"))
-        })?;
+    let mut attempt = 1;
+    loop {
+        let output = Command::new(&git)
+            .arg("-C")
+            .arg(&repo_root)
+            .args(["worktree", "add", "--detach"])
+            .arg(&worktree_path)
+            .arg("HEAD")
+            .output()
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("failed to run git worktree add: {err}"))
+            })?;
+        if output.status.success() {
+            break;
+        }
 
-    if !output.status.success() {
-        return Err(FunctionCallError::RespondToModel(format!(
-            "failed to create worktree `{}`: {}",
-            worktree_path.display(),
-            git_error_text(&output)
-        )));
+        let err_text = git_error_text(&output);
+        if attempt >= WORKTREE_ADD_MAX_ATTEMPTS || !is_transient_git_error(&err_text) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "failed to create worktree `{}`: {err_text}",
+                worktree_path.display()
+            )));
+        }
+        debug!(attempt, error = %err_text, "retrying git worktree add after transient failure");
+        // A failed add can leave a partial checkout behind, which would make the retry fail
+        // with "already exists".
+        let _ = remove_dir_if_exists(&worktree_path).await;
+        tokio::time::sleep(WORKTREE_ADD_RETRY_BACKOFF * attempt).await;
+        attempt += 1;
     }
 
     Ok(WorktreeLease {
         repo_root: Some(repo_root),
         worktree_path,
         created_via_hook: false,
+        env: HashMap::new(),
     })
 }
 
@@ -1068,7 +1900,8 @@ async fn remove_worktree_lease(
         .repo_root
         .clone()
         .ok_or_else(|| "missing repo_root for worktree lease".to_string())?;
-    let output = Command::new("git")
+    let git = resolve_git_executable(&turn.config)?;
+    let output = Command::new(git)
         .arg("-C")
         .arg(&repo_root)
         .args(["worktree", "remove", "--force"])
@@ -1095,16 +1928,19 @@ async fn remove_worktree_lease(
     Ok(())
 }
 
+/// Releases `agent_id`'s worktree lease and returns the path of the worktree it removed, if
+/// any. Shared worktrees are only removed once their last member releases them.
 async fn cleanup_agent_worktree(
     session: &Session,
     turn: &TurnContext,
     agent_id: ThreadId,
-) -> Result<(), String> {
+) -> Result<Option<PathBuf>, String> {
     let Some(lease) = take_worktree_lease(agent_id) else {
-        return Ok(());
+        return Ok(None);
     };
+    let worktree_path = lease.worktree_path.clone();
     match remove_worktree_lease(session, turn, lease.clone()).await {
-        Ok(()) => Ok(()),
+        Ok(()) => Ok(Some(worktree_path)),
         Err(err) => {
             register_worktree_lease(agent_id, lease);
             Err(err)
@@ -1245,6 +2081,22 @@ mod create_team;
 
 mod delete_team;
 
+mod team_status;
+
+mod team_add_member;
+
+mod team_message_wait;
+
+mod team_gc;
+
+mod team_rename;
+
+mod close_all;
+
+mod get_agent_output;
+
+mod list_spawned_agents;
+
 pub mod close_agent {
     use super::*;
     use std::sync::Arc;
@@ -1252,6 +2104,10 @@ pub mod close_agent {
     #[derive(Debug, Deserialize, Serialize)]
     pub(super) struct CloseAgentResult {
         pub(super) status: AgentStatus,
+        /// Set only when `drain_timeout_ms` was requested: `true` when the agent reached a final
+        /// status on its own, `false` when it had to be shut down after the drain timeout.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub(super) drained: Option<bool>,
     }
 
     pub async fn handle(
@@ -1262,6 +2118,15 @@ pub mod close_agent {
     ) -> Result<ToolOutput, FunctionCallError> {
         let args: CloseAgentArgs = parse_arguments(&arguments)?;
         let agent_id = agent_id(&args.id)?;
+        let drain_timeout_ms = match args.drain_timeout_ms {
+            Some(ms) if ms <= 0 => {
+                return Err(FunctionCallError::RespondToModel(
+                    "drain_timeout_ms must be greater than zero".to_string(),
+                ));
+            }
+            Some(ms) => Some(ms.min(MAX_WAIT_TIMEOUT_MS)),
+            None => configured_shutdown_grace_ms(&turn),
+        };
         session
             .send_event(
                 &turn,
@@ -1273,14 +2138,25 @@ pub mod close_agent {
                 .into(),
             )
             .await;
-        let status = match session
+        let (status, drained) = match session
             .services
             .agent_control
             .subscribe_status(agent_id)
             .await
         {
-            Ok(mut status_rx) => status_rx.borrow_and_update().clone(),
-            Err(_) => session.services.agent_control.get_status(agent_id).await,
+            Ok(mut status_rx) => {
+                let status = status_rx.borrow_and_update().clone();
+                match drain_timeout_ms {
+                    Some(timeout_ms) => {
+                        drain_agent(session.clone(), agent_id, status, status_rx, timeout_ms).await
+                    }
+                    None => (status, None),
+                }
+            }
+            Err(_) => (
+                session.services.agent_control.get_status(agent_id).await,
+                drain_timeout_ms.map(|_| true),
+            ),
         };
         let (receiver_agent_nickname, receiver_agent_role) = session
             .services
@@ -1293,6 +2169,20 @@ pub mod close_agent {
             .agent_control
             .shutdown_agent(agent_id)
             .await;
+        // Closing an agent that is already gone (e.g. a repeated close_agent call) is a no-op:
+        // report its terminal status instead of the shutdown error.
+        let (status, result) = match result {
+            Err(_) if !is_closed_status(&status) => {
+                let latest = session.services.agent_control.get_status(agent_id).await;
+                if is_closed_status(&latest) {
+                    (latest, Ok(()))
+                } else {
+                    (status, result.map(|_| ()))
+                }
+            }
+            Err(_) => (status, Ok(())),
+            Ok(_) => (status, Ok(())),
+        };
         session
             .send_event(
                 &turn,
@@ -1307,27 +2197,78 @@ pub mod close_agent {
                 .into(),
             )
             .await;
-        match result {
-            Ok(_) => {}
-            Err(err) => {
-                if !matches!(status, AgentStatus::Shutdown | AgentStatus::NotFound) {
-                    return Err(collab_agent_error(agent_id, err));
-                }
-            }
+        if let Err(err) = result {
+            return Err(collab_agent_error(agent_id, err));
         }
         if let Err(err) = cleanup_agent_worktree(session.as_ref(), turn.as_ref(), agent_id).await {
             return Err(FunctionCallError::RespondToModel(err));
         }
+        if let Err(err) = forget_spawned_agents(
+            turn.config.codex_home.as_path(),
+            session.conversation_id,
+            &[agent_id],
+        )
+        .await
+        {
+            warn!("failed to remove closed agent {agent_id} from spawned agents: {err}");
+        }
 
-        let content = serde_json::to_string(&CloseAgentResult { status }).map_err(|err| {
-            FunctionCallError::Fatal(format!("failed to serialize close_agent result: {err}"))
-        })?;
+        let content =
+            serde_json::to_string(&CloseAgentResult { status, drained }).map_err(|err| {
+                FunctionCallError::Fatal(format!("failed to serialize close_agent result: {err}"))
+            })?;
 
         Ok(ToolOutput::Function {
             body: FunctionCallOutputBody::Text(content),
             success: Some(true),
         })
     }
+
+    /// Drain timeout implied by `agents.shutdown_grace_ms` when a close call does not ask for one.
+    pub(super) fn configured_shutdown_grace_ms(turn: &TurnContext) -> Option<i64> {
+        turn.config.agent_shutdown_grace_ms.map(|ms| {
+            i64::try_from(ms)
+                .unwrap_or(i64::MAX)
+                .min(MAX_WAIT_TIMEOUT_MS)
+        })
+    }
+
+    fn is_closed_status(status: &AgentStatus) -> bool {
+        matches!(status, AgentStatus::Shutdown | AgentStatus::NotFound)
+    }
+
+    /// Interrupts a running agent and waits up to `timeout_ms` for it to settle before the caller
+    /// forces a shutdown. Returns the latest status and whether the agent drained in time.
+    pub(super) async fn drain_agent(
+        session: Arc<Session>,
+        agent_id: ThreadId,
+        status: AgentStatus,
+        status_rx: Receiver<AgentStatus>,
+        timeout_ms: i64,
+    ) -> (AgentStatus, Option<bool>) {
+        if crate::agent::status::is_final(&status) {
+            return (status, Some(true));
+        }
+        if let Err(err) = session
+            .services
+            .agent_control
+            .interrupt_agent(agent_id)
+            .await
+        {
+            debug!("failed to interrupt agent {agent_id} before close: {err}");
+            return (status, Some(false));
+        }
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms as u64);
+        match timeout_at(
+            deadline,
+            wait_for_final_status(session, agent_id, status_rx),
+        )
+        .await
+        {
+            Ok(Some((_, final_status))) => (final_status, Some(true)),
+            Ok(None) | Err(_) => (status, Some(false)),
+        }
+    }
 }
 
 fn agent_id(id: &str) -> Result<ThreadId, FunctionCallError> {
@@ -1335,50 +2276,111 @@ fn agent_id(id: &str) -> Result<ThreadId, FunctionCallError> {
         .map_err(|e| FunctionCallError::RespondToModel(format!("invalid agent id {id}: {e:?}")))
 }
 
+/// Stable, machine-readable category attached to collab tool errors so a caller can branch
+/// its retry logic on the code instead of matching message text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum CollabErrorCode {
+    AgentNotFound,
+    AgentClosed,
+    ManagerUnavailable,
+    AgentLimitReached,
+    DepthLimit,
+    SpawnFailed,
+    ToolFailed,
+}
+
+#[derive(Serialize)]
+struct CollabErrorBody<'a> {
+    code: CollabErrorCode,
+    message: &'a str,
+}
+
+/// Builds a `{"code": ..., "message": ...}` error body for the model.
+fn collab_error(code: CollabErrorCode, message: impl Into<String>) -> FunctionCallError {
+    let message = message.into();
+    let body = serde_json::to_string(&CollabErrorBody {
+        code,
+        message: &message,
+    })
+    .unwrap_or_else(|_| message.clone());
+    FunctionCallError::RespondToModel(body)
+}
+
+fn depth_limit_error() -> FunctionCallError {
+    collab_error(
+        CollabErrorCode::DepthLimit,
+        "Agent depth limit reached. Solve the task yourself.",
+    )
+}
+
 fn collab_spawn_error(err: CodexErr) -> FunctionCallError {
     match err {
-        CodexErr::UnsupportedOperation(_) => {
-            FunctionCallError::RespondToModel("collab manager unavailable".to_string())
-        }
-        err => FunctionCallError::RespondToModel(format!("collab spawn failed: {err}")),
+        CodexErr::UnsupportedOperation(_) => collab_error(
+            CollabErrorCode::ManagerUnavailable,
+            "collab manager unavailable",
+        ),
+        err @ CodexErr::AgentLimitReached { .. } => collab_error(
+            CollabErrorCode::AgentLimitReached,
+            format!(
+                "collab spawn failed: {err}. Wait for running agents to finish or close agents you no longer need before spawning more."
+            ),
+        ),
+        err => collab_error(
+            CollabErrorCode::SpawnFailed,
+            format!("collab spawn failed: {err}"),
+        ),
     }
 }
 
 fn collab_agent_error(agent_id: ThreadId, err: CodexErr) -> FunctionCallError {
     match err {
-        CodexErr::ThreadNotFound(id) => {
-            FunctionCallError::RespondToModel(format!("agent with id {id} not found"))
-        }
-        CodexErr::InternalAgentDied => {
-            FunctionCallError::RespondToModel(format!("agent with id {agent_id} is closed"))
-        }
-        CodexErr::UnsupportedOperation(_) => {
-            FunctionCallError::RespondToModel("collab manager unavailable".to_string())
-        }
-        err => FunctionCallError::RespondToModel(format!("collab tool failed: {err}")),
+        CodexErr::ThreadNotFound(id) => collab_error(
+            CollabErrorCode::AgentNotFound,
+            format!("agent with id {id} not found"),
+        ),
+        CodexErr::InternalAgentDied => collab_error(
+            CollabErrorCode::AgentClosed,
+            format!("agent with id {agent_id} is closed"),
+        ),
+        CodexErr::UnsupportedOperation(_) => collab_error(
+            CollabErrorCode::ManagerUnavailable,
+            "collab manager unavailable",
+        ),
+        err => collab_error(
+            CollabErrorCode::ToolFailed,
+            format!("collab tool failed: {err}"),
+        ),
     }
 }
 
 fn thread_spawn_source(parent_thread_id: ThreadId, depth: i32) -> SessionSource {
-    thread_spawn_source_with_role(parent_thread_id, depth, None)
+    thread_spawn_source_with_identity(parent_thread_id, depth, None, None, None)
 }
 
-fn thread_spawn_source_with_role(
+/// A thread-spawn source carrying the requested nickname and role, plus the id of the tool call
+/// that spawned the agent. `AgentControl` reserves `agent_nickname` as given, or picks one from
+/// the role's candidates when it is `None`.
+fn thread_spawn_source_with_identity(
     parent_thread_id: ThreadId,
     depth: i32,
+    agent_nickname: Option<String>,
     agent_role: Option<String>,
+    call_id: Option<String>,
 ) -> SessionSource {
     SessionSource::SubAgent(SubAgentSource::ThreadSpawn {
         parent_thread_id,
         depth,
-        agent_nickname: None,
+        agent_nickname,
         agent_role,
+        call_id,
     })
 }
 
-fn parse_collab_input(
+async fn parse_collab_input(
     message: Option<String>,
     items: Option<Vec<UserInput>>,
+    turn: &TurnContext,
 ) -> Result<Vec<UserInput>, FunctionCallError> {
     match (message, items) {
         (Some(_), Some(_)) => Err(FunctionCallError::RespondToModel(
@@ -1404,12 +2406,116 @@ fn parse_collab_input(
                     "Items can't be empty".to_string(),
                 ));
             }
-            Ok(items)
+            let roots = collab_input_roots(turn).await;
+            let mut resolved = Vec::with_capacity(items.len());
+            for item in items {
+                resolved.push(resolve_collab_input_path(item, &turn.cwd, roots.as_ref()).await?);
+            }
+            Ok(resolved)
+        }
+    }
+}
+
+/// Canonical directories that local paths in collab input must stay inside: the sender's `cwd`
+/// and sandbox writable roots, plus `codex_home` for skills.
+struct CollabInputRoots {
+    workspace: Vec<PathBuf>,
+    codex_home: PathBuf,
+}
+
+/// Returns `None` when the sandbox grants full disk write access, where any path the sender
+/// can reach is allowed.
+async fn collab_input_roots(turn: &TurnContext) -> Option<CollabInputRoots> {
+    let policy = &turn.file_system_sandbox_policy;
+    if policy.has_full_disk_write_access() {
+        return None;
+    }
+    let mut workspace = vec![canonical_or_raw(&turn.cwd).await];
+    for root in policy.get_writable_roots_with_cwd(&turn.cwd) {
+        workspace.push(canonical_or_raw(root.root.as_path()).await);
+    }
+    Some(CollabInputRoots {
+        workspace,
+        codex_home: canonical_or_raw(&turn.config.codex_home).await,
+    })
+}
+
+async fn canonical_or_raw(path: &Path) -> PathBuf {
+    tokio::fs::canonicalize(path)
+        .await
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Checks that a local path referenced by an input item exists and stays inside `roots`,
+/// resolving relative paths against the sender's `cwd` so the recipient (which may run in
+/// another directory or a worktree) sees the same file. Paths are canonicalized first, so `..`
+/// and symlinks cannot escape. `Mention` targets that are URIs (`app://...`) pass through.
+async fn resolve_collab_input_path(
+    item: UserInput,
+    cwd: &Path,
+    roots: Option<&CollabInputRoots>,
+) -> Result<UserInput, FunctionCallError> {
+    async fn resolve(
+        kind: &str,
+        path: &Path,
+        cwd: &Path,
+        roots: Option<&CollabInputRoots>,
+    ) -> Result<PathBuf, FunctionCallError> {
+        let joined = cwd.join(path);
+        let resolved = tokio::fs::canonicalize(&joined).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "{kind} path `{}` is not accessible: {err}",
+                joined.display()
+            ))
+        })?;
+        if let Some(roots) = roots {
+            let contained = roots
+                .workspace
+                .iter()
+                .chain((kind == "skill").then_some(&roots.codex_home))
+                .any(|root| resolved.starts_with(root));
+            if !contained {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "{kind} path `{}` is outside the workspace and writable roots",
+                    resolved.display()
+                )));
+            }
         }
+        Ok(resolved)
     }
+
+    Ok(match item {
+        UserInput::LocalImage { path } => {
+            let path = resolve("local_image", &path, cwd, roots).await?;
+            let is_file = tokio::fs::metadata(&path)
+                .await
+                .is_ok_and(|metadata| metadata.is_file());
+            if !is_file {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "local_image path `{}` is not a file",
+                    path.display()
+                )));
+            }
+            UserInput::LocalImage { path }
+        }
+        UserInput::Skill { name, path } => UserInput::Skill {
+            path: resolve("skill", &path, cwd, roots).await?,
+            name,
+        },
+        UserInput::Mention { name, path } if !path.contains("://") => UserInput::Mention {
+            path: resolve("mention", Path::new(&path), cwd, roots)
+                .await?
+                .to_string_lossy()
+                .into_owned(),
+            name,
+        },
+        item => item,
+    })
 }
 
-fn input_preview(items: &[UserInput]) -> String {
+/// Renders `items` as the prompt text echoed into collab events, cut to `max_chars` characters
+/// (plus an ellipsis). Only the preview is shortened; agents still receive the full input.
+fn input_preview(items: &[UserInput], max_chars: usize) -> String {
     let parts: Vec<String> = items
         .iter()
         .map(|item| match item {
@@ -1424,7 +2530,11 @@ fn input_preview(items: &[UserInput]) -> String {
         })
         .collect();
 
-    parts.join("\n")
+    let preview = parts.join("\n");
+    match preview.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}...", &preview[..cut]),
+        None => preview,
+    }
 }
 
 /// Builds the base config snapshot for a newly spawned sub-agent.
@@ -1500,6 +2610,121 @@ fn apply_spawn_agent_runtime_overrides(
     Ok(())
 }
 
+/// Orders sandbox policies from most to least restrictive so a spawn can only tighten them.
+fn sandbox_policy_rank(policy: &SandboxPolicy) -> u8 {
+    match policy {
+        SandboxPolicy::ReadOnly { .. } => 0,
+        SandboxPolicy::WorkspaceWrite { .. } => 1,
+        SandboxPolicy::ExternalSandbox { .. } | SandboxPolicy::DangerFullAccess => 2,
+    }
+}
+
+/// Applies a `sandbox` spawn argument (`read-only`, `workspace-write`, or `danger-full-access`)
+/// on top of the parent's policy. Children may be tightened but never loosened past the parent,
+/// and the result must still satisfy the `Constrained` sandbox requirements.
+fn apply_spawn_sandbox_override(
+    config: &mut Config,
+    turn: &TurnContext,
+    sandbox: Option<&str>,
+) -> Result<(), FunctionCallError> {
+    let Some(sandbox) = sandbox.map(str::trim).filter(|sandbox| !sandbox.is_empty()) else {
+        return Ok(());
+    };
+    let parent_policy = turn.sandbox_policy.get();
+    let requested = match sandbox {
+        "read-only" => SandboxPolicy::new_read_only_policy(),
+        "workspace-write" => match parent_policy {
+            SandboxPolicy::WorkspaceWrite { .. } => parent_policy.clone(),
+            _ => SandboxPolicy::new_workspace_write_policy(),
+        },
+        "danger-full-access" => SandboxPolicy::DangerFullAccess,
+        other => {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "unknown sandbox `{other}`; expected read-only, workspace-write, or danger-full-access"
+            )));
+        }
+    };
+    if sandbox_policy_rank(&requested) > sandbox_policy_rank(parent_policy) {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "sandbox `{sandbox}` is less restrictive than the parent's `{parent_policy}`; spawned agents may only tighten the sandbox"
+        )));
+    }
+    if sandbox_policy_rank(&requested) == sandbox_policy_rank(parent_policy) {
+        return Ok(());
+    }
+    config
+        .permissions
+        .sandbox_policy
+        .can_set(&requested)
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!("sandbox `{sandbox}` is not allowed: {err}"))
+        })?;
+    config.permissions.file_system_sandbox_policy = FileSystemSandboxPolicy::from(&requested);
+    config.permissions.network_sandbox_policy = NetworkSandboxPolicy::from(&requested);
+    config
+        .permissions
+        .sandbox_policy
+        .set(requested)
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!("sandbox `{sandbox}` is not allowed: {err}"))
+        })?;
+    Ok(())
+}
+
+/// Rejects `allowed_tools`/`denied_tools` entries (typically from an agent role file) that do not
+/// name a built-in tool, so a typo fails the spawn instead of silently hiding every tool.
+/// Names containing the MCP `server__tool` delimiter are assumed to be MCP tools and skipped.
+fn validate_spawn_tool_filter(
+    config: &Config,
+    turn: &TurnContext,
+    role_name: Option<&str>,
+) -> Result<(), FunctionCallError> {
+    let configured = config
+        .allowed_tools
+        .iter()
+        .flatten()
+        .chain(config.denied_tools.iter());
+    let mut candidates = configured.filter(|name| !name.contains("__")).peekable();
+    if candidates.peek().is_none() {
+        return Ok(());
+    }
+    let known = crate::tools::spec::builtin_tool_names(&turn.tools_config);
+    let unknown = candidates
+        .filter(|name| !known.contains(name.as_str()))
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    let role_name = role_name.unwrap_or(crate::agent::role::DEFAULT_ROLE_NAME);
+    Err(FunctionCallError::RespondToModel(format!(
+        "agent_type '{role_name}' references unknown tools in allowed_tools/denied_tools: {}",
+        unknown.join(", ")
+    )))
+}
+
+/// Checks a per-spawn `max_depth`, which may only tighten the inherited depth limit.
+fn validate_spawn_max_depth(
+    max_depth: Option<i32>,
+    child_depth: i32,
+    inherited_max_depth: i32,
+) -> Result<(), FunctionCallError> {
+    let Some(max_depth) = max_depth else {
+        return Ok(());
+    };
+    if max_depth > inherited_max_depth {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "max_depth cannot exceed the current depth limit of {inherited_max_depth}"
+        )));
+    }
+    if max_depth < child_depth {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "max_depth must be at least {child_depth}, the depth of the new agent"
+        )));
+    }
+    Ok(())
+}
+
 fn apply_spawn_agent_overrides(config: &mut Config, child_depth: i32) {
     if child_depth >= config.agent_max_depth {
         let _ = config.features.disable(Feature::Collab);
@@ -0,0 +1,221 @@
+use super::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+#[derive(Debug, Serialize)]
+struct CloseAllAgentResult {
+    agent_id: String,
+    /// Team and member name, for agents that belonged to one of the closed teams.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    team_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    ok: bool,
+    status: AgentStatus,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CloseAllTeamResult {
+    team_id: String,
+    ok: bool,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CloseAllResult {
+    closed: Vec<CloseAllAgentResult>,
+    teams: Vec<CloseAllTeamResult>,
+    removed_worktrees: Vec<String>,
+}
+
+pub async fn handle(
+    session: Arc<Session>,
+    turn: Arc<TurnContext>,
+    call_id: String,
+    _arguments: String,
+) -> Result<ToolOutput, FunctionCallError> {
+    if let Some(active_team_id) = find_team_for_member(session.conversation_id)? {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "close_all is disabled for agent team teammates (team `{active_team_id}`). Ask the team lead to close agents."
+        )));
+    }
+
+    let lead_thread_id = session.conversation_id;
+    let codex_home = turn.config.codex_home.as_path();
+    let teams = take_team_records(lead_thread_id)?;
+    let mut team_members = HashMap::new();
+    for (team_id, team) in &teams {
+        for member in &team.members {
+            team_members.insert(member.agent_id, (team_id.clone(), member.name.clone()));
+        }
+    }
+
+    // Live children first, then team members that may have been detached from this session,
+    // then recorded agents that still hold a worktree.
+    let mut agent_ids = session
+        .services
+        .agent_control
+        .list_child_thread_ids(lead_thread_id)
+        .await;
+    agent_ids.extend(team_members.keys().copied());
+    for agent in read_spawned_agents(codex_home, lead_thread_id).await {
+        if let Ok(agent_id) = ThreadId::from_string(&agent.agent_id)
+            && has_worktree_lease(agent_id)
+        {
+            agent_ids.push(agent_id);
+        }
+    }
+    let mut seen = HashSet::new();
+    agent_ids.retain(|agent_id| seen.insert(*agent_id));
+
+    let mut receiver_agents = Vec::with_capacity(agent_ids.len());
+    for agent_id in &agent_ids {
+        let (agent_nickname, agent_role) = session
+            .services
+            .agent_control
+            .get_agent_nickname_and_role(*agent_id)
+            .await
+            .unwrap_or((None, None));
+        receiver_agents.push(CollabAgentRef {
+            thread_id: *agent_id,
+            agent_nickname,
+            agent_role,
+        });
+    }
+    let event_call_id = prefixed_team_call_id(TEAM_CLOSE_CALL_PREFIX, &call_id);
+    session
+        .send_event(
+            &turn,
+            CollabWaitingBeginEvent {
+                sender_thread_id: lead_thread_id,
+                receiver_thread_ids: agent_ids.clone(),
+                receiver_agents: receiver_agents.clone(),
+                call_id: event_call_id.clone(),
+            }
+            .into(),
+        )
+        .await;
+
+    // No drain here: this is the emergency stop, so every agent is shut down immediately.
+    let mut statuses = HashMap::new();
+    let mut agent_statuses = Vec::with_capacity(agent_ids.len());
+    let mut closed = Vec::with_capacity(agent_ids.len());
+    let mut removed_worktrees = Vec::new();
+    for (agent_id, agent_ref) in agent_ids.iter().copied().zip(receiver_agents) {
+        let status_before = session.services.agent_control.get_status(agent_id).await;
+        let shutdown_result = session
+            .services
+            .agent_control
+            .shutdown_agent(agent_id)
+            .await;
+        let close_error = match shutdown_result {
+            Err(err) if !matches!(status_before, AgentStatus::Shutdown | AgentStatus::NotFound) => {
+                Some(err.to_string())
+            }
+            _ => None,
+        };
+        let cleanup_error =
+            match cleanup_agent_worktree(session.as_ref(), turn.as_ref(), agent_id).await {
+                Ok(removed) => {
+                    removed_worktrees.extend(removed.map(|path| path.display().to_string()));
+                    None
+                }
+                Err(err) => Some(err),
+            };
+
+        let event_status = match (&status_before, &close_error) {
+            (AgentStatus::NotFound, None) => AgentStatus::NotFound,
+            (_, None) => AgentStatus::Shutdown,
+            (_, Some(_)) => session.services.agent_control.get_status(agent_id).await,
+        };
+        statuses.insert(agent_id, event_status.clone());
+        agent_statuses.push(CollabAgentStatusEntry {
+            thread_id: agent_id,
+            agent_nickname: agent_ref.agent_nickname,
+            agent_role: agent_ref.agent_role,
+            status: event_status,
+        });
+
+        let error = match (close_error, cleanup_error) {
+            (None, None) => None,
+            (Some(err), None) | (None, Some(err)) => Some(err),
+            (Some(err), Some(cleanup_err)) => Some(format!("{err}; {cleanup_err}")),
+        };
+        let (team_id, name) = team_members
+            .remove(&agent_id)
+            .map_or((None, None), |(team_id, name)| (Some(team_id), Some(name)));
+        closed.push(CloseAllAgentResult {
+            agent_id: agent_id.to_string(),
+            team_id,
+            name,
+            ok: error.is_none(),
+            status: status_before,
+            error,
+        });
+    }
+
+    let closed_ids = agent_ids
+        .iter()
+        .zip(&closed)
+        .filter(|(_, result)| result.ok)
+        .map(|(agent_id, _)| *agent_id)
+        .collect::<Vec<_>>();
+    if let Err(err) = forget_spawned_agents(codex_home, lead_thread_id, &closed_ids).await {
+        warn!("failed to remove closed agents from spawned agents: {err}");
+    }
+
+    // Teams persisted by an earlier process are not in the registry but are removed as well.
+    let mut team_ids = teams.into_keys().collect::<Vec<_>>();
+    match list_persisted_teams(codex_home).await {
+        Ok(persisted) => team_ids.extend(
+            persisted
+                .into_iter()
+                .filter(|config| config.lead_thread_id == lead_thread_id.to_string())
+                .map(|config| config.team_name),
+        ),
+        Err(err) => warn!("failed to list persisted teams for close_all: {err}"),
+    }
+    team_ids.sort();
+    team_ids.dedup();
+    let mut team_results = Vec::with_capacity(team_ids.len());
+    for team_id in team_ids {
+        let error = remove_team_persistence(codex_home, lead_thread_id, &team_id)
+            .await
+            .err()
+            .map(|err| err.to_string());
+        team_results.push(CloseAllTeamResult {
+            team_id,
+            ok: error.is_none(),
+            error,
+        });
+    }
+
+    session
+        .send_event(
+            &turn,
+            CollabWaitingEndEvent {
+                sender_thread_id: lead_thread_id,
+                call_id: event_call_id,
+                agent_statuses,
+                statuses,
+            }
+            .into(),
+        )
+        .await;
+
+    let content = serde_json::to_string(&CloseAllResult {
+        closed,
+        teams: team_results,
+        removed_worktrees,
+    })
+    .map_err(|err| {
+        FunctionCallError::Fatal(format!("failed to serialize close_all result: {err}"))
+    })?;
+
+    Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success: Some(true),
+    })
+}
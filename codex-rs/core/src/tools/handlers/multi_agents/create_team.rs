@@ -8,19 +8,40 @@ use std::sync::Arc;
 struct SpawnTeamArgs {
     team_id: Option<String>,
     members: Vec<SpawnTeamMemberArgs>,
+    #[serde(default)]
+    shared_worktree: bool,
+    /// Keep the members that spawned when others fail instead of tearing the team down.
+    #[serde(default)]
+    best_effort: bool,
+    /// Validate the members and report the would-be team without spawning anything.
+    #[serde(default)]
+    dry_run: bool,
+    /// Upper bound on the time to bring every member up; on expiry the team is torn down.
+    spawn_timeout_ms: Option<u64>,
+    /// Team-wide values for members that leave `model`, `model_provider`, or `agent_type` unset.
+    default_model: Option<String>,
+    default_model_provider: Option<String>,
+    default_agent_type: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub(super) struct SpawnTeamMemberArgs {
     pub(super) name: String,
+    /// Display name for the member's thread; defaults to `name`.
+    pub(super) nickname: Option<String>,
     pub(super) task: String,
     pub(super) agent_type: Option<String>,
     pub(super) model_provider: Option<String>,
+    #[serde(default)]
+    pub(super) model_provider_fallbacks: Vec<String>,
     pub(super) model: Option<String>,
+    pub(super) reasoning_effort: Option<ReasoningEffort>,
+    pub(super) reasoning_summary: Option<ReasoningSummary>,
     #[serde(default)]
     pub(super) worktree: bool,
     #[serde(default, alias = "backendground")]
     pub(super) background: bool,
+    pub(super) max_depth: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -28,12 +49,71 @@ struct SpawnTeamMemberResult {
     name: String,
     agent_id: String,
     status: AgentStatus,
+    cwd: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worktree_path: Option<String>,
+}
+
+/// A member spawned by [`spawn_team_member`] together with where it is working.
+pub(super) struct SpawnedTeamMember {
+    pub(super) member: TeamMember,
+    pub(super) cwd: String,
+    pub(super) worktree_path: Option<String>,
+}
+
+/// Frees `team_id` when the only thing holding it is state persisted by a process that
+/// is gone. Teams that still have live members keep the id.
+async fn reclaim_orphaned_team_id(
+    session: &Session,
+    turn: &TurnContext,
+    team_id: &str,
+) -> Result<(), FunctionCallError> {
+    let codex_home = turn.config.codex_home.as_path();
+    let Some(config) =
+        find_persisted_team_config(codex_home, session.conversation_id, team_id).await?
+    else {
+        return Ok(());
+    };
+    match reconcile_persisted_team(session, codex_home, config).await? {
+        TeamReconcileOutcome::Removed => Ok(()),
+        TeamReconcileOutcome::Active
+        | TeamReconcileOutcome::Reclaimed
+        | TeamReconcileOutcome::Foreign => Err(FunctionCallError::RespondToModel(format!(
+            "team `{team_id}` already exists"
+        ))),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DryRunTeamMember {
+    name: String,
+    task: String,
+    agent_type: Option<String>,
+    model_provider: String,
+    model: Option<String>,
+    reasoning_effort: Option<ReasoningEffort>,
+    worktree: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct DryRunTeamResult {
+    team_id: String,
+    dry_run: bool,
+    members: Vec<DryRunTeamMember>,
+}
+
+#[derive(Debug, Serialize)]
+struct SpawnTeamMemberFailure {
+    name: String,
+    error: String,
 }
 
 #[derive(Debug, Serialize)]
 struct SpawnTeamResult {
     team_id: String,
     members: Vec<SpawnTeamMemberResult>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failed: Vec<SpawnTeamMemberFailure>,
 }
 
 pub async fn handle(
@@ -45,6 +125,13 @@ pub async fn handle(
     let SpawnTeamArgs {
         team_id: provided_team_id,
         members: requested_members,
+        shared_worktree,
+        best_effort,
+        dry_run,
+        spawn_timeout_ms,
+        default_model,
+        default_model_provider,
+        default_agent_type,
     } = parse_arguments(&arguments)?;
     if let Some(team_id) = find_team_for_member(session.conversation_id)? {
         return Err(FunctionCallError::RespondToModel(format!(
@@ -56,37 +143,94 @@ pub async fn handle(
             "members must be non-empty".to_string(),
         ));
     }
+    if spawn_timeout_ms == Some(0) {
+        return Err(FunctionCallError::RespondToModel(
+            "spawn_timeout_ms must be greater than zero".to_string(),
+        ));
+    }
+
+    // Resolve team defaults up front so validation, dry runs, and spawning all see the same
+    // member config.
+    let mut requested_members = requested_members;
+    for member in &mut requested_members {
+        member.model = member.model.take().or_else(|| default_model.clone());
+        member.model_provider = member
+            .model_provider
+            .take()
+            .or_else(|| default_model_provider.clone());
+        member.agent_type = member
+            .agent_type
+            .take()
+            .or_else(|| default_agent_type.clone());
+    }
 
     let mut seen_names = HashSet::new();
     for member in &requested_members {
-        let name = member.name.trim();
-        if name.is_empty() {
-            return Err(FunctionCallError::RespondToModel(
-                "member name must be non-empty".to_string(),
-            ));
-        }
+        let name = validate_team_member_args(member)?;
         if !seen_names.insert(name.to_string()) {
             return Err(FunctionCallError::RespondToModel(format!(
                 "duplicate member name `{name}`"
             )));
         }
-        if member.task.trim().is_empty() {
-            return Err(FunctionCallError::RespondToModel(format!(
-                "task for member `{name}` must be non-empty"
-            )));
-        }
     }
 
     let team_id = match provided_team_id {
-        Some(team_id) => normalized_team_id(&team_id)?,
+        Some(team_id) => {
+            let team_id = normalized_team_id(&team_id)?;
+            if dry_run {
+                // Dry runs must not touch persisted state, so only the live registry is checked.
+                if get_team_record(session.conversation_id, &team_id).is_ok() {
+                    return Err(FunctionCallError::RespondToModel(format!(
+                        "team `{team_id}` already exists"
+                    )));
+                }
+            } else {
+                reclaim_orphaned_team_id(&session, &turn, &team_id).await?;
+            }
+            team_id
+        }
         None => ThreadId::new().to_string(),
     };
 
     let child_depth = next_thread_spawn_depth(&turn.session_source);
     if exceeds_thread_spawn_depth_limit(child_depth, turn.config.agent_max_depth) {
-        return Err(FunctionCallError::RespondToModel(
-            "Agent depth limit reached. Solve the task yourself.".to_string(),
-        ));
+        return Err(depth_limit_error());
+    }
+    for member in &requested_members {
+        validate_spawn_max_depth(member.max_depth, child_depth, turn.config.agent_max_depth)?;
+    }
+    // Every member is validated before anything spawns. Best-effort teams set invalid members
+    // aside as failures; otherwise the first invalid member fails the whole call.
+    let prepared = prepare_team_member_configs(
+        &session,
+        &turn,
+        &requested_members,
+        child_depth,
+        shared_worktree,
+    )
+    .await?;
+    let mut failed = Vec::new();
+    let mut member_configs = Vec::with_capacity(requested_members.len());
+    let mut valid_members = Vec::with_capacity(requested_members.len());
+    for (member, config) in requested_members.into_iter().zip(prepared) {
+        match config {
+            Ok(config) => {
+                member_configs.push(config);
+                valid_members.push(member);
+            }
+            Err(err) if best_effort && !dry_run => failed.push(SpawnTeamMemberFailure {
+                name: member.name.trim().to_string(),
+                error: err.to_string(),
+            }),
+            Err(err) => return Err(err),
+        }
+    }
+    let requested_members = valid_members;
+    if dry_run {
+        return dry_run_team(team_id, &requested_members, member_configs);
+    }
+    if requested_members.is_empty() {
+        return Err(no_members_spawned_error(&failed));
     }
     let created_at = now_unix_seconds();
 
@@ -104,108 +248,102 @@ pub async fn handle(
         )
         .await;
 
+    let shared_worktree_lease = if shared_worktree {
+        match create_agent_worktree(&session, &turn).await {
+            Ok(lease) => Some(lease),
+            Err(err) => {
+                session
+                    .send_event(
+                        &turn,
+                        CollabWaitingEndEvent {
+                            sender_thread_id: session.conversation_id,
+                            call_id: event_call_id,
+                            agent_statuses: Vec::new(),
+                            statuses: HashMap::new(),
+                        }
+                        .into(),
+                    )
+                    .await;
+                return Err(err);
+            }
+        }
+    } else {
+        None
+    };
+
     let mut statuses = HashMap::new();
+    let mut locations = HashMap::new();
     let mut spawned_members = Vec::new();
+    let spawn_deadline = spawn_timeout_ms.map(|ms| Instant::now() + Duration::from_millis(ms));
 
-    for member in &requested_members {
-        let member_name = member.name.trim().to_string();
-        let role_name = optional_non_empty(&member.agent_type, "agent_type")?;
-        let model_provider = optional_non_empty(&member.model_provider, "model_provider")?;
-        let model = optional_non_empty(&member.model, "model")?;
-        let use_worktree = member.worktree;
-        let background = member.background;
-
-        let mut config = build_agent_spawn_config(
-            &session.get_base_instructions().await,
-            turn.as_ref(),
-            child_depth,
-        )?;
-        if let Err(err) = apply_role_to_config(&mut config, role_name).await {
-            let should_ignore_unknown_role = role_name
-                .map(|member_role| err == format!("unknown agent_type '{member_role}'"))
-                .unwrap_or(false);
-            if !should_ignore_unknown_role {
-                return Err(FunctionCallError::RespondToModel(err));
-            }
-        }
-        apply_member_model_overrides(&mut config, model_provider, model)?;
-        apply_spawn_agent_runtime_overrides(&mut config, turn.as_ref())?;
-        apply_spawn_agent_overrides(&mut config, child_depth);
-        let worktree_lease = if use_worktree {
-            match create_agent_worktree(&session, &turn).await {
-                Ok(lease) => {
-                    config.cwd = lease.worktree_path.clone();
-                    Some(lease)
-                }
-                Err(err) => {
-                    cleanup_spawned_team_members(&session, &turn, &spawned_members).await;
-                    let agent_statuses = team_member_status_entries(&spawned_members, &statuses);
-                    session
-                        .send_event(
-                            &turn,
-                            CollabWaitingEndEvent {
-                                sender_thread_id: session.conversation_id,
-                                call_id: event_call_id,
-                                agent_statuses,
-                                statuses,
-                            }
-                            .into(),
-                        )
-                        .await;
-                    return Err(err);
+    for (member, config) in requested_members.iter().zip(member_configs) {
+        // Per-member spawn events let clients show members coming up one at a time; the
+        // surrounding waiting events still report the team as a whole.
+        let member_call_id = format!("{event_call_id}/{}", member.name.trim());
+        let prompt = member.task.trim().to_string();
+        session
+            .send_event(
+                &turn,
+                CollabAgentSpawnBeginEvent {
+                    call_id: member_call_id.clone(),
+                    sender_thread_id: session.conversation_id,
+                    prompt: prompt.clone(),
                 }
-            }
-        } else {
-            None
-        };
-
-        let input_items = vec![UserInput::Text {
-            text: member.task.trim().to_string(),
-            text_elements: Vec::new(),
-        }];
-        let spawn_result = session
-            .services
-            .agent_control
-            .spawn_agent_thread(
-                config.clone(),
-                Some(thread_spawn_source_with_role(
-                    session.conversation_id,
-                    child_depth,
-                    role_name.map(str::to_owned),
-                )),
+                .into(),
             )
             .await;
-        let spawn_result = match spawn_result {
-            Ok(result) => Ok(result),
-            Err(err @ CodexErr::AgentLimitReached { .. }) => {
-                if reap_finished_agents_for_slots(session.as_ref(), turn.as_ref(), 1).await == 0 {
-                    Err(err)
-                } else {
-                    session
-                        .services
-                        .agent_control
-                        .spawn_agent_thread(
-                            config,
-                            Some(thread_spawn_source_with_role(
-                                session.conversation_id,
-                                child_depth,
-                                role_name.map(str::to_owned),
-                            )),
-                        )
-                        .await
-                }
-            }
-            Err(err) => Err(err),
-        }
-        .map_err(collab_spawn_error);
-
-        let (agent_id, notification_source) = match spawn_result {
-            Ok((agent_id, notification_source)) => (agent_id, notification_source),
+        let spawn_result = spawn_team_member_before(
+            &session,
+            &turn,
+            &call_id,
+            member,
+            config,
+            child_depth,
+            shared_worktree_lease.as_ref(),
+            spawn_deadline,
+        )
+        .await;
+        let timed_out = spawn_result.is_err();
+        let spawned = match spawn_result.unwrap_or_else(|_| {
+            Err(FunctionCallError::RespondToModel(format!(
+                "create_team exceeded spawn_timeout_ms ({} ms) while spawning member `{}`; members that already spawned were shut down",
+                spawn_timeout_ms.unwrap_or_default(),
+                member.name.trim()
+            )))
+        }) {
+            Ok(spawned) => spawned,
             Err(err) => {
-                if let Some(lease) = worktree_lease {
-                    let _ = remove_worktree_lease(&session, &turn, lease).await;
+                session
+                    .send_event(
+                        &turn,
+                        CollabAgentSpawnEndEvent {
+                            call_id: member_call_id,
+                            sender_thread_id: session.conversation_id,
+                            new_thread_id: None,
+                            new_agent_nickname: None,
+                            new_agent_role: None,
+                            prompt,
+                            status: AgentStatus::NotFound,
+                        }
+                        .into(),
+                    )
+                    .await;
+                // A timeout ends the whole operation, even in best-effort mode.
+                if best_effort && !timed_out {
+                    failed.push(SpawnTeamMemberFailure {
+                        name: member.name.trim().to_string(),
+                        error: err.to_string(),
+                    });
+                    continue;
                 }
                 cleanup_spawned_team_members(&session, &turn, &spawned_members).await;
+                // Members release the shared worktree as they are cleaned up; it is only left
+                // behind when none of them registered it.
+                if spawned_members.is_empty()
+                    && let Some(lease) = shared_worktree_lease
+                {
+                    let _ = remove_worktree_lease(&session, &turn, lease).await;
+                }
                 let agent_statuses = team_member_status_entries(&spawned_members, &statuses);
                 session
                     .send_event(
@@ -223,89 +361,53 @@ pub async fn handle(
             }
         };
 
-        let hook_context = dispatch_subagent_start_hook(
-            session.as_ref(),
-            turn.as_ref(),
-            agent_id,
-            role_name.unwrap_or("default"),
-        )
-        .await;
-        if !hook_context.is_empty() {
-            let injected = hook_context.join("\n\n");
-            if let Err(err) = session
-                .services
-                .agent_control
-                .inject_developer_message_without_turn(agent_id, injected)
-                .await
-            {
-                warn!("failed to inject subagent_start hook context: {err}");
-            }
-        }
-
-        if let Some(memory) = crate::agent::memory::read_agent_memory(
-            turn.config.codex_home.as_path(),
-            role_name.unwrap_or("default"),
-        )
-        .await
-        {
-            let memory_prompt = format!(
-                "# Agent Memory\nThe following is your persistent memory from previous sessions:\n\n{memory}"
-            );
-            if let Err(err) = session
-                .services
-                .agent_control
-                .inject_developer_message_without_turn(agent_id, memory_prompt)
-                .await
-            {
-                warn!("failed to inject agent memory: {err}");
-            }
-        }
-
-        if let Err(err) = session
+        let SpawnedTeamMember {
+            member: spawned,
+            cwd,
+            worktree_path,
+        } = spawned;
+        let status = session
             .services
             .agent_control
-            .send_spawn_input(agent_id, input_items, notification_source)
-            .await
-        {
-            if let Some(lease) = worktree_lease {
-                let _ = remove_worktree_lease(&session, &turn, lease).await;
-            }
-            let _ = session
-                .services
-                .agent_control
-                .shutdown_agent(agent_id)
-                .await;
-            cleanup_spawned_team_members(&session, &turn, &spawned_members).await;
-            let agent_statuses = team_member_status_entries(&spawned_members, &statuses);
-            session
-                .send_event(
-                    &turn,
-                    CollabWaitingEndEvent {
-                        sender_thread_id: session.conversation_id,
-                        call_id: event_call_id,
-                        agent_statuses,
-                        statuses,
-                    }
-                    .into(),
-                )
-                .await;
-            return Err(collab_spawn_error(err));
-        }
-
-        if let Some(lease) = worktree_lease {
-            register_worktree_lease(agent_id, lease);
-        }
-        if background {
-            maybe_start_background_agent_cleanup(session.clone(), turn.clone(), agent_id);
+            .get_status(spawned.agent_id)
+            .await;
+        session
+            .send_event(
+                &turn,
+                CollabAgentSpawnEndEvent {
+                    call_id: member_call_id,
+                    sender_thread_id: session.conversation_id,
+                    new_thread_id: Some(spawned.agent_id),
+                    new_agent_nickname: Some(spawned.name.clone()),
+                    new_agent_role: spawned.agent_type.clone(),
+                    prompt,
+                    status: status.clone(),
+                }
+                .into(),
+            )
+            .await;
+        statuses.insert(spawned.agent_id, status);
+        locations.insert(spawned.agent_id, (cwd, worktree_path));
+        spawned_members.push(spawned);
+    }
+    if spawned_members.is_empty() {
+        // Only reachable in best-effort mode, when every member failed to spawn.
+        if let Some(lease) = shared_worktree_lease {
+            let _ = remove_worktree_lease(&session, &turn, lease).await;
         }
-
-        let status = session.services.agent_control.get_status(agent_id).await;
-        statuses.insert(agent_id, status);
-        spawned_members.push(TeamMember {
-            name: member_name,
-            agent_id,
-            agent_type: member.agent_type.clone(),
-        });
+        session
+            .send_event(
+                &turn,
+                CollabWaitingEndEvent {
+                    sender_thread_id: session.conversation_id,
+                    call_id: event_call_id,
+                    agent_statuses: Vec::new(),
+                    statuses,
+                }
+                .into(),
+            )
+            .await;
+        return Err(no_members_spawned_error(&failed));
     }
     let team_record = TeamRecord {
         members: spawned_members.clone(),
@@ -342,7 +444,12 @@ pub async fn handle(
     .await
     {
         let _ = remove_team_record(session.conversation_id, &team_id);
-        let _ = remove_team_persistence(turn.config.codex_home.as_path(), &team_id).await;
+        let _ = remove_team_persistence(
+            turn.config.codex_home.as_path(),
+            session.conversation_id,
+            &team_id,
+        )
+        .await;
         cleanup_spawned_team_members(&session, &turn, &spawned_members).await;
         let agent_statuses = team_member_status_entries(&spawned_members, &statuses);
         session
@@ -415,16 +522,26 @@ pub async fn handle(
 
     let members = spawned_members
         .into_iter()
-        .map(|member| SpawnTeamMemberResult {
-            status: statuses
-                .get(&member.agent_id)
-                .cloned()
-                .unwrap_or(AgentStatus::NotFound),
-            name: member.name,
-            agent_id: member.agent_id.to_string(),
+        .map(|member| {
+            let (cwd, worktree_path) = locations.remove(&member.agent_id).unwrap_or_default();
+            SpawnTeamMemberResult {
+                status: statuses
+                    .get(&member.agent_id)
+                    .cloned()
+                    .unwrap_or(AgentStatus::NotFound),
+                name: member.name,
+                agent_id: member.agent_id.to_string(),
+                cwd,
+                worktree_path,
+            }
         })
         .collect::<Vec<_>>();
-    let content = serde_json::to_string(&SpawnTeamResult { team_id, members }).map_err(|err| {
+    let result = SpawnTeamResult {
+        team_id,
+        members,
+        failed,
+    };
+    let content = serde_json::to_string(&result).map_err(|err| {
         FunctionCallError::Fatal(format!("failed to serialize create_team result: {err}"))
     })?;
 
@@ -433,3 +550,380 @@ pub async fn handle(
         success: Some(true),
     })
 }
+
+/// Checks the per-member fields shared by `create_team` and `team_add_member` and returns the
+/// trimmed member name.
+pub(super) fn validate_team_member_args(
+    member: &SpawnTeamMemberArgs,
+) -> Result<&str, FunctionCallError> {
+    let name = member.name.trim();
+    if name.is_empty() {
+        return Err(FunctionCallError::RespondToModel(
+            "member name must be non-empty".to_string(),
+        ));
+    }
+    if member.task.trim().is_empty() {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "task for member `{name}` must be non-empty"
+        )));
+    }
+    Ok(name)
+}
+
+fn no_members_spawned_error(failed: &[SpawnTeamMemberFailure]) -> FunctionCallError {
+    let errors = failed
+        .iter()
+        .map(|failure| format!("{}: {}", failure.name, failure.error))
+        .collect::<Vec<_>>()
+        .join("; ");
+    FunctionCallError::RespondToModel(format!("no team members could be spawned ({errors})"))
+}
+
+/// Resolves every member's config, which is later handed to [`spawn_team_member`] unchanged,
+/// and checks worktree prerequisites, without launching agents or creating worktrees. Returns
+/// one result per member; only a failed shared-worktree check fails the whole call.
+async fn prepare_team_member_configs(
+    session: &Session,
+    turn: &TurnContext,
+    requested_members: &[SpawnTeamMemberArgs],
+    child_depth: i32,
+    shared_worktree: bool,
+) -> Result<Vec<Result<Config, FunctionCallError>>, FunctionCallError> {
+    if shared_worktree {
+        precheck_agent_worktree(session, turn)?;
+    }
+
+    let mut configs = Vec::with_capacity(requested_members.len());
+    for member in requested_members {
+        let config = match optional_non_empty(&member.nickname, "nickname") {
+            Ok(_) => build_team_member_config(session, turn, member, child_depth).await,
+            Err(err) => Err(err),
+        };
+        configs.push(match config {
+            Ok(config) if member.worktree && !shared_worktree => {
+                precheck_agent_worktree(session, turn).map(|()| config)
+            }
+            config => config,
+        });
+    }
+    Ok(configs)
+}
+
+/// Reports the plan for members resolved by [`prepare_team_member_configs`], without spawning
+/// agents, creating worktrees, persisting the team, or emitting events.
+fn dry_run_team(
+    team_id: String,
+    requested_members: &[SpawnTeamMemberArgs],
+    member_configs: Vec<Config>,
+) -> Result<ToolOutput, FunctionCallError> {
+    let mut members = Vec::with_capacity(requested_members.len());
+    for (member, config) in requested_members.iter().zip(member_configs) {
+        members.push(DryRunTeamMember {
+            name: member.name.trim().to_string(),
+            task: member.task.trim().to_string(),
+            agent_type: member.agent_type.clone(),
+            model_provider: config.model_provider_id,
+            model: config.model,
+            reasoning_effort: config.model_reasoning_effort,
+            worktree: member.worktree,
+        });
+    }
+
+    let result = DryRunTeamResult {
+        team_id,
+        dry_run: true,
+        members,
+    };
+    let content = serde_json::to_string(&result).map_err(|err| {
+        FunctionCallError::Fatal(format!("failed to serialize create_team result: {err}"))
+    })?;
+
+    Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success: Some(true),
+    })
+}
+
+/// Resolves the child config for a team member: role, tool filter, model and reasoning
+/// overrides, and the runtime state inherited from `turn`. Shared by real spawns and dry runs
+/// so both report the same errors.
+pub(super) async fn build_team_member_config(
+    session: &Session,
+    turn: &TurnContext,
+    member: &SpawnTeamMemberArgs,
+    child_depth: i32,
+) -> Result<Config, FunctionCallError> {
+    let role_name = optional_non_empty(&member.agent_type, "agent_type")?;
+    let model_provider = optional_non_empty(&member.model_provider, "model_provider")?;
+    let model = optional_non_empty(&member.model, "model")?;
+
+    let mut config =
+        build_agent_spawn_config(&session.get_base_instructions().await, turn, child_depth)?;
+    if let Err(err) = apply_role_to_config(&mut config, role_name).await {
+        let should_ignore_unknown_role = role_name
+            .map(|member_role| err == format!("unknown agent_type '{member_role}'"))
+            .unwrap_or(false);
+        if !should_ignore_unknown_role {
+            return Err(FunctionCallError::RespondToModel(err));
+        }
+    }
+    validate_spawn_tool_filter(&config, turn, role_name)?;
+    apply_member_model_overrides(
+        &mut config,
+        model_provider,
+        &member.model_provider_fallbacks,
+        model,
+    )?;
+    apply_member_reasoning_overrides(
+        &mut config,
+        member.reasoning_effort,
+        member.reasoning_summary,
+    );
+    apply_spawn_agent_runtime_overrides(&mut config, turn)?;
+    if let Some(max_depth) = member.max_depth {
+        config.agent_max_depth = max_depth;
+    }
+    apply_spawn_agent_overrides(&mut config, child_depth);
+    Ok(config)
+}
+
+/// Runs [`spawn_team_member`], giving up at `deadline` when one is set. A member that is
+/// still coming up at the deadline keeps spawning in the background and is shut down as
+/// soon as it finishes, so it never outlives the failed call.
+async fn spawn_team_member_before(
+    session: &Arc<Session>,
+    turn: &Arc<TurnContext>,
+    call_id: &str,
+    member: &SpawnTeamMemberArgs,
+    config: Config,
+    child_depth: i32,
+    shared_worktree: Option<&WorktreeLease>,
+    deadline: Option<Instant>,
+) -> Result<Result<SpawnedTeamMember, FunctionCallError>, tokio::time::error::Elapsed> {
+    let Some(deadline) = deadline else {
+        return Ok(spawn_team_member(
+            session,
+            turn,
+            call_id,
+            member,
+            config,
+            child_depth,
+            shared_worktree,
+        )
+        .await);
+    };
+    let mut spawn_task = {
+        let session = Arc::clone(session);
+        let turn = Arc::clone(turn);
+        let call_id = call_id.to_string();
+        let member = member.clone();
+        let shared_worktree = shared_worktree.cloned();
+        tokio::spawn(async move {
+            spawn_team_member(
+                &session,
+                &turn,
+                &call_id,
+                &member,
+                config,
+                child_depth,
+                shared_worktree.as_ref(),
+            )
+            .await
+        })
+    };
+    match timeout_at(deadline, &mut spawn_task).await {
+        Ok(joined) => Ok(joined.unwrap_or_else(|err| {
+            Err(FunctionCallError::Fatal(format!(
+                "team member spawn task failed: {err}"
+            )))
+        })),
+        Err(elapsed) => {
+            let session = Arc::clone(session);
+            let turn = Arc::clone(turn);
+            tokio::spawn(async move {
+                if let Ok(Ok(spawned)) = spawn_task.await {
+                    cleanup_spawned_team_members(&session, &turn, &[spawned.member]).await;
+                }
+            });
+            Err(elapsed)
+        }
+    }
+}
+
+/// Spawns one team member and sends it its initial task. `call_id` is the
+/// `create_team`/`team_add_member` call doing so and is recorded in the member's session source.
+///
+/// `config` is the member's config from [`build_team_member_config`], so the config that was
+/// validated is the one that spawns. When `shared_worktree` is set the member works in that
+/// worktree instead of creating its own. On failure any worktree or thread created for this
+/// member is cleaned up; members spawned earlier and the shared worktree are left to the caller.
+pub(super) async fn spawn_team_member(
+    session: &Arc<Session>,
+    turn: &Arc<TurnContext>,
+    call_id: &str,
+    member: &SpawnTeamMemberArgs,
+    mut config: Config,
+    child_depth: i32,
+    shared_worktree: Option<&WorktreeLease>,
+) -> Result<SpawnedTeamMember, FunctionCallError> {
+    let member_name = member.name.trim().to_string();
+    let nickname = optional_non_empty(&member.nickname, "nickname")?
+        .unwrap_or(member_name.as_str())
+        .to_string();
+    let role_name = optional_non_empty(&member.agent_type, "agent_type")?;
+    let worktree_lease = match shared_worktree {
+        Some(_) => None,
+        None if member.worktree => Some(create_agent_worktree(session, turn).await?),
+        None => None,
+    };
+    if let Some(lease) = worktree_lease.as_ref().or(shared_worktree) {
+        config.cwd = lease.worktree_path.clone();
+        config
+            .permissions
+            .shell_environment_policy
+            .r#set
+            .extend(lease.env.clone());
+    }
+    let cwd = config.cwd.display().to_string();
+    let worktree_path = worktree_lease
+        .as_ref()
+        .or(shared_worktree)
+        .map(|lease| lease.worktree_path.display().to_string());
+
+    let input_items = vec![UserInput::Text {
+        text: member.task.trim().to_string(),
+        text_elements: Vec::new(),
+    }];
+    let spawn_result = session
+        .services
+        .agent_control
+        .spawn_agent_thread(
+            config.clone(),
+            Some(thread_spawn_source_with_identity(
+                session.conversation_id,
+                child_depth,
+                Some(nickname.clone()),
+                role_name.map(str::to_owned),
+                Some(call_id.to_string()),
+            )),
+        )
+        .await;
+    let spawn_result = match spawn_result {
+        Ok(result) => Ok(result),
+        Err(err @ CodexErr::AgentLimitReached { .. }) => {
+            if reap_finished_agents_for_slots(session.as_ref(), turn.as_ref(), 1).await == 0 {
+                Err(err)
+            } else {
+                session
+                    .services
+                    .agent_control
+                    .spawn_agent_thread(
+                        config,
+                        Some(thread_spawn_source_with_identity(
+                            session.conversation_id,
+                            child_depth,
+                            Some(nickname),
+                            role_name.map(str::to_owned),
+                            Some(call_id.to_string()),
+                        )),
+                    )
+                    .await
+            }
+        }
+        Err(err) => Err(err),
+    }
+    .map_err(collab_spawn_error);
+
+    let (agent_id, notification_source) = match spawn_result {
+        Ok((agent_id, notification_source)) => (agent_id, notification_source),
+        Err(err) => {
+            if let Some(lease) = worktree_lease {
+                let _ = remove_worktree_lease(session, turn, lease).await;
+            }
+            return Err(err);
+        }
+    };
+    let spawned_at = now_unix_seconds();
+
+    let (hook_context, hook_env) = dispatch_subagent_start_hook(
+        session.as_ref(),
+        turn.as_ref(),
+        agent_id,
+        role_name.unwrap_or("default"),
+    )
+    .await;
+    if !hook_context.is_empty() {
+        let injected = hook_context.join("\n\n");
+        if let Err(err) = session
+            .services
+            .agent_control
+            .inject_developer_message_without_turn(agent_id, injected)
+            .await
+        {
+            warn!("failed to inject subagent_start hook context: {err}");
+        }
+    }
+    if !hook_env.is_empty()
+        && let Err(err) = session
+            .services
+            .agent_control
+            .merge_agent_shell_environment(agent_id, hook_env)
+            .await
+    {
+        warn!("failed to apply subagent_start hook env: {err}");
+    }
+
+    if let Some(memory) = crate::agent::memory::read_agent_memory(
+        turn.config.codex_home.as_path(),
+        role_name.unwrap_or("default"),
+    )
+    .await
+    {
+        let memory_prompt = format!(
+            "# Agent Memory\nThe following is your persistent memory from previous sessions:\n\n{memory}"
+        );
+        if let Err(err) = session
+            .services
+            .agent_control
+            .inject_developer_message_without_turn(agent_id, memory_prompt)
+            .await
+        {
+            warn!("failed to inject agent memory: {err}");
+        }
+    }
+
+    if let Err(err) = session
+        .services
+        .agent_control
+        .send_spawn_input(agent_id, input_items, notification_source)
+        .await
+    {
+        if let Some(lease) = worktree_lease {
+            let _ = remove_worktree_lease(session, turn, lease).await;
+        }
+        let _ = session
+            .services
+            .agent_control
+            .shutdown_agent(agent_id)
+            .await;
+        return Err(collab_spawn_error(err));
+    }
+
+    if let Some(lease) = worktree_lease.or_else(|| shared_worktree.cloned()) {
+        register_worktree_lease(agent_id, lease);
+    }
+    if member.background {
+        maybe_start_background_agent_cleanup(session.clone(), turn.clone(), agent_id);
+    }
+
+    Ok(SpawnedTeamMember {
+        member: TeamMember {
+            name: member_name,
+            agent_id,
+            agent_type: member.agent_type.clone(),
+            spawned_at,
+        },
+        cwd,
+        worktree_path,
+    })
+}
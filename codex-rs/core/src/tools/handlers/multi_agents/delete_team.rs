@@ -11,6 +11,10 @@ struct DeleteTeamArgs {
     team_id: String,
     #[serde(default = "default_true")]
     cleanup: bool,
+    /// Interrupt members and let them reach a final status before shutting them down.
+    #[serde(default)]
+    drain: bool,
+    drain_timeout_ms: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -20,6 +24,9 @@ struct DeleteTeamMemberResult {
     ok: bool,
     status: AgentStatus,
     error: Option<String>,
+    /// Set only when draining: whether the member settled before the drain timeout.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    drained: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -28,6 +35,8 @@ struct DeleteTeamResult {
     removed_from_registry: bool,
     removed_team_config: bool,
     removed_task_dir: bool,
+    /// Worktrees removed while closing members (a shared worktree is listed once).
+    removed_worktrees: Vec<String>,
     closed: Vec<DeleteTeamMemberResult>,
 }
 
@@ -39,6 +48,24 @@ pub async fn handle(
 ) -> Result<ToolOutput, FunctionCallError> {
     let args: DeleteTeamArgs = parse_arguments(&arguments)?;
     let team_id = normalized_team_id(&args.team_id)?;
+    let drain_timeout_ms = match (args.drain, args.drain_timeout_ms) {
+        (false, None) => close_agent::configured_shutdown_grace_ms(&turn),
+        (false, Some(_)) => {
+            return Err(FunctionCallError::RespondToModel(
+                "drain_timeout_ms requires drain to be true".to_string(),
+            ));
+        }
+        (true, Some(ms)) if ms <= 0 => {
+            return Err(FunctionCallError::RespondToModel(
+                "drain_timeout_ms must be greater than zero".to_string(),
+            ));
+        }
+        (true, timeout_ms) => Some(
+            timeout_ms
+                .unwrap_or(DEFAULT_WAIT_TIMEOUT_MS)
+                .min(MAX_WAIT_TIMEOUT_MS),
+        ),
+    };
 
     if let Some(active_team_id) = find_team_for_member(session.conversation_id)? {
         return Err(FunctionCallError::RespondToModel(format!(
@@ -48,11 +75,22 @@ pub async fn handle(
 
     let existing_team = get_team_record(session.conversation_id, &team_id).ok();
     let persisted_config = if existing_team.is_some() {
-        read_persisted_team_config(turn.config.codex_home.as_path(), &team_id)
-            .await
-            .ok()
+        read_persisted_team_config(
+            turn.config.codex_home.as_path(),
+            session.conversation_id,
+            &team_id,
+        )
+        .await
+        .ok()
     } else {
-        Some(read_persisted_team_config(turn.config.codex_home.as_path(), &team_id).await?)
+        Some(
+            read_persisted_team_config(
+                turn.config.codex_home.as_path(),
+                session.conversation_id,
+                &team_id,
+            )
+            .await?,
+        )
     };
     if let Some(config) = persisted_config.as_ref()
         && session.conversation_id.to_string() != config.lead_thread_id
@@ -78,6 +116,7 @@ pub async fn handle(
                         name: member.name.clone(),
                         agent_id: agent_id(&member.agent_id)?,
                         agent_type: member.agent_type.clone(),
+                        spawned_at: member.spawned_at,
                     })
                 })
                 .collect::<Result<Vec<_>, FunctionCallError>>()?
@@ -99,9 +138,41 @@ pub async fn handle(
         )
         .await;
 
+    // Drain every member concurrently so the total wait is bounded by one drain timeout.
+    let drained_by_agent: HashMap<ThreadId, bool> = match drain_timeout_ms {
+        Some(timeout_ms) => futures::future::join_all(members.iter().map(|member| {
+            let session = session.clone();
+            let agent_id = member.agent_id;
+            async move {
+                let drained = match session
+                    .services
+                    .agent_control
+                    .subscribe_status(agent_id)
+                    .await
+                {
+                    Ok(mut status_rx) => {
+                        let status = status_rx.borrow_and_update().clone();
+                        close_agent::drain_agent(session, agent_id, status, status_rx, timeout_ms)
+                            .await
+                            .1
+                            .unwrap_or(false)
+                    }
+                    Err(_) => true,
+                };
+                (agent_id, drained)
+            }
+        }))
+        .await
+        .into_iter()
+        .collect(),
+        None => HashMap::new(),
+    };
+
     let mut statuses = HashMap::new();
     let mut closed = Vec::with_capacity(members.len());
+    let mut removed_worktrees = Vec::new();
     for member in &members {
+        let drained = drained_by_agent.get(&member.agent_id).copied();
         let status_before = session
             .services
             .agent_control
@@ -140,9 +211,13 @@ pub async fn handle(
         statuses.insert(member.agent_id, event_status);
 
         let cleanup_error =
-            cleanup_agent_worktree(session.as_ref(), turn.as_ref(), member.agent_id)
-                .await
-                .err();
+            match cleanup_agent_worktree(session.as_ref(), turn.as_ref(), member.agent_id).await {
+                Ok(removed) => {
+                    removed_worktrees.extend(removed.map(|path| path.display().to_string()));
+                    None
+                }
+                Err(err) => Some(err),
+            };
 
         match (close_result, cleanup_error) {
             (Ok(_), None) => closed.push(DeleteTeamMemberResult {
@@ -151,6 +226,7 @@ pub async fn handle(
                 ok: true,
                 status: status_before,
                 error: None,
+                drained,
             }),
             (Ok(_), Some(cleanup_err)) => closed.push(DeleteTeamMemberResult {
                 name: member.name.clone(),
@@ -158,6 +234,7 @@ pub async fn handle(
                 ok: false,
                 status: status_before,
                 error: Some(cleanup_err),
+                drained,
             }),
             (Err(err), None) => closed.push(DeleteTeamMemberResult {
                 name: member.name.clone(),
@@ -165,6 +242,7 @@ pub async fn handle(
                 ok: false,
                 status: status_before,
                 error: Some(err),
+                drained,
             }),
             (Err(err), Some(cleanup_err)) => closed.push(DeleteTeamMemberResult {
                 name: member.name.clone(),
@@ -172,13 +250,35 @@ pub async fn handle(
                 ok: false,
                 status: status_before,
                 error: Some(format!("{err}; {cleanup_err}")),
+                drained,
             }),
         }
     }
 
+    let closed_ids = members
+        .iter()
+        .zip(&closed)
+        .filter(|(_, result)| result.ok)
+        .map(|(member, _)| member.agent_id)
+        .collect::<Vec<_>>();
+    if let Err(err) = forget_spawned_agents(
+        turn.config.codex_home.as_path(),
+        session.conversation_id,
+        &closed_ids,
+    )
+    .await
+    {
+        warn!("failed to remove closed team members from spawned agents: {err}");
+    }
+
     remove_team_record(session.conversation_id, &team_id)?;
     if args.cleanup
-        && let Err(err) = remove_team_persistence(turn.config.codex_home.as_path(), &team_id).await
+        && let Err(err) = remove_team_persistence(
+            turn.config.codex_home.as_path(),
+            session.conversation_id,
+            &team_id,
+        )
+        .await
     {
         if let Some(original_team) = original_team {
             let _ = restore_team_record(session.conversation_id, &team_id, original_team);
@@ -205,6 +305,7 @@ pub async fn handle(
         removed_from_registry: true,
         removed_team_config: args.cleanup,
         removed_task_dir: args.cleanup,
+        removed_worktrees,
         closed,
     })
     .map_err(|err| {
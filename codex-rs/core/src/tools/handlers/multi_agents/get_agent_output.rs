@@ -0,0 +1,76 @@
+use super::*;
+use crate::find_thread_path_by_id_str;
+use crate::rollout::RolloutRecorder;
+use crate::stream_events_utils::last_assistant_message_from_item;
+use codex_protocol::protocol::RolloutItem;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct GetAgentOutputArgs {
+    id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct GetAgentOutputResult {
+    agent_id: String,
+    status: AgentStatus,
+    message: Option<String>,
+    partial: bool,
+}
+
+pub async fn handle(
+    session: Arc<Session>,
+    turn: Arc<TurnContext>,
+    _call_id: String,
+    arguments: String,
+) -> Result<ToolOutput, FunctionCallError> {
+    let args: GetAgentOutputArgs = parse_arguments(&arguments)?;
+    let agent_id = agent_id(&args.id)?;
+    let agent_control = &session.services.agent_control;
+    let status = agent_control.get_status(agent_id).await;
+
+    // Live agents may still be buffering rollout writes, so flush them before reading.
+    let rollout_path = match agent_control.flushed_rollout_path(agent_id).await {
+        Some(path) => Some(path),
+        None => find_thread_path_by_id_str(turn.config.codex_home.as_path(), &agent_id.to_string())
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!(
+                    "failed to locate rollout for agent {agent_id}: {err}"
+                ))
+            })?,
+    };
+    let Some(rollout_path) = rollout_path else {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "no rollout found for agent {agent_id}"
+        )));
+    };
+    let (items, _, _) = RolloutRecorder::load_rollout_items(&rollout_path)
+        .await
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to read rollout for agent {agent_id}: {err}"
+            ))
+        })?;
+    let message = items.iter().rev().find_map(|item| match item {
+        RolloutItem::ResponseItem(item) => last_assistant_message_from_item(item, false),
+        _ => None,
+    });
+
+    let content = serde_json::to_string(&GetAgentOutputResult {
+        agent_id: agent_id.to_string(),
+        partial: !crate::agent::status::is_final(&status),
+        status,
+        message,
+    })
+    .map_err(|err| {
+        FunctionCallError::Fatal(format!(
+            "failed to serialize get_agent_output result: {err}"
+        ))
+    })?;
+
+    Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success: Some(true),
+    })
+}
@@ -0,0 +1,52 @@
+use super::*;
+use std::sync::Arc;
+
+#[derive(Debug, Serialize)]
+struct ListedSpawnedAgent {
+    agent_id: String,
+    agent_type: Option<String>,
+    model: Option<String>,
+    spawned_at: i64,
+    status: AgentStatus,
+}
+
+#[derive(Debug, Serialize)]
+struct ListSpawnedAgentsResult {
+    agents: Vec<ListedSpawnedAgent>,
+}
+
+pub async fn handle(
+    session: Arc<Session>,
+    turn: Arc<TurnContext>,
+    _call_id: String,
+    _arguments: String,
+) -> Result<ToolOutput, FunctionCallError> {
+    let recorded =
+        read_spawned_agents(turn.config.codex_home.as_path(), session.conversation_id).await;
+
+    let mut agents = Vec::with_capacity(recorded.len());
+    for agent in recorded {
+        let status = match ThreadId::from_string(&agent.agent_id) {
+            Ok(agent_id) => session.services.agent_control.get_status(agent_id).await,
+            Err(_) => AgentStatus::NotFound,
+        };
+        agents.push(ListedSpawnedAgent {
+            agent_id: agent.agent_id,
+            agent_type: agent.agent_type,
+            model: agent.model,
+            spawned_at: agent.spawned_at,
+            status,
+        });
+    }
+
+    let content = serde_json::to_string(&ListSpawnedAgentsResult { agents }).map_err(|err| {
+        FunctionCallError::Fatal(format!(
+            "failed to serialize list_spawned_agents result: {err}"
+        ))
+    })?;
+
+    Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success: Some(true),
+    })
+}
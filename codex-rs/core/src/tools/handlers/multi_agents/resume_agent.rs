@@ -22,9 +22,7 @@ pub async fn handle(
     let receiver_thread_id = agent_id(&args.id)?;
     let child_depth = next_thread_spawn_depth(&turn.session_source);
     if exceeds_thread_spawn_depth_limit(child_depth, turn.config.agent_max_depth) {
-        return Err(FunctionCallError::RespondToModel(
-            "Agent depth limit reached. Solve the task yourself.".to_string(),
-        ));
+        return Err(depth_limit_error());
     }
 
     let (receiver_agent_nickname, receiver_agent_role) = session
@@ -13,7 +13,17 @@ struct SendMessageArgs {
     #[serde(default)]
     broadcast: bool,
     #[serde(default)]
-    interrupt: bool,
+    members: Option<Vec<String>>,
+    #[serde(default)]
+    agent_type: Option<String>,
+    #[serde(
+        default,
+        alias = "interrupt",
+        deserialize_with = "deserialize_interrupt_mode"
+    )]
+    interrupt_mode: InterruptMode,
+    #[serde(default)]
+    response_schema: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -77,6 +87,7 @@ pub async fn handle(
     arguments: String,
 ) -> Result<ToolOutput, FunctionCallError> {
     let args: SendMessageArgs = parse_arguments(&arguments)?;
+    validate_response_schema(args.response_schema.as_ref())?;
 
     if args.broadcast {
         let team_id = args.team_id.clone().ok_or_else(|| {
@@ -84,6 +95,16 @@ pub async fn handle(
         })?;
         return broadcast_to_team(session, turn, call_id, &team_id, args).await;
     }
+    if args.members.is_some() {
+        return Err(FunctionCallError::RespondToModel(
+            "members is only supported with broadcast".to_string(),
+        ));
+    }
+    if args.agent_type.is_some() {
+        return Err(FunctionCallError::RespondToModel(
+            "agent_type is only supported with broadcast".to_string(),
+        ));
+    }
 
     if let Some(team_id) = args.team_id.clone() {
         if args.to == "lead" {
@@ -102,8 +123,8 @@ async fn direct_send(
     args: SendMessageArgs,
 ) -> Result<ToolOutput, FunctionCallError> {
     let receiver_thread_id = agent_id(&args.to)?;
-    let input_items = parse_collab_input(args.message, args.items)?;
-    let prompt = input_preview(&input_items);
+    let input_items = parse_collab_input(args.message, args.items, &turn).await?;
+    let prompt = input_preview(&input_items, turn.config.agent_input_preview_max_chars);
     let submission_id = send_message_to_member(
         &session,
         &turn,
@@ -111,7 +132,8 @@ async fn direct_send(
         receiver_thread_id,
         input_items,
         prompt,
-        args.interrupt,
+        args.interrupt_mode,
+        args.response_schema,
     )
     .await?;
 
@@ -139,8 +161,8 @@ async fn message_team_member(
     let team = get_team_record(session.conversation_id, &team_id)?;
     let member = find_team_member(&team, &team_id, &args.to)?;
 
-    let input_items = parse_collab_input(args.message, args.items)?;
-    let prompt = input_preview(&input_items);
+    let input_items = parse_collab_input(args.message, args.items, &turn).await?;
+    let prompt = input_preview(&input_items, turn.config.agent_input_preview_max_chars);
     let delivery = send_message_to_member(
         &session,
         &turn,
@@ -148,7 +170,8 @@ async fn message_team_member(
         member.agent_id,
         input_items,
         prompt,
-        args.interrupt,
+        args.interrupt_mode,
+        args.response_schema,
     )
     .await;
 
@@ -186,12 +209,18 @@ async fn broadcast_to_team(
 ) -> Result<ToolOutput, FunctionCallError> {
     let team_id = normalized_team_id(team_id)?;
     let team = get_team_record(session.conversation_id, &team_id)?;
-    let input_items = parse_collab_input(args.message, args.items)?;
-    let prompt = input_preview(&input_items);
+    let recipients = broadcast_recipients(
+        &team,
+        &team_id,
+        args.members.as_deref(),
+        args.agent_type.as_deref(),
+    )?;
+    let input_items = parse_collab_input(args.message, args.items, &turn).await?;
+    let prompt = input_preview(&input_items, turn.config.agent_input_preview_max_chars);
     let mut sent = Vec::new();
     let mut failed = Vec::new();
 
-    for member in &team.members {
+    for member in recipients {
         let member_call_id = format!("{call_id}:{}", member.name);
         match send_message_to_member(
             &session,
@@ -200,7 +229,8 @@ async fn broadcast_to_team(
             member.agent_id,
             input_items.clone(),
             prompt.clone(),
-            args.interrupt,
+            args.interrupt_mode,
+            args.response_schema.clone(),
         )
         .await
         {
@@ -233,6 +263,58 @@ async fn broadcast_to_team(
     })
 }
 
+/// Returns the members a broadcast should reach, in team order. `members` and `agent_type`
+/// narrow the team and are intersected when both are given; without either this is the whole
+/// team.
+fn broadcast_recipients<'a>(
+    team: &'a TeamRecord,
+    team_id: &str,
+    members: Option<&[String]>,
+    agent_type: Option<&str>,
+) -> Result<Vec<&'a TeamMember>, FunctionCallError> {
+    let selected = match members {
+        Some([]) => {
+            return Err(FunctionCallError::RespondToModel(
+                "members must be non-empty when provided".to_string(),
+            ));
+        }
+        Some(members) => {
+            let mut selected = std::collections::HashSet::new();
+            for name in members {
+                selected.insert(find_team_member(team, team_id, name)?.agent_id);
+            }
+            Some(selected)
+        }
+        None => None,
+    };
+    let agent_type = agent_type
+        .map(|agent_type| required_non_empty(agent_type, "agent_type"))
+        .transpose()?;
+
+    let recipients = team
+        .members
+        .iter()
+        .filter(|member| {
+            selected
+                .as_ref()
+                .is_none_or(|selected| selected.contains(&member.agent_id))
+        })
+        .filter(|member| {
+            agent_type.is_none_or(|agent_type| {
+                team_member_agent_type(member).eq_ignore_ascii_case(agent_type)
+            })
+        })
+        .collect::<Vec<_>>();
+    if recipients.is_empty()
+        && let Some(agent_type) = agent_type
+    {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "no selected members of team `{team_id}` have agent_type `{agent_type}`"
+        )));
+    }
+    Ok(recipients)
+}
+
 async fn ask_lead(
     session: Arc<Session>,
     turn: Arc<TurnContext>,
@@ -242,8 +324,19 @@ async fn ask_lead(
 ) -> Result<ToolOutput, FunctionCallError> {
     let team_id = normalized_team_id(team_id)?;
 
-    let config =
-        super::read_persisted_team_config(turn.config.codex_home.as_path(), &team_id).await?;
+    let Some(lead_thread_id) =
+        super::find_team_lead(session.conversation_id, &team_id, &turn.session_source)?
+    else {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "team `{team_id}` not found"
+        )));
+    };
+    let config = super::read_persisted_team_config(
+        turn.config.codex_home.as_path(),
+        lead_thread_id,
+        &team_id,
+    )
+    .await?;
     let sender_thread_id = session.conversation_id.to_string();
     if sender_thread_id == config.lead_thread_id {
         return Err(FunctionCallError::RespondToModel(
@@ -264,8 +357,8 @@ async fn ask_lead(
         })?;
     let lead_thread_id = agent_id(&config.lead_thread_id)?;
 
-    let input_items = parse_collab_input(args.message, args.items)?;
-    let prompt = input_preview(&input_items);
+    let input_items = parse_collab_input(args.message, args.items, &turn).await?;
+    let prompt = input_preview(&input_items, turn.config.agent_input_preview_max_chars);
     let delivery = send_message_to_member(
         &session,
         &turn,
@@ -273,7 +366,8 @@ async fn ask_lead(
         lead_thread_id,
         input_items,
         prompt,
-        args.interrupt,
+        args.interrupt_mode,
+        args.response_schema,
     )
     .await;
 
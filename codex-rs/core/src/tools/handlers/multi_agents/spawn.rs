@@ -4,6 +4,7 @@ use crate::agent::role::apply_role_to_config;
 use crate::agent::control::SpawnAgentOptions;
 use crate::agent::exceeds_thread_spawn_depth_limit;
 use crate::agent::next_thread_spawn_depth;
+use codex_protocol::models::ResponseItem;
 use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
@@ -11,19 +12,33 @@ struct SpawnAgentArgs {
     message: Option<String>,
     items: Option<Vec<UserInput>>,
     agent_type: Option<String>,
+    nickname: Option<String>,
     model_provider: Option<String>,
+    #[serde(default)]
+    model_provider_fallbacks: Vec<String>,
     model: Option<String>,
+    reasoning_effort: Option<ReasoningEffort>,
+    reasoning_summary: Option<ReasoningSummary>,
     #[serde(default)]
     fork_context: bool,
+    seed_history: Option<Vec<ResponseItem>>,
     #[serde(default)]
     worktree: bool,
     #[serde(default, alias = "backendground")]
     background: bool,
+    max_depth: Option<i32>,
+    sandbox: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 struct SpawnAgentResult {
     agent_id: String,
+    spawned_at: i64,
+    cwd: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worktree_path: Option<String>,
+    /// Submission id of the turn started by the initial input, for correlating later waits.
+    initial_submission_id: String,
 }
 
 pub async fn handle(
@@ -43,19 +58,29 @@ pub async fn handle(
         .as_deref()
         .map(str::trim)
         .filter(|role| !role.is_empty());
+    let nickname = optional_non_empty(&args.nickname, "nickname")?;
     let model_provider = optional_non_empty(&args.model_provider, "model_provider")?;
     let model = optional_non_empty(&args.model, "model")?;
     let use_worktree = args.worktree;
     let background = args.background;
-    let input_items = parse_collab_input(args.message, args.items)?;
-    let prompt = input_preview(&input_items);
+    if args.seed_history.is_some() && args.fork_context {
+        return Err(FunctionCallError::RespondToModel(
+            "seed_history cannot be combined with fork_context".to_string(),
+        ));
+    }
+    if args.seed_history.as_ref().is_some_and(Vec::is_empty) {
+        return Err(FunctionCallError::RespondToModel(
+            "seed_history must be non-empty when provided".to_string(),
+        ));
+    }
+    let input_items = parse_collab_input(args.message, args.items, &turn).await?;
+    let prompt = input_preview(&input_items, turn.config.agent_input_preview_max_chars);
     let session_source = turn.session_source.clone();
     let child_depth = next_thread_spawn_depth(&session_source);
     if exceeds_thread_spawn_depth_limit(child_depth, turn.config.agent_max_depth) {
-        return Err(FunctionCallError::RespondToModel(
-            "Agent depth limit reached. Solve the task yourself.".to_string(),
-        ));
+        return Err(depth_limit_error());
     }
+    validate_spawn_max_depth(args.max_depth, child_depth, turn.config.agent_max_depth)?;
     session
         .send_event(
             &turn,
@@ -67,10 +92,12 @@ pub async fn handle(
             .into(),
         )
         .await;
-    let thread_spawn_session_source = Some(thread_spawn_source_with_role(
+    let thread_spawn_session_source = Some(thread_spawn_source_with_identity(
         session.conversation_id,
         child_depth,
+        nickname.map(str::to_owned),
         role_name.map(str::to_owned),
+        Some(call_id.clone()),
     ));
     let mut config = build_agent_spawn_config(
         &session.get_base_instructions().await,
@@ -80,13 +107,29 @@ pub async fn handle(
     apply_role_to_config(&mut config, role_name)
         .await
         .map_err(FunctionCallError::RespondToModel)?;
-    apply_member_model_overrides(&mut config, model_provider, model)?;
+    validate_spawn_tool_filter(&config, turn.as_ref(), role_name)?;
+    apply_member_model_overrides(
+        &mut config,
+        model_provider,
+        &args.model_provider_fallbacks,
+        model,
+    )?;
+    apply_member_reasoning_overrides(&mut config, args.reasoning_effort, args.reasoning_summary);
     apply_spawn_agent_runtime_overrides(&mut config, turn.as_ref())?;
+    apply_spawn_sandbox_override(&mut config, turn.as_ref(), args.sandbox.as_deref())?;
+    if let Some(max_depth) = args.max_depth {
+        config.agent_max_depth = max_depth;
+    }
     apply_spawn_agent_overrides(&mut config, child_depth);
     let worktree_lease = if use_worktree {
         match create_agent_worktree(&session, &turn).await {
             Ok(lease) => {
                 config.cwd = lease.worktree_path.clone();
+                config
+                    .permissions
+                    .shell_environment_policy
+                    .r#set
+                    .extend(lease.env.clone());
                 Some(lease)
             }
             Err(err) => {
@@ -111,15 +154,22 @@ pub async fn handle(
     } else {
         None
     };
+    let cwd = config.cwd.display().to_string();
+    let child_model = config.model.clone();
+    let worktree_path = worktree_lease
+        .as_ref()
+        .map(|lease| lease.worktree_path.display().to_string());
+    let spawn_options = SpawnAgentOptions {
+        fork_parent_spawn_call_id: args.fork_context.then(|| call_id.clone()),
+        seed_history: args.seed_history,
+    };
     let spawn_result = session
         .services
         .agent_control
         .spawn_agent_thread_with_options(
             config.clone(),
             thread_spawn_session_source,
-            SpawnAgentOptions {
-                fork_parent_spawn_call_id: args.fork_context.then(|| call_id.clone()),
-            },
+            spawn_options.clone(),
         )
         .await;
     let result = match spawn_result {
@@ -131,13 +181,16 @@ pub async fn handle(
                 session
                     .services
                     .agent_control
-                    .spawn_agent_thread(
+                    .spawn_agent_thread_with_options(
                         config,
-                        Some(thread_spawn_source_with_role(
+                        Some(thread_spawn_source_with_identity(
                             session.conversation_id,
                             child_depth,
+                            nickname.map(str::to_owned),
                             role_name.map(str::to_owned),
+                            Some(call_id.clone()),
                         )),
+                        spawn_options,
                     )
                     .await
             }
@@ -170,8 +223,9 @@ pub async fn handle(
             return Err(err);
         }
     };
+    let spawned_at = now_unix_seconds();
 
-    let hook_context = dispatch_subagent_start_hook(
+    let (hook_context, hook_env) = dispatch_subagent_start_hook(
         session.as_ref(),
         turn.as_ref(),
         agent_id,
@@ -189,6 +243,15 @@ pub async fn handle(
             warn!("failed to inject subagent_start hook context: {err}");
         }
     }
+    if !hook_env.is_empty()
+        && let Err(err) = session
+            .services
+            .agent_control
+            .merge_agent_shell_environment(agent_id, hook_env)
+            .await
+    {
+        warn!("failed to apply subagent_start hook env: {err}");
+    }
 
     if let Some(memory) = crate::agent::memory::read_agent_memory(
         turn.config.codex_home.as_path(),
@@ -209,41 +272,58 @@ pub async fn handle(
         }
     }
 
-    if let Err(err) = session
+    let initial_submission_id = match session
         .services
         .agent_control
         .send_spawn_input(agent_id, input_items, notification_source)
         .await
     {
-        if let Some(lease) = worktree_lease {
-            let _ = remove_worktree_lease(&session, &turn, lease).await;
+        Ok(submission_id) => submission_id,
+        Err(err) => {
+            if let Some(lease) = worktree_lease {
+                let _ = remove_worktree_lease(&session, &turn, lease).await;
+            }
+            let _ = session
+                .services
+                .agent_control
+                .shutdown_agent(agent_id)
+                .await;
+            session
+                .send_event(
+                    &turn,
+                    CollabAgentSpawnEndEvent {
+                        call_id,
+                        sender_thread_id: session.conversation_id,
+                        new_thread_id: None,
+                        new_agent_nickname: None,
+                        new_agent_role: None,
+                        prompt,
+                        status: AgentStatus::NotFound,
+                    }
+                    .into(),
+                )
+                .await;
+            return Err(collab_spawn_error(err));
         }
-        let _ = session
-            .services
-            .agent_control
-            .shutdown_agent(agent_id)
-            .await;
-        session
-            .send_event(
-                &turn,
-                CollabAgentSpawnEndEvent {
-                    call_id,
-                    sender_thread_id: session.conversation_id,
-                    new_thread_id: None,
-                    new_agent_nickname: None,
-                    new_agent_role: None,
-                    prompt,
-                    status: AgentStatus::NotFound,
-                }
-                .into(),
-            )
-            .await;
-        return Err(collab_spawn_error(err));
-    }
+    };
 
     if let Some(lease) = worktree_lease {
         register_worktree_lease(agent_id, lease);
     }
+    if let Err(err) = record_spawned_agent(
+        turn.config.codex_home.as_path(),
+        session.conversation_id,
+        PersistedSpawnedAgent {
+            agent_id: agent_id.to_string(),
+            agent_type: role_name.map(str::to_owned),
+            model: child_model,
+            spawned_at,
+        },
+    )
+    .await
+    {
+        warn!("failed to record spawned agent {agent_id}: {err}");
+    }
     if background {
         maybe_start_background_agent_cleanup(session.clone(), turn.clone(), agent_id);
     }
@@ -273,6 +353,10 @@ pub async fn handle(
 
     let content = serde_json::to_string(&SpawnAgentResult {
         agent_id: agent_id.to_string(),
+        spawned_at,
+        cwd,
+        worktree_path,
+        initial_submission_id,
     })
     .map_err(|err| {
         FunctionCallError::Fatal(format!("failed to serialize spawn_agent result: {err}"))
@@ -0,0 +1,192 @@
+use super::create_team::SpawnTeamMemberArgs;
+use super::create_team::SpawnedTeamMember;
+use super::create_team::build_team_member_config;
+use super::create_team::spawn_team_member;
+use super::create_team::validate_team_member_args;
+use super::*;
+use crate::agent::next_thread_spawn_depth;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct TeamAddMemberArgs {
+    team_id: String,
+    #[serde(flatten)]
+    member: SpawnTeamMemberArgs,
+}
+
+#[derive(Debug, Serialize)]
+struct TeamAddMemberResult {
+    team_id: String,
+    name: String,
+    agent_id: String,
+    status: AgentStatus,
+    cwd: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worktree_path: Option<String>,
+}
+
+pub async fn handle(
+    session: Arc<Session>,
+    turn: Arc<TurnContext>,
+    call_id: String,
+    arguments: String,
+) -> Result<ToolOutput, FunctionCallError> {
+    let TeamAddMemberArgs { team_id, member } = parse_arguments(&arguments)?;
+    if let Some(active_team_id) = find_team_for_member(session.conversation_id)? {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "team_add_member is disabled for agent team teammates (team `{active_team_id}`). Ask the team lead to add members."
+        )));
+    }
+    let team_id = normalized_team_id(&team_id)?;
+    let team = get_team_record(session.conversation_id, &team_id)?;
+    let name = validate_team_member_args(&member)?;
+    if team.members.iter().any(|existing| existing.name == name) {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "duplicate member name `{name}`"
+        )));
+    }
+
+    let child_depth = next_thread_spawn_depth(&turn.session_source);
+    if exceeds_thread_spawn_depth_limit(child_depth, turn.config.agent_max_depth) {
+        return Err(depth_limit_error());
+    }
+    validate_spawn_max_depth(member.max_depth, child_depth, turn.config.agent_max_depth)?;
+    let config = build_team_member_config(&session, &turn, &member, child_depth).await?;
+
+    let event_call_id = prefixed_team_call_id(TEAM_SPAWN_CALL_PREFIX, &call_id);
+    session
+        .send_event(
+            &turn,
+            CollabWaitingBeginEvent {
+                sender_thread_id: session.conversation_id,
+                receiver_thread_ids: Vec::new(),
+                receiver_agents: Vec::new(),
+                call_id: event_call_id.clone(),
+            }
+            .into(),
+        )
+        .await;
+
+    let SpawnedTeamMember {
+        member: spawned,
+        cwd,
+        worktree_path,
+    } = match spawn_team_member(
+        &session,
+        &turn,
+        &call_id,
+        &member,
+        config,
+        child_depth,
+        None,
+    )
+    .await
+    {
+        Ok(spawned) => spawned,
+        Err(err) => {
+            session
+                .send_event(
+                    &turn,
+                    CollabWaitingEndEvent {
+                        sender_thread_id: session.conversation_id,
+                        call_id: event_call_id,
+                        agent_statuses: Vec::new(),
+                        statuses: HashMap::new(),
+                    }
+                    .into(),
+                )
+                .await;
+            return Err(err);
+        }
+    };
+    let spawned_members = vec![spawned.clone()];
+    let status = session
+        .services
+        .agent_control
+        .get_status(spawned.agent_id)
+        .await;
+    let statuses = HashMap::from([(spawned.agent_id, status.clone())]);
+
+    let updated_team = match append_team_member(session.conversation_id, &team_id, spawned.clone())
+    {
+        Ok(updated_team) => updated_team,
+        Err(err) => {
+            cleanup_spawned_team_members(&session, &turn, &spawned_members).await;
+            let agent_statuses = team_member_status_entries(&spawned_members, &statuses);
+            session
+                .send_event(
+                    &turn,
+                    CollabWaitingEndEvent {
+                        sender_thread_id: session.conversation_id,
+                        call_id: event_call_id,
+                        agent_statuses,
+                        statuses,
+                    }
+                    .into(),
+                )
+                .await;
+            return Err(err);
+        }
+    };
+    if let Err(err) = persist_team_state(
+        turn.config.codex_home.as_path(),
+        session.conversation_id,
+        &team_id,
+        &updated_team,
+    )
+    .await
+    {
+        let mut previous_team = updated_team;
+        previous_team
+            .members
+            .retain(|existing| existing.agent_id != spawned.agent_id);
+        let _ = restore_team_record(session.conversation_id, &team_id, previous_team);
+        cleanup_spawned_team_members(&session, &turn, &spawned_members).await;
+        let agent_statuses = team_member_status_entries(&spawned_members, &statuses);
+        session
+            .send_event(
+                &turn,
+                CollabWaitingEndEvent {
+                    sender_thread_id: session.conversation_id,
+                    call_id: event_call_id,
+                    agent_statuses,
+                    statuses,
+                }
+                .into(),
+            )
+            .await;
+        return Err(err);
+    }
+
+    let agent_statuses = team_member_status_entries(&spawned_members, &statuses);
+    session
+        .send_event(
+            &turn,
+            CollabWaitingEndEvent {
+                sender_thread_id: session.conversation_id,
+                call_id: event_call_id,
+                agent_statuses,
+                statuses,
+            }
+            .into(),
+        )
+        .await;
+
+    let content = serde_json::to_string(&TeamAddMemberResult {
+        team_id,
+        name: spawned.name,
+        agent_id: spawned.agent_id.to_string(),
+        status,
+        cwd,
+        worktree_path,
+    })
+    .map_err(|err| {
+        FunctionCallError::Fatal(format!("failed to serialize team_add_member result: {err}"))
+    })?;
+
+    Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success: Some(true),
+    })
+}
@@ -0,0 +1,62 @@
+use super::*;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct TeamGcArgs {
+    team_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TeamGcEntry {
+    team_id: String,
+    outcome: TeamReconcileOutcome,
+}
+
+#[derive(Debug, Serialize)]
+struct TeamGcResult {
+    teams: Vec<TeamGcEntry>,
+}
+
+pub async fn handle(
+    session: Arc<Session>,
+    turn: Arc<TurnContext>,
+    _call_id: String,
+    arguments: String,
+) -> Result<ToolOutput, FunctionCallError> {
+    let args: TeamGcArgs = parse_arguments(&arguments)?;
+    if let Some(team_id) = find_team_for_member(session.conversation_id)? {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "team_gc is disabled for agent team teammates (team `{team_id}`). Ask the team lead to clean up teams."
+        )));
+    }
+    let codex_home = turn.config.codex_home.as_path();
+
+    let configs = match args.team_id {
+        Some(team_id) => {
+            let team_id = normalized_team_id(&team_id)?;
+            vec![read_persisted_team_config(codex_home, session.conversation_id, &team_id).await?]
+        }
+        None => list_persisted_teams(codex_home)
+            .await
+            .map_err(|err| FunctionCallError::Fatal(format!("failed to list teams: {err}")))?
+            .into_iter()
+            .filter(|config| config.lead_thread_id == session.conversation_id.to_string())
+            .collect(),
+    };
+
+    let mut teams = Vec::with_capacity(configs.len());
+    for config in configs {
+        let team_id = config.team_name.clone();
+        let outcome = reconcile_persisted_team(session.as_ref(), codex_home, config).await?;
+        teams.push(TeamGcEntry { team_id, outcome });
+    }
+
+    let content = serde_json::to_string(&TeamGcResult { teams }).map_err(|err| {
+        FunctionCallError::Fatal(format!("failed to serialize team_gc result: {err}"))
+    })?;
+
+    Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success: Some(true),
+    })
+}
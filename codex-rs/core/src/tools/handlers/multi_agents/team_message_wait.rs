@@ -0,0 +1,155 @@
+use super::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct TeamMessageWaitArgs {
+    team_id: String,
+    to: String,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    items: Option<Vec<UserInput>>,
+    #[serde(
+        default,
+        alias = "interrupt",
+        deserialize_with = "deserialize_interrupt_mode"
+    )]
+    interrupt_mode: InterruptMode,
+    #[serde(default)]
+    response_schema: Option<serde_json::Value>,
+    timeout_ms: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct TeamMessageWaitResult {
+    team_id: String,
+    member_name: String,
+    agent_id: String,
+    submission_id: String,
+    status: AgentStatus,
+    timed_out: bool,
+}
+
+pub async fn handle(
+    session: Arc<Session>,
+    turn: Arc<TurnContext>,
+    call_id: String,
+    arguments: String,
+) -> Result<ToolOutput, FunctionCallError> {
+    let args: TeamMessageWaitArgs = parse_arguments(&arguments)?;
+    validate_response_schema(args.response_schema.as_ref())?;
+    let team_id = normalized_team_id(&args.team_id)?;
+    let team = get_team_record(session.conversation_id, &team_id)?;
+    let member = find_team_member(&team, &team_id, &args.to)?;
+    let timeout_ms = normalize_wait_timeout(args.timeout_ms)?;
+    let input_items = parse_collab_input(args.message, args.items, &turn).await?;
+    let prompt = input_preview(&input_items, turn.config.agent_input_preview_max_chars);
+
+    // Subscribe before sending so the status left over from the member's previous turn is not
+    // mistaken for the answer to this message.
+    let status_rx = match session
+        .services
+        .agent_control
+        .subscribe_status(member.agent_id)
+        .await
+    {
+        Ok(mut status_rx) => {
+            status_rx.mark_unchanged();
+            Some(status_rx)
+        }
+        Err(CodexErr::ThreadNotFound(_)) => None,
+        Err(err) => return Err(collab_agent_error(member.agent_id, err)),
+    };
+
+    let submission_id = send_message_to_member(
+        &session,
+        &turn,
+        call_id.clone(),
+        member.agent_id,
+        input_items,
+        prompt,
+        args.interrupt_mode,
+        args.response_schema,
+    )
+    .await?;
+
+    let event_call_id = prefixed_team_call_id(TEAM_WAIT_CALL_PREFIX, &call_id);
+    session
+        .send_event(
+            &turn,
+            CollabWaitingBeginEvent {
+                sender_thread_id: session.conversation_id,
+                receiver_thread_ids: vec![member.agent_id],
+                receiver_agents: team_member_refs(std::slice::from_ref(&member)),
+                call_id: event_call_id.clone(),
+            }
+            .into(),
+        )
+        .await;
+
+    let final_status = match status_rx {
+        Some(mut status_rx) => {
+            let session = session.clone();
+            let agent_id = member.agent_id;
+            let wait = async move {
+                let _ = status_rx.changed().await;
+                wait_for_final_status(session, agent_id, status_rx).await
+            };
+            match timeout_ms {
+                Some(timeout_ms) => {
+                    let deadline = Instant::now() + Duration::from_millis(timeout_ms as u64);
+                    timeout_at(deadline, wait).await.ok().flatten()
+                }
+                None => wait.await,
+            }
+            .map(|(_, status)| status)
+        }
+        None => Some(AgentStatus::NotFound),
+    };
+    let timed_out = final_status.is_none();
+    let status = match final_status {
+        Some(status) => status,
+        None => {
+            session
+                .services
+                .agent_control
+                .get_status(member.agent_id)
+                .await
+        }
+    };
+
+    let statuses = HashMap::from([(member.agent_id, status.clone())]);
+    let agent_statuses = team_member_status_entries(std::slice::from_ref(&member), &statuses);
+    session
+        .send_event(
+            &turn,
+            CollabWaitingEndEvent {
+                sender_thread_id: session.conversation_id,
+                call_id: event_call_id,
+                agent_statuses,
+                statuses,
+            }
+            .into(),
+        )
+        .await;
+
+    let content = serde_json::to_string(&TeamMessageWaitResult {
+        team_id,
+        member_name: member.name,
+        agent_id: member.agent_id.to_string(),
+        submission_id,
+        status,
+        timed_out,
+    })
+    .map_err(|err| {
+        FunctionCallError::Fatal(format!(
+            "failed to serialize team_message_wait result: {err}"
+        ))
+    })?;
+
+    Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success: None,
+    })
+}
@@ -0,0 +1,136 @@
+use super::*;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct TeamRenameArgs {
+    team_id: String,
+    new_team_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TeamRenameResult {
+    previous_team_id: String,
+    team_id: String,
+}
+
+pub async fn handle(
+    session: Arc<Session>,
+    turn: Arc<TurnContext>,
+    _call_id: String,
+    arguments: String,
+) -> Result<ToolOutput, FunctionCallError> {
+    let args: TeamRenameArgs = parse_arguments(&arguments)?;
+    let team_id = normalized_team_id(&args.team_id)?;
+    let new_team_id = normalized_team_id(&args.new_team_id)?;
+    if team_id == new_team_id {
+        return Err(FunctionCallError::RespondToModel(
+            "new_team_id must differ from team_id".to_string(),
+        ));
+    }
+    if let Some(active_team_id) = find_team_for_member(session.conversation_id)? {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "team_rename is disabled for agent team teammates (team `{active_team_id}`). Ask the team lead to rename teams."
+        )));
+    }
+
+    let codex_home = turn.config.codex_home.as_path();
+    let lead_thread_id = session.conversation_id;
+    if find_persisted_team_config(codex_home, lead_thread_id, &new_team_id)
+        .await?
+        .is_some()
+    {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "team `{new_team_id}` already exists"
+        )));
+    }
+    let owns_legacy_team = read_legacy_team_config(codex_home, lead_thread_id, &team_id)
+        .await?
+        .is_some();
+
+    let team = rename_team_record(lead_thread_id, &team_id, &new_team_id)?;
+    let mut moved = Vec::new();
+    if let Err(err) = move_team_persistence(
+        codex_home,
+        lead_thread_id,
+        &team_id,
+        &new_team_id,
+        owns_legacy_team,
+        &team,
+        &mut moved,
+    )
+    .await
+    {
+        // Put everything back so the team stays reachable under its old id.
+        for (from, to) in moved.iter().rev() {
+            if let Err(err) = tokio::fs::rename(to, from).await {
+                warn!(
+                    "failed to restore {} while undoing rename of team `{team_id}`: {err}",
+                    from.display()
+                );
+            }
+        }
+        let _ = rename_team_record(lead_thread_id, &new_team_id, &team_id);
+        return Err(err);
+    }
+    if owns_legacy_team
+        && let Err(err) = remove_dir_if_exists(&legacy_team_dir(codex_home, &team_id)).await
+    {
+        warn!("failed to remove legacy config for renamed team `{team_id}`: {err}");
+    }
+
+    let content = serde_json::to_string(&TeamRenameResult {
+        previous_team_id: team_id,
+        team_id: new_team_id,
+    })
+    .map_err(|err| {
+        FunctionCallError::Fatal(format!("failed to serialize team_rename result: {err}"))
+    })?;
+
+    Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success: Some(true),
+    })
+}
+
+/// Moves the team's config and task directories to `new_team_id` and rewrites its config with
+/// the new name. Every directory moved is recorded in `moved` (as `(from, to)`) so the caller
+/// can undo a partial rename.
+async fn move_team_persistence(
+    codex_home: &Path,
+    lead_thread_id: ThreadId,
+    team_id: &str,
+    new_team_id: &str,
+    owns_legacy_team: bool,
+    team: &TeamRecord,
+    moved: &mut Vec<(PathBuf, PathBuf)>,
+) -> Result<(), FunctionCallError> {
+    let new_tasks_dir = team_tasks_dir(codex_home, lead_thread_id, new_team_id);
+    let mut moves = vec![
+        (
+            team_dir(codex_home, lead_thread_id, team_id),
+            team_dir(codex_home, lead_thread_id, new_team_id),
+        ),
+        (
+            team_tasks_dir(codex_home, lead_thread_id, team_id),
+            new_tasks_dir.clone(),
+        ),
+    ];
+    if owns_legacy_team {
+        moves.push((legacy_team_tasks_dir(codex_home, team_id), new_tasks_dir));
+    }
+    for (from, to) in moves {
+        if !tokio::fs::try_exists(&from).await.unwrap_or(false) {
+            continue;
+        }
+        if let Some(parent) = to.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|err| team_persistence_error("rename team directory", team_id, err))?;
+        }
+        tokio::fs::rename(&from, &to)
+            .await
+            .map_err(|err| team_persistence_error("rename team directory", team_id, err))?;
+        moved.push((from, to));
+    }
+    persist_team_state(codex_home, lead_thread_id, new_team_id, team).await
+}
@@ -0,0 +1,65 @@
+use super::*;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct TeamStatusArgs {
+    team_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TeamStatusMember {
+    name: String,
+    agent_id: String,
+    agent_type: Option<String>,
+    spawned_at: i64,
+    status: AgentStatus,
+}
+
+#[derive(Debug, Serialize)]
+struct TeamStatusResult {
+    team_id: String,
+    created_at: i64,
+    members: Vec<TeamStatusMember>,
+}
+
+pub async fn handle(
+    session: Arc<Session>,
+    _turn: Arc<TurnContext>,
+    _call_id: String,
+    arguments: String,
+) -> Result<ToolOutput, FunctionCallError> {
+    let args: TeamStatusArgs = parse_arguments(&arguments)?;
+    let team_id = normalized_team_id(&args.team_id)?;
+    let team = get_team_record(session.conversation_id, &team_id)?;
+
+    // Snapshot only: never wait on members here, `wait` covers blocking.
+    let mut members = Vec::with_capacity(team.members.len());
+    for member in team.members {
+        let status = session
+            .services
+            .agent_control
+            .get_status(member.agent_id)
+            .await;
+        members.push(TeamStatusMember {
+            name: member.name,
+            agent_id: member.agent_id.to_string(),
+            agent_type: member.agent_type,
+            spawned_at: member.spawned_at,
+            status,
+        });
+    }
+
+    let result = TeamStatusResult {
+        team_id,
+        created_at: team.created_at,
+        members,
+    };
+    let content = serde_json::to_string(&result).map_err(|err| {
+        FunctionCallError::Fatal(format!("failed to serialize team_status result: {err}"))
+    })?;
+
+    Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success: Some(true),
+    })
+}
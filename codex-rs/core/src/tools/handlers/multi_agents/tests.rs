@@ -108,10 +108,6 @@ fn list_worktree_paths(codex_home: &Path, lead_thread_id: ThreadId) -> Vec<PathB
     worktrees
 }
 
-fn team_tasks_dir(codex_home: &Path, team_id: &str) -> PathBuf {
-    codex_home.join("tasks").join(team_id)
-}
-
 #[test]
 fn team_member_refs_formats_agent_type() {
     let typed_id = ThreadId::new();
@@ -122,16 +118,19 @@ fn team_member_refs_formats_agent_type() {
             name: "typed".to_string(),
             agent_id: typed_id,
             agent_type: Some(" reviewer ".to_string()),
+            spawned_at: 0,
         },
         TeamMember {
             name: "blank".to_string(),
             agent_id: blank_id,
             agent_type: Some("   ".to_string()),
+            spawned_at: 0,
         },
         TeamMember {
             name: "none".to_string(),
             agent_id: none_id,
             agent_type: None,
+            spawned_at: 0,
         },
     ];
 
@@ -260,6 +259,7 @@ async fn spawn_agent_uses_explorer_role_and_inherits_approval_policy() {
     #[derive(Debug, Deserialize)]
     struct SpawnAgentResult {
         agent_id: String,
+        initial_submission_id: String,
     }
 
     let (mut session, mut turn) = make_session_and_context().await;
@@ -311,6 +311,7 @@ async fn spawn_agent_uses_explorer_role_and_inherits_approval_policy() {
     let result: SpawnAgentResult =
         serde_json::from_str(&content).expect("spawn_agent result should be json");
     let agent_id = agent_id(&result.agent_id).expect("agent_id should be valid");
+    assert!(!result.initial_submission_id.is_empty());
     let snapshot = manager
         .get_thread(agent_id)
         .await
@@ -320,6 +321,12 @@ async fn spawn_agent_uses_explorer_role_and_inherits_approval_policy() {
     assert_eq!(snapshot.model, expected_model);
     assert_eq!(snapshot.reasoning_effort, None);
     assert_eq!(snapshot.approval_policy, AskForApproval::OnRequest);
+    let SessionSource::SubAgent(SubAgentSource::ThreadSpawn { call_id, .. }) =
+        snapshot.session_source
+    else {
+        panic!("expected thread-spawn sub-agent source");
+    };
+    assert_eq!(call_id.as_deref(), Some("call-1"));
 }
 
 #[tokio::test]
@@ -563,6 +570,101 @@ async fn spawn_agent_accepts_background_field() {
         .expect("shutdown spawned agent");
 }
 
+#[tokio::test]
+async fn spawn_agent_seeds_history_before_initial_message() {
+    #[derive(Debug, Deserialize)]
+    struct SpawnAgentResult {
+        agent_id: String,
+    }
+
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+
+    let Err(err) = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "spawn_agent",
+            function_payload(json!({
+                "message": "new task",
+                "fork_context": true,
+                "seed_history": [{
+                    "type": "message",
+                    "role": "user",
+                    "content": [{"type": "input_text", "text": "seeded context"}]
+                }]
+            })),
+        ))
+        .await
+    else {
+        panic!("seed_history with fork_context should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(
+            "seed_history cannot be combined with fork_context".to_string()
+        )
+    );
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session,
+            turn,
+            "spawn_agent",
+            function_payload(json!({
+                "message": "new task",
+                "seed_history": [{
+                    "type": "message",
+                    "role": "user",
+                    "content": [{"type": "input_text", "text": "seeded context"}]
+                }]
+            })),
+        ))
+        .await
+        .expect("spawn_agent should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: SpawnAgentResult =
+        serde_json::from_str(&content).expect("spawn_agent result should be json");
+    let agent_id = agent_id(&result.agent_id).expect("agent_id should be valid");
+    let thread = manager
+        .get_thread(agent_id)
+        .await
+        .expect("spawned agent should exist");
+
+    let mut seeded_index = None;
+    let mut prompt_index = None;
+    for _ in 0..50 {
+        let history = thread.codex.session.clone_history().await;
+        let texts = history
+            .raw_items()
+            .iter()
+            .map(|item| serde_json::to_string(item).expect("response item should serialize"))
+            .collect::<Vec<_>>();
+        seeded_index = texts
+            .iter()
+            .position(|text| text.contains("seeded context"));
+        prompt_index = texts.iter().position(|text| text.contains("new task"));
+        if seeded_index.is_some() && prompt_index.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let seeded_index = seeded_index.expect("seed history should be recorded");
+    let prompt_index = prompt_index.expect("initial message should be recorded");
+    assert!(seeded_index < prompt_index);
+
+    let _ = manager.agent_control().shutdown_agent(agent_id).await;
+}
+
 #[tokio::test]
 async fn spawn_agent_dispatches_subagent_start_hook() {
     #[derive(Debug, Deserialize)]
@@ -692,11 +794,139 @@ async fn spawn_agent_rejects_worktree_outside_git_repo() {
     );
 }
 
+#[test]
+fn transient_git_errors_are_limited_to_lock_contention() {
+    assert!(is_transient_git_error(
+        "fatal: Unable to create '/repo/.git/index.lock': File exists."
+    ));
+    assert!(is_transient_git_error(
+        "error: could not lock config file .git/config: File exists"
+    ));
+    assert!(!is_transient_git_error("fatal: invalid reference: HEAD"));
+    assert!(!is_transient_git_error(
+        "fatal: '/tmp/worktree' already exists"
+    ));
+}
+
+#[tokio::test]
+async fn resolve_git_executable_rejects_missing_configured_path() {
+    let (_session, turn) = make_session_and_context().await;
+    let missing = turn.config.codex_home.join("missing-git");
+    let mut config = (*turn.config).clone();
+    config.git_path = Some(missing.clone());
+
+    assert_eq!(
+        resolve_git_executable(&config),
+        Err(format!(
+            "git executable `{}` from git_path does not exist",
+            missing.display()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn spawn_agent_worktree_uses_configured_worktree_root() {
+    #[derive(Debug, Deserialize)]
+    struct SpawnAgentResult {
+        agent_id: String,
+        worktree_path: Option<String>,
+    }
+
+    let (mut session, mut turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let repo_dir = tempfile::tempdir().expect("temp dir");
+    let worktree_root = tempfile::tempdir().expect("temp dir");
+    turn.cwd = repo_dir.path().to_path_buf();
+    init_git_repo(turn.cwd.as_path());
+    let mut config = (*turn.config).clone();
+    config.worktree_root = Some(worktree_root.path().to_path_buf());
+    turn.config = Arc::new(config);
+    let expected_root = worktree_root
+        .path()
+        .join(session.conversation_id.to_string());
+    let codex_home = turn.config.codex_home.clone();
+    let lead_thread_id = session.conversation_id;
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+
+    let spawn_output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "spawn_agent",
+            function_payload(json!({"message": "inspect this repo", "worktree": true})),
+        ))
+        .await
+        .expect("spawn_agent with worktree should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(spawn_content),
+        ..
+    } = spawn_output
+    else {
+        panic!("expected function output");
+    };
+    let spawn_result: SpawnAgentResult =
+        serde_json::from_str(&spawn_content).expect("spawn_agent result should be json");
+    let worktree_path = PathBuf::from(spawn_result.worktree_path.expect("worktree path"));
+    assert!(
+        worktree_path.starts_with(&expected_root),
+        "{worktree_path:?} should be under {expected_root:?}"
+    );
+    assert!(list_worktree_paths(codex_home.as_path(), lead_thread_id).is_empty());
+
+    MultiAgentHandler
+        .handle(invocation(
+            session,
+            turn,
+            "close_agent",
+            function_payload(json!({"id": spawn_result.agent_id})),
+        ))
+        .await
+        .expect("close_agent should succeed");
+    assert!(!worktree_path.exists());
+}
+
+#[tokio::test]
+async fn ensure_writable_worktree_root_reports_unusable_root() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let blocker = dir.path().join("not-a-dir");
+    std::fs::write(&blocker, "").expect("write blocker file");
+    let root = blocker.join("conversation");
+
+    let Err(FunctionCallError::RespondToModel(message)) =
+        ensure_writable_worktree_root(&root).await
+    else {
+        panic!("a root under a regular file should be rejected");
+    };
+    assert!(
+        message.starts_with(&format!(
+            "failed to create worktree root `{}`",
+            root.display()
+        )),
+        "{message}"
+    );
+
+    ensure_writable_worktree_root(&dir.path().join("ok"))
+        .await
+        .expect("fresh directory should be writable");
+    assert_eq!(
+        std::fs::read_dir(dir.path().join("ok"))
+            .expect("read root")
+            .count(),
+        0,
+        "the write probe should be removed"
+    );
+}
+
 #[tokio::test]
 async fn spawn_agent_worktree_sets_cwd_and_close_agent_cleans_up() {
     #[derive(Debug, Deserialize)]
     struct SpawnAgentResult {
         agent_id: String,
+        spawned_at: i64,
+        cwd: String,
+        worktree_path: Option<String>,
     }
 
     #[derive(Debug, Deserialize)]
@@ -754,6 +984,12 @@ async fn spawn_agent_worktree_sets_cwd_and_close_agent_cleans_up() {
     assert_eq!(snapshot.cwd.starts_with(&expected_worktree_root), true);
     assert_ne!(snapshot.cwd, turn.cwd);
     assert_eq!(snapshot.cwd.exists(), true);
+    assert_eq!(spawn_result.cwd, snapshot.cwd.display().to_string());
+    assert!(spawn_result.spawned_at > 0);
+    assert_eq!(
+        spawn_result.worktree_path,
+        Some(snapshot.cwd.display().to_string())
+    );
     assert_eq!(
         list_worktree_paths(codex_home.as_path(), lead_thread_id).len(),
         1
@@ -833,7 +1069,10 @@ async fn spawn_agent_errors_when_manager_dropped() {
     };
     assert_eq!(
         err,
-        FunctionCallError::RespondToModel("collab manager unavailable".to_string())
+        FunctionCallError::RespondToModel(
+            json!({"code": "manager_unavailable", "message": "collab manager unavailable"})
+                .to_string()
+        )
     );
 }
 
@@ -848,6 +1087,7 @@ async fn spawn_agent_rejects_when_depth_limit_exceeded() {
         depth: turn.config.agent_max_depth,
         agent_nickname: None,
         agent_role: None,
+        call_id: None,
     });
 
     let invocation = invocation(
@@ -861,121 +1101,535 @@ async fn spawn_agent_rejects_when_depth_limit_exceeded() {
     };
     assert_eq!(
         err,
-        FunctionCallError::RespondToModel(
-            "Agent depth limit reached. Solve the task yourself.".to_string()
+        collab_error(
+            CollabErrorCode::DepthLimit,
+            "Agent depth limit reached. Solve the task yourself."
         )
     );
 }
 
 #[tokio::test]
-async fn send_message_rejects_empty_message() {
-    let (session, turn) = make_session_and_context().await;
-    let invocation = invocation(
-        Arc::new(session),
-        Arc::new(turn),
-        "send_message",
-        function_payload(json!({"to": ThreadId::new().to_string(), "message": ""})),
-    );
-    let Err(err) = MultiAgentHandler.handle(invocation).await else {
-        panic!("empty message should be rejected");
-    };
-    assert_eq!(
-        err,
-        FunctionCallError::RespondToModel("Empty message can't be sent to an agent".to_string())
-    );
-}
+async fn spawn_agent_rejects_max_depth_above_inherited_limit() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let inherited_max_depth = turn.config.agent_max_depth;
 
-#[tokio::test]
-async fn send_message_rejects_when_message_and_items_are_both_set() {
-    let (session, turn) = make_session_and_context().await;
     let invocation = invocation(
         Arc::new(session),
         Arc::new(turn),
-        "send_message",
+        "spawn_agent",
         function_payload(json!({
-            "to": ThreadId::new().to_string(),
             "message": "hello",
-            "items": [{"type": "mention", "name": "drive", "path": "app://drive"}]
+            "max_depth": inherited_max_depth + 1
         })),
     );
     let Err(err) = MultiAgentHandler.handle(invocation).await else {
-        panic!("message+items should be rejected");
+        panic!("max_depth above the inherited limit should be rejected");
     };
     assert_eq!(
         err,
-        FunctionCallError::RespondToModel(
-            "Provide either message or items, but not both".to_string()
-        )
+        FunctionCallError::RespondToModel(format!(
+            "max_depth cannot exceed the current depth limit of {inherited_max_depth}"
+        ))
     );
+    assert!(manager.captured_ops().is_empty());
 }
 
 #[tokio::test]
-async fn send_message_rejects_invalid_id() {
-    let (session, turn) = make_session_and_context().await;
-    let invocation = invocation(
-        Arc::new(session),
-        Arc::new(turn),
-        "send_message",
-        function_payload(json!({"to": "not-a-uuid", "message": "hi"})),
-    );
-    let Err(err) = MultiAgentHandler.handle(invocation).await else {
-        panic!("invalid id should be rejected");
-    };
-    let FunctionCallError::RespondToModel(msg) = err else {
-        panic!("expected respond-to-model error");
-    };
-    assert!(msg.starts_with("invalid agent id not-a-uuid:"));
-}
+async fn spawn_agent_can_tighten_sandbox() {
+    #[derive(Debug, Deserialize)]
+    struct SpawnAgentResult {
+        agent_id: String,
+    }
 
-#[tokio::test]
-async fn send_message_reports_missing_agent() {
-    let (mut session, turn) = make_session_and_context().await;
+    let (mut session, mut turn) = make_session_and_context().await;
     let manager = thread_manager();
     session.services.agent_control = manager.agent_control();
-    let agent_id = ThreadId::new();
+    turn.sandbox_policy
+        .set(SandboxPolicy::new_workspace_write_policy())
+        .expect("sandbox policy should be set");
+
     let invocation = invocation(
         Arc::new(session),
         Arc::new(turn),
-        "send_message",
-        function_payload(json!({"to": agent_id.to_string(), "message": "hi"})),
+        "spawn_agent",
+        function_payload(json!({
+            "message": "review only",
+            "sandbox": "read-only"
+        })),
     );
-    let Err(err) = MultiAgentHandler.handle(invocation).await else {
-        panic!("missing agent should be reported");
+    let output = MultiAgentHandler
+        .handle(invocation)
+        .await
+        .expect("spawn_agent should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
     };
+    let result: SpawnAgentResult =
+        serde_json::from_str(&content).expect("spawn_agent result should be json");
+    let agent_id = agent_id(&result.agent_id).expect("agent_id should be valid");
+    let snapshot = manager
+        .get_thread(agent_id)
+        .await
+        .expect("spawned agent thread should exist")
+        .config_snapshot()
+        .await;
     assert_eq!(
-        err,
-        FunctionCallError::RespondToModel(format!("agent with id {agent_id} not found"))
+        snapshot.sandbox_policy,
+        SandboxPolicy::new_read_only_policy()
     );
 }
 
 #[tokio::test]
-async fn send_message_interrupts_before_prompt() {
+async fn spawn_agent_applies_reasoning_effort_override() {
+    #[derive(Debug, Deserialize)]
+    struct SpawnAgentResult {
+        agent_id: String,
+    }
+
     let (mut session, turn) = make_session_and_context().await;
     let manager = thread_manager();
     session.services.agent_control = manager.agent_control();
-    let config = turn.config.as_ref().clone();
-    let thread = manager.start_thread(config).await.expect("start thread");
-    let agent_id = thread.thread_id;
+
     let invocation = invocation(
         Arc::new(session),
         Arc::new(turn),
-        "send_message",
+        "spawn_agent",
         function_payload(json!({
-            "to": agent_id.to_string(),
-            "message": "hi",
-            "interrupt": true
+            "message": "scout the repo",
+            "reasoning_effort": "low"
         })),
     );
-    MultiAgentHandler
+    let output = MultiAgentHandler
         .handle(invocation)
         .await
-        .expect("send_message should succeed");
-
-    let ops = manager.captured_ops();
-    let ops_for_agent: Vec<&Op> = ops
-        .iter()
-        .filter_map(|(id, op)| (*id == agent_id).then_some(op))
-        .collect();
+        .expect("spawn_agent should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: SpawnAgentResult =
+        serde_json::from_str(&content).expect("spawn_agent result should be json");
+    let agent_id = agent_id(&result.agent_id).expect("agent_id should be valid");
+    let snapshot = manager
+        .get_thread(agent_id)
+        .await
+        .expect("spawned agent thread should exist")
+        .config_snapshot()
+        .await;
+    assert_eq!(snapshot.reasoning_effort, Some(ReasoningEffort::Low));
+}
+
+#[tokio::test]
+async fn spawn_agent_uses_requested_nickname() {
+    #[derive(Debug, Deserialize)]
+    struct SpawnAgentResult {
+        agent_id: String,
+    }
+
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+
+    let Err(FunctionCallError::RespondToModel(message)) = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "spawn_agent",
+            function_payload(json!({"message": "scout the repo", "nickname": "  "})),
+        ))
+        .await
+    else {
+        panic!("blank nickname should be rejected");
+    };
+    assert_eq!(message, "nickname must be non-empty when provided");
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn,
+            "spawn_agent",
+            function_payload(json!({"message": "scout the repo", "nickname": " Ada "})),
+        ))
+        .await
+        .expect("spawn_agent should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: SpawnAgentResult =
+        serde_json::from_str(&content).expect("spawn_agent result should be json");
+    let agent_id = agent_id(&result.agent_id).expect("agent_id should be valid");
+    let (nickname, _role) = session
+        .services
+        .agent_control
+        .get_agent_nickname_and_role(agent_id)
+        .await
+        .expect("spawned agent should have metadata");
+    assert_eq!(nickname.as_deref(), Some("Ada"));
+}
+
+#[tokio::test]
+async fn spawn_agent_rejects_sandbox_wider_than_parent() {
+    let (mut session, mut turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    turn.sandbox_policy
+        .set(SandboxPolicy::new_read_only_policy())
+        .expect("sandbox policy should be set");
+
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "spawn_agent",
+        function_payload(json!({
+            "message": "hello",
+            "sandbox": "danger-full-access"
+        })),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("widening the sandbox should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(
+            "sandbox `danger-full-access` is less restrictive than the parent's `read-only`; spawned agents may only tighten the sandbox"
+                .to_string()
+        )
+    );
+    assert!(manager.captured_ops().is_empty());
+}
+
+#[tokio::test]
+async fn spawn_agent_is_recorded_for_list_spawned_agents() {
+    #[derive(Debug, Deserialize)]
+    struct SpawnAgentResult {
+        agent_id: String,
+    }
+
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "spawn_agent",
+            function_payload(json!({
+                "message": "inspect this repo",
+                "agent_type": "explorer"
+            })),
+        ))
+        .await
+        .expect("spawn_agent should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let spawned: SpawnAgentResult =
+        serde_json::from_str(&content).expect("spawn_agent result should be json");
+
+    let recorded =
+        read_spawned_agents(turn.config.codex_home.as_path(), session.conversation_id).await;
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].agent_id, spawned.agent_id);
+    assert_eq!(recorded[0].agent_type.as_deref(), Some("explorer"));
+    let note =
+        spawned_agents_resume_note(turn.config.codex_home.as_path(), session.conversation_id)
+            .await
+            .expect("resume note should list the spawned agent");
+    assert!(note.contains(&spawned.agent_id));
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "list_spawned_agents",
+            function_payload(json!({})),
+        ))
+        .await
+        .expect("list_spawned_agents should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let listed: serde_json::Value =
+        serde_json::from_str(&content).expect("list_spawned_agents result should be json");
+    assert_eq!(listed["agents"][0]["agent_id"], json!(spawned.agent_id));
+    assert_eq!(listed["agents"][0]["agent_type"], json!("explorer"));
+    assert_ne!(
+        listed["agents"][0]["status"],
+        serde_json::to_value(AgentStatus::NotFound).expect("serialize status")
+    );
+
+    MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "close_agent",
+            function_payload(json!({"id": spawned.agent_id})),
+        ))
+        .await
+        .expect("close_agent should succeed");
+    assert_eq!(
+        read_spawned_agents(turn.config.codex_home.as_path(), session.conversation_id).await,
+        Vec::new()
+    );
+    assert!(
+        !spawned_agents_path(turn.config.codex_home.as_path(), session.conversation_id).exists()
+    );
+}
+
+#[tokio::test]
+async fn record_spawned_agent_backs_up_unparseable_registry() {
+    let codex_home = tempfile::tempdir().expect("temp dir");
+    let lead_thread_id = ThreadId::new();
+    let path = spawned_agents_path(codex_home.path(), lead_thread_id);
+    std::fs::create_dir_all(path.parent().expect("registry dir")).expect("create registry dir");
+    std::fs::write(&path, "not json").expect("write corrupt registry");
+
+    let agent = PersistedSpawnedAgent {
+        agent_id: ThreadId::new().to_string(),
+        agent_type: None,
+        model: None,
+        spawned_at: 1,
+    };
+    record_spawned_agent(codex_home.path(), lead_thread_id, agent.clone())
+        .await
+        .expect("record should succeed after moving the corrupt registry aside");
+
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".corrupt");
+    assert_eq!(
+        std::fs::read_to_string(PathBuf::from(backup)).expect("read backup"),
+        "not json"
+    );
+    assert_eq!(
+        read_spawned_agents(codex_home.path(), lead_thread_id).await,
+        vec![agent]
+    );
+}
+
+#[tokio::test]
+async fn send_message_rejects_empty_message() {
+    let (session, turn) = make_session_and_context().await;
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "send_message",
+        function_payload(json!({"to": ThreadId::new().to_string(), "message": ""})),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("empty message should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("Empty message can't be sent to an agent".to_string())
+    );
+}
+
+#[tokio::test]
+async fn send_message_rejects_when_message_and_items_are_both_set() {
+    let (session, turn) = make_session_and_context().await;
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "send_message",
+        function_payload(json!({
+            "to": ThreadId::new().to_string(),
+            "message": "hello",
+            "items": [{"type": "mention", "name": "drive", "path": "app://drive"}]
+        })),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("message+items should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(
+            "Provide either message or items, but not both".to_string()
+        )
+    );
+}
+
+#[tokio::test]
+async fn send_message_rejects_missing_local_image_before_delivery() {
+    let (session, mut turn) = make_session_and_context().await;
+    let cwd = tempfile::tempdir().expect("temp dir");
+    turn.cwd = cwd.path().to_path_buf();
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "send_message",
+        function_payload(json!({
+            "to": ThreadId::new().to_string(),
+            "items": [{"type": "local_image", "path": "missing.png"}]
+        })),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("missing local image should be rejected");
+    };
+    let FunctionCallError::RespondToModel(msg) = err else {
+        panic!("expected respond-to-model error");
+    };
+    let expected_prefix = format!(
+        "local_image path `{}` is not accessible:",
+        cwd.path().join("missing.png").display()
+    );
+    assert!(msg.starts_with(&expected_prefix), "unexpected error: {msg}");
+}
+
+#[tokio::test]
+async fn send_message_rejects_paths_outside_workspace_roots() {
+    let (session, mut turn) = make_session_and_context().await;
+    let workspace = tempfile::tempdir().expect("temp dir");
+    let outside = tempfile::tempdir().expect("temp dir");
+    let cwd = workspace.path().join("repo");
+    std::fs::create_dir(&cwd).expect("create cwd");
+    std::fs::write(workspace.path().join("secret.png"), b"png").expect("write file");
+    std::fs::write(outside.path().join("secret.png"), b"png").expect("write file");
+    turn.cwd = cwd;
+    turn.file_system_sandbox_policy =
+        FileSystemSandboxPolicy::from(&SandboxPolicy::new_read_only_policy());
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+
+    for path in [
+        "../secret.png".to_string(),
+        outside.path().join("secret.png").display().to_string(),
+    ] {
+        let Err(FunctionCallError::RespondToModel(msg)) = MultiAgentHandler
+            .handle(invocation(
+                session.clone(),
+                turn.clone(),
+                "send_message",
+                function_payload(json!({
+                    "to": ThreadId::new().to_string(),
+                    "items": [{"type": "local_image", "path": path}]
+                })),
+            ))
+            .await
+        else {
+            panic!("path `{path}` outside the workspace should be rejected");
+        };
+        assert!(
+            msg.ends_with("is outside the workspace and writable roots"),
+            "unexpected error for `{path}`: {msg}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn send_message_rejects_invalid_id() {
+    let (session, turn) = make_session_and_context().await;
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "send_message",
+        function_payload(json!({"to": "not-a-uuid", "message": "hi"})),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("invalid id should be rejected");
+    };
+    let FunctionCallError::RespondToModel(msg) = err else {
+        panic!("expected respond-to-model error");
+    };
+    assert!(msg.starts_with("invalid agent id not-a-uuid:"));
+}
+
+#[tokio::test]
+async fn send_message_rejects_messaging_self() {
+    let (session, turn) = make_session_and_context().await;
+    let self_id = session.conversation_id.to_string();
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "send_message",
+        function_payload(json!({"to": self_id, "message": "hi"})),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("messaging self should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("an agent cannot send a message to itself".to_string())
+    );
+}
+
+#[tokio::test]
+async fn send_message_reports_missing_agent() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let agent_id = ThreadId::new();
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "send_message",
+        function_payload(json!({"to": agent_id.to_string(), "message": "hi"})),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("missing agent should be reported");
+    };
+    assert_eq!(
+        err,
+        collab_error(
+            CollabErrorCode::AgentNotFound,
+            format!("agent with id {agent_id} not found")
+        )
+    );
+}
+
+#[tokio::test]
+async fn send_message_interrupts_before_prompt() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let config = turn.config.as_ref().clone();
+    let thread = manager.start_thread(config).await.expect("start thread");
+    let agent_id = thread.thread_id;
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "send_message",
+        function_payload(json!({
+            "to": agent_id.to_string(),
+            "message": "hi",
+            "interrupt": true
+        })),
+    );
+    MultiAgentHandler
+        .handle(invocation)
+        .await
+        .expect("send_message should succeed");
+
+    let ops = manager.captured_ops();
+    let ops_for_agent: Vec<&Op> = ops
+        .iter()
+        .filter_map(|(id, op)| (*id == agent_id).then_some(op))
+        .collect();
     assert_eq!(ops_for_agent.len(), 2);
     assert!(matches!(ops_for_agent[0], Op::Interrupt));
     assert!(matches!(ops_for_agent[1], Op::UserInput { .. }));
@@ -987,6 +1641,71 @@ async fn send_message_interrupts_before_prompt() {
         .expect("shutdown should submit");
 }
 
+#[tokio::test]
+async fn send_message_interrupt_mode_none_only_queues_input() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let config = turn.config.as_ref().clone();
+    let thread = manager.start_thread(config).await.expect("start thread");
+    let agent_id = thread.thread_id;
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "send_message",
+        function_payload(json!({
+            "to": agent_id.to_string(),
+            "message": "hi",
+            "interrupt_mode": "none"
+        })),
+    );
+    MultiAgentHandler
+        .handle(invocation)
+        .await
+        .expect("send_message should succeed");
+
+    let ops = manager.captured_ops();
+    let ops_for_agent: Vec<&Op> = ops
+        .iter()
+        .filter_map(|(id, op)| (*id == agent_id).then_some(op))
+        .collect();
+    assert_eq!(ops_for_agent.len(), 1);
+    assert!(matches!(ops_for_agent[0], Op::UserInput { .. }));
+
+    let _ = thread
+        .thread
+        .submit(Op::Shutdown {})
+        .await
+        .expect("shutdown should submit");
+}
+
+#[test]
+fn interrupt_mode_accepts_names_and_legacy_bools() {
+    #[derive(Debug, Deserialize)]
+    struct Args {
+        #[serde(
+            default,
+            alias = "interrupt",
+            deserialize_with = "deserialize_interrupt_mode"
+        )]
+        interrupt_mode: InterruptMode,
+    }
+
+    let parse = |value: serde_json::Value| {
+        serde_json::from_value::<Args>(value)
+            .expect("interrupt mode should parse")
+            .interrupt_mode
+    };
+    assert_eq!(parse(json!({})), InterruptMode::None);
+    assert_eq!(parse(json!({"interrupt": false})), InterruptMode::None);
+    assert_eq!(parse(json!({"interrupt": true})), InterruptMode::Soft);
+    assert_eq!(
+        parse(json!({"interrupt_mode": "hard"})),
+        InterruptMode::Hard
+    );
+    assert!(serde_json::from_value::<Args>(json!({"interrupt_mode": "later"})).is_err());
+}
+
 #[tokio::test]
 async fn send_message_accepts_structured_items() {
     let (mut session, turn) = make_session_and_context().await;
@@ -1038,6 +1757,76 @@ async fn send_message_accepts_structured_items() {
         .expect("shutdown should submit");
 }
 
+#[tokio::test]
+async fn send_message_forwards_response_schema() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let config = turn.config.as_ref().clone();
+    let thread = manager.start_thread(config).await.expect("start thread");
+    let agent_id = thread.thread_id;
+    let schema = json!({
+        "type": "object",
+        "properties": {"summary": {"type": "string"}},
+        "required": ["summary"]
+    });
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "send_message",
+        function_payload(json!({
+            "to": agent_id.to_string(),
+            "message": "summarize",
+            "response_schema": schema
+        })),
+    );
+    MultiAgentHandler
+        .handle(invocation)
+        .await
+        .expect("send_message should succeed");
+
+    let expected = Op::UserInput {
+        items: vec![UserInput::Text {
+            text: "summarize".to_string(),
+            text_elements: Vec::new(),
+        }],
+        final_output_json_schema: Some(schema),
+    };
+    let captured = manager
+        .captured_ops()
+        .into_iter()
+        .find(|(id, op)| *id == agent_id && *op == expected);
+    assert_eq!(captured, Some((agent_id, expected)));
+
+    let _ = thread
+        .thread
+        .submit(Op::Shutdown {})
+        .await
+        .expect("shutdown should submit");
+}
+
+#[tokio::test]
+async fn send_message_rejects_non_object_response_schema() {
+    let (session, turn) = make_session_and_context().await;
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "send_message",
+        function_payload(json!({
+            "to": ThreadId::new().to_string(),
+            "message": "summarize",
+            "response_schema": "string"
+        })),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("non-object response_schema should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("response_schema must be a JSON object".to_string())
+    );
+}
+
 #[tokio::test]
 async fn send_message_includes_receiver_metadata_in_events() {
     let (mut session, turn, rx) = make_session_and_context_with_rx().await;
@@ -1141,7 +1930,10 @@ async fn resume_agent_reports_missing_agent() {
     };
     assert_eq!(
         err,
-        FunctionCallError::RespondToModel(format!("agent with id {agent_id} not found"))
+        collab_error(
+            CollabErrorCode::AgentNotFound,
+            format!("agent with id {agent_id} not found")
+        )
     );
 }
 
@@ -1292,6 +2084,7 @@ async fn resume_agent_rejects_when_depth_limit_exceeded() {
         depth: turn.config.agent_max_depth,
         agent_nickname: None,
         agent_role: None,
+        call_id: None,
     });
 
     let invocation = invocation(
@@ -1305,10 +2098,100 @@ async fn resume_agent_rejects_when_depth_limit_exceeded() {
     };
     assert_eq!(
         err,
-        FunctionCallError::RespondToModel(
-            "Agent depth limit reached. Solve the task yourself.".to_string()
+        collab_error(
+            CollabErrorCode::DepthLimit,
+            "Agent depth limit reached. Solve the task yourself."
+        )
+    );
+}
+
+#[tokio::test]
+async fn get_agent_output_reports_missing_rollout() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let agent_id = ThreadId::new();
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "get_agent_output",
+        function_payload(json!({"id": agent_id.to_string()})),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("missing rollout should be reported");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(format!("no rollout found for agent {agent_id}"))
+    );
+}
+
+#[tokio::test]
+async fn get_agent_output_reads_last_assistant_message_of_closed_agent() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let config = turn.config.as_ref().clone();
+    let message = |role: &str, text: &str| {
+        RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![if role == "assistant" {
+                ContentItem::OutputText {
+                    text: text.to_string(),
+                }
+            } else {
+                ContentItem::InputText {
+                    text: text.to_string(),
+                }
+            }],
+            end_turn: None,
+            phase: None,
+        })
+    };
+    let thread = manager
+        .resume_thread_with_history(
+            config,
+            InitialHistory::Forked(vec![
+                message("user", "do the thing"),
+                message("assistant", "first answer"),
+                message("assistant", "final answer"),
+            ]),
+            AuthManager::from_auth_for_testing(CodexAuth::from_api_key("dummy")),
+            false,
         )
+        .await
+        .expect("start thread");
+    let agent_id = thread.thread_id;
+    let _ = manager
+        .agent_control()
+        .shutdown_agent(agent_id)
+        .await
+        .expect("shutdown agent");
+
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "get_agent_output",
+        function_payload(json!({"id": agent_id.to_string()})),
     );
+    let output = MultiAgentHandler
+        .handle(invocation)
+        .await
+        .expect("get_agent_output should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success,
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("get_agent_output result should be json");
+    assert_eq!(result["message"], json!("final answer"));
+    assert_eq!(result["partial"], json!(false));
+    assert_eq!(success, Some(true));
 }
 
 #[derive(Debug, Deserialize, PartialEq, Eq)]
@@ -1356,6 +2239,27 @@ async fn wait_rejects_invalid_id() {
     assert!(msg.starts_with("invalid agent id invalid:"));
 }
 
+#[tokio::test]
+async fn wait_rejects_waiting_on_self() {
+    let (session, turn) = make_session_and_context().await;
+    let self_id = session.conversation_id.to_string();
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "wait",
+        function_payload(json!({"ids": [self_id]})),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("waiting on self should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(
+            "an agent cannot wait on itself; ids must not include your own agent id".to_string()
+        )
+    );
+}
+
 #[tokio::test]
 async fn wait_rejects_empty_ids() {
     let (session, turn) = make_session_and_context().await;
@@ -1369,13 +2273,52 @@ async fn wait_rejects_empty_ids() {
         panic!("empty ids should be rejected");
     };
     assert_eq!(
-        err,
-        FunctionCallError::RespondToModel("ids must be non-empty".to_string())
+        err,
+        FunctionCallError::RespondToModel("ids must be non-empty".to_string())
+    );
+}
+
+#[tokio::test]
+async fn wait_returns_not_found_for_missing_agents() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let id_a = ThreadId::new();
+    let id_b = ThreadId::new();
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "wait",
+        function_payload(json!({
+            "ids": [id_a.to_string(), id_b.to_string()],
+            "timeout_ms": 1000
+        })),
+    );
+    let output = MultiAgentHandler
+        .handle(invocation)
+        .await
+        .expect("wait should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success,
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: WaitResult = serde_json::from_str(&content).expect("wait result should be json");
+    assert_eq!(
+        result,
+        WaitResult {
+            status: HashMap::from([(id_a, AgentStatus::NotFound), (id_b, AgentStatus::NotFound),]),
+            timed_out: false
+        }
     );
+    assert_eq!(success, None);
 }
 
 #[tokio::test]
-async fn wait_returns_not_found_for_missing_agents() {
+async fn wait_first_error_returns_all_statuses_when_nothing_failed() {
     let (mut session, turn) = make_session_and_context().await;
     let manager = thread_manager();
     session.services.agent_control = manager.agent_control();
@@ -1387,6 +2330,7 @@ async fn wait_returns_not_found_for_missing_agents() {
         "wait",
         function_payload(json!({
             "ids": [id_a.to_string(), id_b.to_string()],
+            "mode": "first_error",
             "timeout_ms": 1000
         })),
     );
@@ -1396,7 +2340,6 @@ async fn wait_returns_not_found_for_missing_agents() {
         .expect("wait should succeed");
     let ToolOutput::Function {
         body: FunctionCallOutputBody::Text(content),
-        success,
         ..
     } = output
     else {
@@ -1406,11 +2349,10 @@ async fn wait_returns_not_found_for_missing_agents() {
     assert_eq!(
         result,
         WaitResult {
-            status: HashMap::from([(id_a, AgentStatus::NotFound), (id_b, AgentStatus::NotFound),]),
+            status: HashMap::from([(id_a, AgentStatus::NotFound), (id_b, AgentStatus::NotFound)]),
             timed_out: false
         }
     );
-    assert_eq!(success, None);
 }
 
 #[tokio::test]
@@ -1459,6 +2401,222 @@ async fn wait_times_out_when_status_is_not_final() {
         .expect("shutdown should submit");
 }
 
+#[tokio::test]
+async fn wait_rejects_member_timeouts_without_team_id() {
+    let (session, turn) = make_session_and_context().await;
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "wait",
+        function_payload(json!({
+            "ids": [ThreadId::new().to_string()],
+            "member_timeouts": {"worker": 1000}
+        })),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("member_timeouts without team_id should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("member_timeouts requires team_id".to_string())
+    );
+}
+
+#[tokio::test]
+async fn wait_team_rejects_member_timeouts_for_unknown_member() {
+    let (session, turn) = make_session_and_context().await;
+    let session = Arc::new(session);
+    insert_team_record(
+        session.conversation_id,
+        "team-timeouts".to_string(),
+        TeamRecord {
+            members: vec![TeamMember {
+                name: "worker".to_string(),
+                agent_id: ThreadId::new(),
+                agent_type: None,
+                spawned_at: 0,
+            }],
+            created_at: 0,
+        },
+    )
+    .expect("team insert should succeed");
+
+    let invocation = invocation(
+        session.clone(),
+        Arc::new(turn),
+        "wait",
+        function_payload(json!({
+            "team_id": "team-timeouts",
+            "member_timeouts": {"reviewer": 1000}
+        })),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("unknown member in member_timeouts should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(
+            "member `reviewer` not found in team `team-timeouts`".to_string()
+        )
+    );
+    remove_team_record(session.conversation_id, "team-timeouts")
+        .expect("team record should be removed");
+}
+
+#[tokio::test]
+async fn wait_team_all_reports_last_member_to_finish() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let config = turn.config.as_ref().clone();
+    let thread = manager.start_thread(config).await.expect("start thread");
+    let last_id = thread.thread_id;
+    let finished_id = ThreadId::new();
+    let session = Arc::new(session);
+    insert_team_record(
+        session.conversation_id,
+        "team-critical-path".to_string(),
+        TeamRecord {
+            members: vec![
+                TeamMember {
+                    name: "finished".to_string(),
+                    agent_id: finished_id,
+                    agent_type: None,
+                    spawned_at: 0,
+                },
+                TeamMember {
+                    name: "last".to_string(),
+                    agent_id: last_id,
+                    agent_type: None,
+                    spawned_at: 0,
+                },
+            ],
+            created_at: 0,
+        },
+    )
+    .expect("team insert should succeed");
+
+    let shutdown = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        thread
+            .thread
+            .submit(Op::Shutdown {})
+            .await
+            .expect("shutdown should submit");
+    });
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            Arc::new(turn),
+            "wait",
+            function_payload(json!({
+                "team_id": "team-critical-path",
+                "mode": "all",
+                "timeout_ms": MIN_WAIT_TIMEOUT_MS
+            })),
+        ))
+        .await
+        .expect("wait should succeed");
+    shutdown.await.expect("shutdown task should finish");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("wait result should be json");
+    assert_eq!(result["timed_out"], json!(false));
+    assert_eq!(result["triggered_agent_id"], json!(last_id.to_string()));
+    assert_eq!(result["triggered_member"], json!("last"));
+
+    remove_team_record(session.conversation_id, "team-critical-path")
+        .expect("team record should be removed");
+}
+
+#[tokio::test]
+async fn wait_team_member_timeouts_only_time_out_the_slow_member() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let config = turn.config.as_ref().clone();
+    let thread = manager.start_thread(config).await.expect("start thread");
+    let slow_id = thread.thread_id;
+    let finished_id = ThreadId::new();
+    let session = Arc::new(session);
+    insert_team_record(
+        session.conversation_id,
+        "team-member-timeouts".to_string(),
+        TeamRecord {
+            members: vec![
+                TeamMember {
+                    name: "finished".to_string(),
+                    agent_id: finished_id,
+                    agent_type: None,
+                    spawned_at: 0,
+                },
+                TeamMember {
+                    name: "slow".to_string(),
+                    agent_id: slow_id,
+                    agent_type: None,
+                    spawned_at: 0,
+                },
+            ],
+            created_at: 0,
+        },
+    )
+    .expect("team insert should succeed");
+
+    let invocation = invocation(
+        session.clone(),
+        Arc::new(turn),
+        "wait",
+        function_payload(json!({
+            "team_id": "team-member-timeouts",
+            "timeout_ms": MAX_WAIT_TIMEOUT_MS,
+            "member_timeouts": {"slow": MIN_WAIT_TIMEOUT_MS}
+        })),
+    );
+    let output = timeout(
+        Duration::from_millis((MIN_WAIT_TIMEOUT_MS + 5_000) as u64),
+        MultiAgentHandler.handle(invocation),
+    )
+    .await
+    .expect("wait should honor the member timeout")
+    .expect("wait should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("wait result should be json");
+    assert_eq!(result["timed_out"], json!(true));
+    assert_eq!(result["timed_out_ids"], json!([slow_id.to_string()]));
+    assert_eq!(result["already_final"], json!([finished_id.to_string()]));
+    assert!(
+        result["elapsed_ms"]
+            .as_u64()
+            .is_some_and(|elapsed| elapsed >= MIN_WAIT_TIMEOUT_MS as u64),
+        "elapsed_ms should cover the member timeout: {result}"
+    );
+    assert_eq!(
+        result["status"][finished_id.to_string()],
+        serde_json::to_value(AgentStatus::NotFound).expect("serialize status")
+    );
+
+    remove_team_record(session.conversation_id, "team-member-timeouts")
+        .expect("team record should be removed");
+    let _ = thread
+        .thread
+        .submit(Op::Shutdown {})
+        .await
+        .expect("shutdown should submit");
+}
+
 #[tokio::test]
 async fn wait_clamps_short_timeouts_to_minimum() {
     let (mut session, turn) = make_session_and_context().await;
@@ -1659,24 +2817,165 @@ async fn close_agent_submits_shutdown_and_returns_status() {
     let (mut session, turn) = make_session_and_context().await;
     let manager = thread_manager();
     session.services.agent_control = manager.agent_control();
-    let config = turn.config.as_ref().clone();
+    let config = turn.config.as_ref().clone();
+    let thread = manager.start_thread(config).await.expect("start thread");
+    let agent_id = thread.thread_id;
+    let status_before = manager.agent_control().get_status(agent_id).await;
+
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "close_agent",
+        function_payload(json!({"id": agent_id.to_string()})),
+    );
+    let output = MultiAgentHandler
+        .handle(invocation)
+        .await
+        .expect("close_agent should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success,
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: close_agent::CloseAgentResult =
+        serde_json::from_str(&content).expect("close_agent result should be json");
+    assert_eq!(result.status, status_before);
+    assert_eq!(result.drained, None);
+    assert_eq!(success, Some(true));
+
+    let ops = manager.captured_ops();
+    let submitted_shutdown = ops
+        .iter()
+        .any(|(id, op)| *id == agent_id && matches!(op, Op::Shutdown));
+    assert_eq!(submitted_shutdown, true);
+
+    let status_after = manager.agent_control().get_status(agent_id).await;
+    assert_eq!(status_after, AgentStatus::NotFound);
+}
+
+#[tokio::test]
+async fn close_agent_is_idempotent_for_already_closed_agent() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let config = turn.config.as_ref().clone();
+    let thread = manager.start_thread(config).await.expect("start thread");
+    let agent_id = thread.thread_id;
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+
+    for _ in 0..2 {
+        let output = MultiAgentHandler
+            .handle(invocation(
+                session.clone(),
+                turn.clone(),
+                "close_agent",
+                function_payload(json!({"id": agent_id.to_string()})),
+            ))
+            .await
+            .expect("close_agent should succeed");
+        let ToolOutput::Function { success, .. } = output else {
+            panic!("expected function output");
+        };
+        assert_eq!(success, Some(true));
+    }
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session,
+            turn,
+            "close_agent",
+            function_payload(json!({"id": agent_id.to_string()})),
+        ))
+        .await
+        .expect("repeated close_agent should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: close_agent::CloseAgentResult =
+        serde_json::from_str(&content).expect("close_agent result should be json");
+    assert_eq!(result.status, AgentStatus::NotFound);
+}
+
+#[tokio::test]
+async fn close_agent_interrupts_then_forces_shutdown_after_drain_timeout() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let config = turn.config.as_ref().clone();
+    let thread = manager.start_thread(config).await.expect("start thread");
+    let agent_id = thread.thread_id;
+
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "close_agent",
+        function_payload(json!({
+            "id": agent_id.to_string(),
+            "drain_timeout_ms": 50
+        })),
+    );
+    let output = MultiAgentHandler
+        .handle(invocation)
+        .await
+        .expect("close_agent should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: close_agent::CloseAgentResult =
+        serde_json::from_str(&content).expect("close_agent result should be json");
+    assert_eq!(result.drained, Some(false));
+
+    let ops = manager
+        .captured_ops()
+        .into_iter()
+        .filter(|(id, _)| *id == agent_id)
+        .map(|(_, op)| op)
+        .collect::<Vec<_>>();
+    let interrupt_index = ops
+        .iter()
+        .position(|op| matches!(op, Op::Interrupt))
+        .expect("drain should interrupt the agent first");
+    let shutdown_index = ops
+        .iter()
+        .position(|op| matches!(op, Op::Shutdown))
+        .expect("agent should be shut down after the drain timeout");
+    assert!(interrupt_index < shutdown_index);
+}
+
+#[tokio::test]
+async fn close_agent_applies_configured_shutdown_grace_period() {
+    let (mut session, mut turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let mut config = turn.config.as_ref().clone();
+    config.agent_shutdown_grace_ms = Some(50);
+    turn.config = Arc::new(config.clone());
     let thread = manager.start_thread(config).await.expect("start thread");
     let agent_id = thread.thread_id;
-    let status_before = manager.agent_control().get_status(agent_id).await;
 
-    let invocation = invocation(
-        Arc::new(session),
-        Arc::new(turn),
-        "close_agent",
-        function_payload(json!({"id": agent_id.to_string()})),
-    );
     let output = MultiAgentHandler
-        .handle(invocation)
+        .handle(invocation(
+            Arc::new(session),
+            Arc::new(turn),
+            "close_agent",
+            function_payload(json!({"id": agent_id.to_string()})),
+        ))
         .await
         .expect("close_agent should succeed");
     let ToolOutput::Function {
         body: FunctionCallOutputBody::Text(content),
-        success,
         ..
     } = output
     else {
@@ -1684,17 +2983,44 @@ async fn close_agent_submits_shutdown_and_returns_status() {
     };
     let result: close_agent::CloseAgentResult =
         serde_json::from_str(&content).expect("close_agent result should be json");
-    assert_eq!(result.status, status_before);
-    assert_eq!(success, Some(true));
+    assert_eq!(result.drained, Some(false));
 
-    let ops = manager.captured_ops();
-    let submitted_shutdown = ops
+    let ops = manager
+        .captured_ops()
+        .into_iter()
+        .filter(|(id, _)| *id == agent_id)
+        .map(|(_, op)| op)
+        .collect::<Vec<_>>();
+    let interrupt_index = ops
         .iter()
-        .any(|(id, op)| *id == agent_id && matches!(op, Op::Shutdown));
-    assert_eq!(submitted_shutdown, true);
+        .position(|op| matches!(op, Op::Interrupt))
+        .expect("grace period should interrupt the agent first");
+    let shutdown_index = ops
+        .iter()
+        .position(|op| matches!(op, Op::Shutdown))
+        .expect("agent should be shut down after the grace period");
+    assert!(interrupt_index < shutdown_index);
+}
 
-    let status_after = manager.agent_control().get_status(agent_id).await;
-    assert_eq!(status_after, AgentStatus::NotFound);
+#[tokio::test]
+async fn close_agent_rejects_non_positive_drain_timeout() {
+    let (session, turn) = make_session_and_context().await;
+    let invocation = invocation(
+        Arc::new(session),
+        Arc::new(turn),
+        "close_agent",
+        function_payload(json!({
+            "id": ThreadId::new().to_string(),
+            "drain_timeout_ms": 0
+        })),
+    );
+    let Err(err) = MultiAgentHandler.handle(invocation).await else {
+        panic!("non-positive drain timeout should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("drain_timeout_ms must be greater than zero".to_string())
+    );
 }
 
 #[tokio::test]
@@ -2072,8 +3398,9 @@ async fn spawn_agent_fails_when_limit_reached_without_reclaimable_threads() {
     };
     assert_eq!(
         err,
-        FunctionCallError::RespondToModel(
-            "collab spawn failed: agent thread limit reached (max 1)".to_string()
+        collab_error(
+            CollabErrorCode::AgentLimitReached,
+            "collab spawn failed: agent thread limit reached (max 1). Wait for running agents to finish or close agents you no longer need before spawning more."
         )
     );
 
@@ -2115,6 +3442,7 @@ struct DeleteTeamResult {
     removed_from_registry: bool,
     removed_team_config: bool,
     removed_task_dir: bool,
+    removed_worktrees: Vec<String>,
     closed: Vec<DeleteTeamMemberResult>,
 }
 
@@ -2263,99 +3591,448 @@ async fn delete_team_closes_active_teammate_and_releases_slot() {
     let spawn_output = MultiAgentHandler
         .handle(spawn_invocation)
         .await
-        .expect("spawn_team should succeed");
-    let ToolOutput::Function {
-        body: FunctionCallOutputBody::Text(spawn_content),
-        success: spawn_success,
-        ..
-    } = spawn_output
+        .expect("spawn_team should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(spawn_content),
+        success: spawn_success,
+        ..
+    } = spawn_output
+    else {
+        panic!("expected function output");
+    };
+    assert_eq!(spawn_success, Some(true));
+    let spawn_result: SpawnTeamResult =
+        serde_json::from_str(&spawn_content).expect("spawn_team result should be json");
+    assert_eq!(spawn_result.members.len(), 1);
+    let member_thread_id = agent_id(&spawn_result.members[0].agent_id).expect("valid agent id");
+
+    let spawned_threads = session.services.agent_control.spawned_thread_ids();
+    assert_eq!(spawned_threads.len(), 1);
+    assert_eq!(spawned_threads.contains(&member_thread_id), true);
+
+    MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "delete_team",
+            function_payload(json!({
+                "team_id": spawn_result.team_id
+            })),
+        ))
+        .await
+        .expect("delete_team should succeed");
+
+    let spawned_threads = session.services.agent_control.spawned_thread_ids();
+    assert_eq!(spawned_threads.contains(&member_thread_id), false);
+
+    let unblocked_invocation = invocation(
+        session.clone(),
+        turn.clone(),
+        "spawn_agent",
+        function_payload(json!({"message": "unblocked"})),
+    );
+    let unblocked_output = MultiAgentHandler
+        .handle(unblocked_invocation)
+        .await
+        .expect("spawn_agent should succeed after delete_team releases slot");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(unblocked_content),
+        success: unblocked_success,
+        ..
+    } = unblocked_output
+    else {
+        panic!("expected function output");
+    };
+    assert_eq!(unblocked_success, Some(true));
+    let unblocked_result: SpawnAgentResult =
+        serde_json::from_str(&unblocked_content).expect("spawn_agent result should be json");
+    let unblocked_thread_id = agent_id(&unblocked_result.agent_id).expect("valid agent id");
+    let _ = manager
+        .agent_control()
+        .shutdown_agent(unblocked_thread_id)
+        .await;
+}
+
+#[test]
+fn insert_team_record_allows_multiple_teams_per_session() {
+    let lead_thread_id = ThreadId::new();
+    let first_record = TeamRecord {
+        members: vec![TeamMember {
+            name: "worker".to_string(),
+            agent_id: ThreadId::new(),
+            agent_type: None,
+            spawned_at: 0,
+        }],
+        created_at: 0,
+    };
+    let second_record = TeamRecord {
+        members: vec![TeamMember {
+            name: "reviewer".to_string(),
+            agent_id: ThreadId::new(),
+            agent_type: None,
+            spawned_at: 0,
+        }],
+        created_at: 0,
+    };
+    insert_team_record(lead_thread_id, "team-1".to_string(), first_record)
+        .expect("first insert should succeed");
+    insert_team_record(lead_thread_id, "team-2".to_string(), second_record.clone())
+        .expect("second insert should succeed");
+    let err = insert_team_record(lead_thread_id, "team-2".to_string(), second_record)
+        .expect_err("duplicate team id should fail");
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("team `team-2` already exists".to_string())
+    );
+    remove_team_record(lead_thread_id, "team-1").expect("cleanup should succeed");
+    remove_team_record(lead_thread_id, "team-2").expect("cleanup should succeed");
+}
+
+#[tokio::test]
+async fn team_status_reports_member_statuses_without_waiting() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let config = turn.config.as_ref().clone();
+    let thread = manager.start_thread(config).await.expect("start thread");
+    let running_id = thread.thread_id;
+    let missing_id = ThreadId::new();
+    let session = Arc::new(session);
+    insert_team_record(
+        session.conversation_id,
+        "team-status".to_string(),
+        TeamRecord {
+            members: vec![
+                TeamMember {
+                    name: "worker".to_string(),
+                    agent_id: running_id,
+                    agent_type: Some("explorer".to_string()),
+                    spawned_at: 40,
+                },
+                TeamMember {
+                    name: "gone".to_string(),
+                    agent_id: missing_id,
+                    agent_type: None,
+                    spawned_at: 0,
+                },
+            ],
+            created_at: 42,
+        },
+    )
+    .expect("team insert should succeed");
+
+    let output = timeout(
+        Duration::from_secs(5),
+        MultiAgentHandler.handle(invocation(
+            session.clone(),
+            Arc::new(turn),
+            "team_status",
+            function_payload(json!({"team_id": "team-status"})),
+        )),
+    )
+    .await
+    .expect("team_status should not block")
+    .expect("team_status should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("team_status result should be json");
+    assert_eq!(result["team_id"], json!("team-status"));
+    assert_eq!(result["created_at"], json!(42));
+    let members = result["members"]
+        .as_array()
+        .expect("members should be an array");
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0]["name"], json!("worker"));
+    assert_eq!(members[0]["agent_id"], json!(running_id.to_string()));
+    assert_eq!(members[0]["agent_type"], json!("explorer"));
+    assert_eq!(members[0]["spawned_at"], json!(40));
+    assert_ne!(
+        members[0]["status"],
+        serde_json::to_value(AgentStatus::NotFound).expect("serialize status")
+    );
+    assert_eq!(members[1]["name"], json!("gone"));
+    assert_eq!(
+        members[1]["status"],
+        serde_json::to_value(AgentStatus::NotFound).expect("serialize status")
+    );
+
+    remove_team_record(session.conversation_id, "team-status")
+        .expect("team record should be removed");
+    let _ = thread
+        .thread
+        .submit(Op::Shutdown {})
+        .await
+        .expect("shutdown should submit");
+}
+
+#[tokio::test]
+async fn team_status_rejects_unknown_team() {
+    let (session, turn) = make_session_and_context().await;
+    let Err(err) = MultiAgentHandler
+        .handle(invocation(
+            Arc::new(session),
+            Arc::new(turn),
+            "team_status",
+            function_payload(json!({"team_id": "missing-team"})),
+        ))
+        .await
+    else {
+        panic!("unknown team should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("team `missing-team` not found".to_string())
+    );
+}
+
+#[tokio::test]
+async fn close_all_shuts_down_team_members_and_removes_teams() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let config = turn.config.as_ref().clone();
+    let thread = manager.start_thread(config).await.expect("start thread");
+    let agent_id = thread.thread_id;
+    let status_before = manager.agent_control().get_status(agent_id).await;
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+    let codex_home = turn.config.codex_home.clone();
+    let lead_thread_id = session.conversation_id;
+    let team = TeamRecord {
+        members: vec![TeamMember {
+            name: "worker".to_string(),
+            agent_id,
+            agent_type: None,
+            spawned_at: 1,
+        }],
+        created_at: 1,
+    };
+    insert_team_record(lead_thread_id, "close-all-team".to_string(), team.clone())
+        .expect("team record should be inserted");
+    persist_team_state(
+        codex_home.as_path(),
+        lead_thread_id,
+        "close-all-team",
+        &team,
+    )
+    .await
+    .expect("team state should persist");
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "close_all",
+            function_payload(json!({})),
+        ))
+        .await
+        .expect("close_all should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success,
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    assert_eq!(success, Some(true));
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("close_all result should be json");
+    assert_eq!(
+        result["closed"],
+        json!([{
+            "agent_id": agent_id.to_string(),
+            "team_id": "close-all-team",
+            "name": "worker",
+            "ok": true,
+            "status": serde_json::to_value(status_before).expect("status should serialize"),
+            "error": null,
+        }])
+    );
+    assert_eq!(
+        result["teams"],
+        json!([{"team_id": "close-all-team", "ok": true, "error": null}])
+    );
+
+    let submitted_shutdown = manager
+        .captured_ops()
+        .iter()
+        .any(|(id, op)| *id == agent_id && matches!(op, Op::Shutdown));
+    assert_eq!(submitted_shutdown, true);
+    assert_eq!(
+        manager.agent_control().get_status(agent_id).await,
+        AgentStatus::NotFound
+    );
+    assert!(get_team_record(lead_thread_id, "close-all-team").is_err());
+    assert!(!team_dir(codex_home.as_path(), lead_thread_id, "close-all-team").exists());
+}
+
+#[tokio::test]
+async fn team_rename_moves_registry_entry_and_persisted_state() {
+    let (session, turn) = make_session_and_context().await;
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+    let codex_home = turn.config.codex_home.clone();
+    let lead_thread_id = session.conversation_id;
+    let team = TeamRecord {
+        members: vec![TeamMember {
+            name: "worker".to_string(),
+            agent_id: ThreadId::new(),
+            agent_type: None,
+            spawned_at: 1,
+        }],
+        created_at: 1,
+    };
+    for team_id in ["old-team", "taken-team"] {
+        insert_team_record(lead_thread_id, team_id.to_string(), team.clone())
+            .expect("team record should be inserted");
+        persist_team_state(codex_home.as_path(), lead_thread_id, team_id, &team)
+            .await
+            .expect("team state should persist");
+    }
+    let old_tasks_dir = team_tasks_dir(codex_home.as_path(), lead_thread_id, "old-team");
+    tokio::fs::create_dir_all(&old_tasks_dir)
+        .await
+        .expect("create tasks dir");
+    tokio::fs::write(old_tasks_dir.join("1.json"), "{}")
+        .await
+        .expect("write task file");
+
+    let Err(err) = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "team_rename",
+            function_payload(json!({"team_id": "old-team", "new_team_id": "taken-team"})),
+        ))
+        .await
     else {
-        panic!("expected function output");
+        panic!("renaming onto an existing team should be rejected");
     };
-    assert_eq!(spawn_success, Some(true));
-    let spawn_result: SpawnTeamResult =
-        serde_json::from_str(&spawn_content).expect("spawn_team result should be json");
-    assert_eq!(spawn_result.members.len(), 1);
-    let member_thread_id = agent_id(&spawn_result.members[0].agent_id).expect("valid agent id");
-
-    let spawned_threads = session.services.agent_control.spawned_thread_ids();
-    assert_eq!(spawned_threads.len(), 1);
-    assert_eq!(spawned_threads.contains(&member_thread_id), true);
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("team `taken-team` already exists".to_string())
+    );
+    assert!(get_team_record(lead_thread_id, "old-team").is_ok());
 
-    MultiAgentHandler
+    let output = MultiAgentHandler
         .handle(invocation(
             session.clone(),
             turn.clone(),
-            "delete_team",
-            function_payload(json!({
-                "team_id": spawn_result.team_id
-            })),
+            "team_rename",
+            function_payload(json!({"team_id": "old-team", "new_team_id": "new-team"})),
         ))
         .await
-        .expect("delete_team should succeed");
-
-    let spawned_threads = session.services.agent_control.spawned_thread_ids();
-    assert_eq!(spawned_threads.contains(&member_thread_id), false);
-
-    let unblocked_invocation = invocation(
-        session.clone(),
-        turn.clone(),
-        "spawn_agent",
-        function_payload(json!({"message": "unblocked"})),
-    );
-    let unblocked_output = MultiAgentHandler
-        .handle(unblocked_invocation)
-        .await
-        .expect("spawn_agent should succeed after delete_team releases slot");
+        .expect("team_rename should succeed");
     let ToolOutput::Function {
-        body: FunctionCallOutputBody::Text(unblocked_content),
-        success: unblocked_success,
+        body: FunctionCallOutputBody::Text(content),
         ..
-    } = unblocked_output
+    } = output
     else {
         panic!("expected function output");
     };
-    assert_eq!(unblocked_success, Some(true));
-    let unblocked_result: SpawnAgentResult =
-        serde_json::from_str(&unblocked_content).expect("spawn_agent result should be json");
-    let unblocked_thread_id = agent_id(&unblocked_result.agent_id).expect("valid agent id");
-    let _ = manager
-        .agent_control()
-        .shutdown_agent(unblocked_thread_id)
-        .await;
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("team_rename result should be json");
+    assert_eq!(
+        result,
+        json!({"previous_team_id": "old-team", "team_id": "new-team"})
+    );
+    assert!(get_team_record(lead_thread_id, "old-team").is_err());
+    assert_eq!(
+        get_team_record(lead_thread_id, "new-team")
+            .expect("renamed team should be registered")
+            .members
+            .len(),
+        1
+    );
+    let persisted = read_persisted_team_config(codex_home.as_path(), lead_thread_id, "new-team")
+        .await
+        .expect("renamed team config should exist");
+    assert_eq!(persisted.team_name, "new-team");
+    assert!(
+        team_tasks_dir(codex_home.as_path(), lead_thread_id, "new-team")
+            .join("1.json")
+            .exists()
+    );
+    assert!(!team_dir(codex_home.as_path(), lead_thread_id, "old-team").exists());
+    assert!(!old_tasks_dir.exists());
+
+    for team_id in ["new-team", "taken-team"] {
+        remove_team_record(lead_thread_id, team_id).expect("team record should be removed");
+    }
 }
 
-#[test]
-fn insert_team_record_allows_multiple_teams_per_session() {
-    let lead_thread_id = ThreadId::new();
-    let first_record = TeamRecord {
-        members: vec![TeamMember {
-            name: "worker".to_string(),
-            agent_id: ThreadId::new(),
-            agent_type: None,
-        }],
-        created_at: 0,
-    };
-    let second_record = TeamRecord {
-        members: vec![TeamMember {
-            name: "reviewer".to_string(),
-            agent_id: ThreadId::new(),
-            agent_type: None,
-        }],
-        created_at: 0,
+#[tokio::test]
+async fn team_gc_removes_own_teams_without_live_members_and_keeps_other_leads_teams() {
+    let (session, turn) = make_session_and_context().await;
+    let codex_home = turn.config.codex_home.clone();
+    let orphan_id = ThreadId::new().to_string();
+    let orphan_lead_thread_id = session.conversation_id;
+    let other_id = ThreadId::new().to_string();
+    let other_lead_thread_id = ThreadId::new();
+    for (lead_thread_id, team_id) in [
+        (orphan_lead_thread_id, &orphan_id),
+        (other_lead_thread_id, &other_id),
+    ] {
+        let config = PersistedTeamConfig {
+            team_name: team_id.clone(),
+            lead_thread_id: lead_thread_id.to_string(),
+            created_at: 1,
+            members: vec![PersistedTeamMember {
+                name: "worker".to_string(),
+                agent_id: ThreadId::new().to_string(),
+                agent_type: None,
+                spawned_at: 1,
+            }],
+        };
+        write_json_atomic(
+            &team_config_path(codex_home.as_path(), lead_thread_id, team_id),
+            &config,
+        )
+        .await
+        .expect("write orphaned team config");
+    }
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            Arc::new(session),
+            Arc::new(turn),
+            "team_gc",
+            function_payload(json!({})),
+        ))
+        .await
+        .expect("team_gc should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
     };
-    insert_team_record(lead_thread_id, "team-1".to_string(), first_record)
-        .expect("first insert should succeed");
-    insert_team_record(lead_thread_id, "team-2".to_string(), second_record.clone())
-        .expect("second insert should succeed");
-    let err = insert_team_record(lead_thread_id, "team-2".to_string(), second_record)
-        .expect_err("duplicate team id should fail");
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("team_gc result should be json");
     assert_eq!(
-        err,
-        FunctionCallError::RespondToModel("team `team-2` already exists".to_string())
+        result["teams"],
+        json!([{"team_id": orphan_id, "outcome": "removed"}])
     );
-    remove_team_record(lead_thread_id, "team-1").expect("cleanup should succeed");
-    remove_team_record(lead_thread_id, "team-2").expect("cleanup should succeed");
+    assert_eq!(
+        tokio::fs::metadata(team_dir(
+            codex_home.as_path(),
+            orphan_lead_thread_id,
+            &orphan_id
+        ))
+        .await
+        .is_err(),
+        true
+    );
+    // Another lead's members may be alive in a different process, so its team is left alone.
+    assert!(team_config_path(codex_home.as_path(), other_lead_thread_id, &other_id).exists());
 }
 
 #[tokio::test]
@@ -2371,6 +4048,7 @@ async fn spawn_is_rejected_for_agent_team_teammates() {
                 name: "worker".to_string(),
                 agent_id: member_thread_id,
                 agent_type: None,
+                spawned_at: 0,
             }],
             created_at: 0,
         },
@@ -2459,8 +4137,12 @@ async fn spawn_team_wait_team_and_close_team_flow() {
     for member in &spawn_result.members {
         assert_eq!(member.status, AgentStatus::PendingInit);
     }
-    let persisted_config_path =
-        team_config_path(turn.config.codex_home.as_path(), &spawn_result.team_id);
+    let lead_thread_id = session.conversation_id;
+    let persisted_config_path = team_config_path(
+        turn.config.codex_home.as_path(),
+        lead_thread_id,
+        &spawn_result.team_id,
+    );
     let persisted_config_raw = tokio::fs::read_to_string(&persisted_config_path)
         .await
         .expect("team config should be persisted");
@@ -2473,8 +4155,11 @@ async fn spawn_team_wait_team_and_close_team_flow() {
     );
     assert_eq!(persisted_config.members.len(), 2);
 
-    let persisted_tasks_dir =
-        team_tasks_dir(turn.config.codex_home.as_path(), &spawn_result.team_id);
+    let persisted_tasks_dir = team_tasks_dir(
+        turn.config.codex_home.as_path(),
+        lead_thread_id,
+        &spawn_result.team_id,
+    );
     assert_eq!(
         tokio::fs::metadata(&persisted_tasks_dir).await.is_err(),
         true
@@ -2552,6 +4237,7 @@ async fn spawn_team_wait_team_and_close_team_flow() {
     assert_eq!(
         tokio::fs::metadata(team_dir(
             turn.config.codex_home.as_path(),
+            lead_thread_id,
             &spawn_result.team_id
         ))
         .await
@@ -2559,14 +4245,236 @@ async fn spawn_team_wait_team_and_close_team_flow() {
         true
     );
     assert_eq!(
-        tokio::fs::metadata(team_tasks_dir(
-            turn.config.codex_home.as_path(),
-            &spawn_result.team_id,
+        tokio::fs::metadata(team_tasks_dir(
+            turn.config.codex_home.as_path(),
+            lead_thread_id,
+            &spawn_result.team_id,
+        ))
+        .await
+        .is_err(),
+        true
+    );
+}
+
+#[tokio::test]
+async fn team_add_member_appends_and_persists_new_member() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+
+    let spawn_output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "create_team",
+            function_payload(json!({
+                "members": [{"name": "planner", "task": "plan the work"}]
+            })),
+        ))
+        .await
+        .expect("create_team should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(spawn_content),
+        ..
+    } = spawn_output
+    else {
+        panic!("expected function output");
+    };
+    let spawn_result: SpawnTeamResult =
+        serde_json::from_str(&spawn_content).expect("create_team result should be json");
+
+    let Err(err) = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "team_add_member",
+            function_payload(json!({
+                "team_id": spawn_result.team_id,
+                "name": "planner",
+                "task": "plan again"
+            })),
+        ))
+        .await
+    else {
+        panic!("duplicate member name should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("duplicate member name `planner`".to_string())
+    );
+
+    let add_output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "team_add_member",
+            function_payload(json!({
+                "team_id": spawn_result.team_id,
+                "name": "reviewer",
+                "task": "review the plan"
+            })),
+        ))
+        .await
+        .expect("team_add_member should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(add_content),
+        success,
+        ..
+    } = add_output
+    else {
+        panic!("expected function output");
+    };
+    assert_eq!(success, Some(true));
+    let add_result: serde_json::Value =
+        serde_json::from_str(&add_content).expect("team_add_member result should be json");
+    assert_eq!(add_result["name"], json!("reviewer"));
+    let reviewer_id = add_result["agent_id"]
+        .as_str()
+        .map(agent_id)
+        .expect("agent_id should be present")
+        .expect("agent_id should be valid");
+
+    let team = get_team_record(session.conversation_id, &spawn_result.team_id)
+        .expect("team should still exist");
+    assert_eq!(
+        team.members
+            .iter()
+            .map(|member| member.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["planner", "reviewer"]
+    );
+    let persisted = read_persisted_team_config(
+        turn.config.codex_home.as_path(),
+        session.conversation_id,
+        &spawn_result.team_id,
+    )
+    .await
+    .expect("team config should be persisted");
+    assert_eq!(
+        persisted
+            .members
+            .iter()
+            .map(|member| member.agent_id.clone())
+            .collect::<Vec<_>>()
+            .last(),
+        Some(&reviewer_id.to_string())
+    );
+    let captured_reviewer_input = manager
+        .captured_ops()
+        .iter()
+        .any(|(id, op)| *id == reviewer_id && matches!(op, Op::UserInput { .. }));
+    assert_eq!(captured_reviewer_input, true);
+
+    MultiAgentHandler
+        .handle(invocation(
+            session,
+            turn,
+            "delete_team",
+            function_payload(json!({"team_id": spawn_result.team_id})),
+        ))
+        .await
+        .expect("delete_team should succeed");
+}
+
+#[tokio::test]
+async fn create_team_emits_spawn_events_per_member() {
+    let (mut session, turn, rx) = make_session_and_context_with_rx().await;
+    let manager = thread_manager();
+    Arc::get_mut(&mut session)
+        .expect("session should be unique")
+        .services
+        .agent_control = manager.agent_control();
+
+    let spawn_output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "create_team",
+            function_payload(json!({
+                "members": [
+                    {"name": "planner", "task": "plan the work", "nickname": "Ada"},
+                    {"name": "worker", "task": "execute the task"}
+                ]
+            })),
+        ))
+        .await
+        .expect("create_team should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(spawn_content),
+        ..
+    } = spawn_output
+    else {
+        panic!("expected function output");
+    };
+    let spawn_result: SpawnTeamResult =
+        serde_json::from_str(&spawn_content).expect("create_team result should be json");
+    let mut thread_nicknames = Vec::new();
+    for member in &spawn_result.members {
+        let (nickname, _role) = session
+            .services
+            .agent_control
+            .get_agent_nickname_and_role(agent_id(&member.agent_id).expect("member agent_id"))
+            .await
+            .expect("member should have metadata");
+        thread_nicknames.push((member.name.clone(), nickname));
+    }
+    assert_eq!(
+        thread_nicknames,
+        vec![
+            ("planner".to_string(), Some("Ada".to_string())),
+            ("worker".to_string(), Some("worker".to_string())),
+        ],
+        "thread nicknames default to the member name"
+    );
+
+    let spawn_events = timeout(Duration::from_secs(5), async {
+        let mut spawn_events = Vec::new();
+        loop {
+            let event = rx.recv().await.expect("event should be received");
+            match event.msg {
+                codex_protocol::protocol::EventMsg::CollabAgentSpawnBegin(ev) => {
+                    spawn_events.push(format!("begin {}", ev.call_id));
+                }
+                codex_protocol::protocol::EventMsg::CollabAgentSpawnEnd(ev) => {
+                    assert!(ev.new_thread_id.is_some());
+                    spawn_events.push(format!(
+                        "end {} {}",
+                        ev.call_id,
+                        ev.new_agent_nickname.unwrap_or_default()
+                    ));
+                }
+                codex_protocol::protocol::EventMsg::CollabWaitingEnd(ev)
+                    if ev.call_id == "team/spawn:call-1" =>
+                {
+                    break spawn_events;
+                }
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("create_team should emit a CollabWaitingEnd event");
+    assert_eq!(
+        spawn_events,
+        vec![
+            "begin team/spawn:call-1/planner".to_string(),
+            "end team/spawn:call-1/planner planner".to_string(),
+            "begin team/spawn:call-1/worker".to_string(),
+            "end team/spawn:call-1/worker worker".to_string(),
+        ]
+    );
+
+    MultiAgentHandler
+        .handle(invocation(
+            session,
+            turn,
+            "delete_team",
+            function_payload(json!({"team_id": spawn_result.team_id})),
         ))
         .await
-        .is_err(),
-        true
-    );
+        .expect("delete_team should succeed");
 }
 
 #[tokio::test]
@@ -2752,12 +4660,14 @@ async fn delete_team_only_removes_requested_team_when_multiple_teams_exist() {
 
     let team_a_dir_exists = tokio::fs::metadata(team_dir(
         turn.config.codex_home.as_path(),
+        session.conversation_id,
         &spawn_team_a_result.team_id,
     ))
     .await
     .is_ok();
     let team_b_dir_exists = tokio::fs::metadata(team_dir(
         turn.config.codex_home.as_path(),
+        session.conversation_id,
         &spawn_team_b_result.team_id,
     ))
     .await
@@ -2766,6 +4676,7 @@ async fn delete_team_only_removes_requested_team_when_multiple_teams_exist() {
     assert_eq!(team_b_dir_exists, true);
     let team_b_tasks_exist = tokio::fs::metadata(team_tasks_dir(
         turn.config.codex_home.as_path(),
+        session.conversation_id,
         &spawn_team_b_result.team_id,
     ))
     .await
@@ -2870,41 +4781,350 @@ async fn spawn_team_accepts_backendground_alias() {
     let spawn_output = MultiAgentHandler
         .handle(spawn_invocation)
         .await
-        .expect("spawn_team should accept backendground alias");
+        .expect("spawn_team should accept backendground alias");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(spawn_content),
+        success: spawn_success,
+        ..
+    } = spawn_output
+    else {
+        panic!("expected function output");
+    };
+    let spawn_result: SpawnTeamResult =
+        serde_json::from_str(&spawn_content).expect("spawn_team result should be json");
+    assert_eq!(spawn_success, Some(true));
+    assert_eq!(spawn_result.team_id, team_id);
+    assert_eq!(spawn_result.members.len(), 1);
+
+    let delete_invocation = invocation(
+        session,
+        turn,
+        "delete_team",
+        function_payload(json!({
+            "team_id": spawn_result.team_id
+        })),
+    );
+    let delete_output = MultiAgentHandler
+        .handle(delete_invocation)
+        .await
+        .expect("delete_team should succeed");
+    let ToolOutput::Function {
+        success: delete_success,
+        ..
+    } = delete_output
+    else {
+        panic!("expected function output");
+    };
+    assert_eq!(delete_success, Some(true));
+}
+
+#[tokio::test]
+async fn create_team_best_effort_keeps_members_that_spawned() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+    let team_id = ThreadId::new().to_string();
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "create_team",
+            function_payload(json!({
+                "team_id": team_id,
+                "best_effort": true,
+                "members": [
+                    {"name": "planner", "task": "plan the work"},
+                    {"name": "broken", "task": "work", "model_provider": "missing-provider"}
+                ]
+            })),
+        ))
+        .await
+        .expect("best-effort create_team should succeed with a partial team");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("create_team result should be json");
+    let members = result["members"]
+        .as_array()
+        .expect("members should be an array");
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0]["name"], json!("planner"));
+    assert_eq!(
+        result["failed"],
+        json!([{
+            "name": "broken",
+            "error": "model_provider `missing-provider` not found"
+        }])
+    );
+    let team = get_team_record(session.conversation_id, &team_id).expect("team should exist");
+    assert_eq!(team.members.len(), 1);
+
+    MultiAgentHandler
+        .handle(invocation(
+            session,
+            turn,
+            "delete_team",
+            function_payload(json!({"team_id": team_id})),
+        ))
+        .await
+        .expect("delete_team should succeed");
+}
+
+#[tokio::test]
+async fn create_team_validates_every_member_before_spawning() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+    let team_id = ThreadId::new().to_string();
+
+    let Err(err) = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "create_team",
+            function_payload(json!({
+                "team_id": team_id,
+                "members": [
+                    {"name": "planner", "task": "plan the work"},
+                    {"name": "builder", "task": "build it"},
+                    {"name": "broken", "task": "work", "model": "  "}
+                ]
+            })),
+        ))
+        .await
+    else {
+        panic!("an invalid member should fail create_team");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("model must be non-empty when provided".to_string())
+    );
+    assert!(manager.captured_ops().is_empty());
+    assert!(get_team_record(session.conversation_id, &team_id).is_err());
+    assert!(
+        tokio::fs::metadata(team_dir(
+            turn.config.codex_home.as_path(),
+            session.conversation_id,
+            &team_id
+        ))
+        .await
+        .is_err()
+    );
+}
+
+#[tokio::test]
+async fn create_team_spawn_timeout_must_be_positive_and_bounds_spawning() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+    let team_id = ThreadId::new().to_string();
+
+    let Err(err) = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "create_team",
+            function_payload(json!({
+                "team_id": team_id,
+                "spawn_timeout_ms": 0,
+                "members": [{"name": "planner", "task": "plan the work"}]
+            })),
+        ))
+        .await
+    else {
+        panic!("zero spawn_timeout_ms should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("spawn_timeout_ms must be greater than zero".to_string())
+    );
+    assert!(manager.captured_ops().is_empty());
+
+    MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "create_team",
+            function_payload(json!({
+                "team_id": team_id,
+                "spawn_timeout_ms": 60_000,
+                "members": [{"name": "planner", "task": "plan the work"}]
+            })),
+        ))
+        .await
+        .expect("create_team within spawn_timeout_ms should succeed");
+    let team = get_team_record(session.conversation_id, &team_id).expect("team should exist");
+    assert_eq!(team.members.len(), 1);
+
+    MultiAgentHandler
+        .handle(invocation(
+            session,
+            turn,
+            "delete_team",
+            function_payload(json!({"team_id": team_id})),
+        ))
+        .await
+        .expect("delete_team should succeed");
+}
+
+#[tokio::test]
+async fn create_team_dry_run_validates_without_spawning() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+    let team_id = ThreadId::new().to_string();
+    let expected_provider = turn.config.model_provider_id.clone();
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "create_team",
+            function_payload(json!({
+                "team_id": team_id,
+                "dry_run": true,
+                "members": [
+                    {"name": "scout", "task": "map the repo", "reasoning_effort": "low"}
+                ]
+            })),
+        ))
+        .await
+        .expect("dry run should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("dry run result should be json");
+    assert_eq!(result["dry_run"], json!(true));
+    assert_eq!(result["members"][0]["name"], json!("scout"));
+    assert_eq!(result["members"][0]["task"], json!("map the repo"));
+    assert_eq!(
+        result["members"][0]["model_provider"],
+        json!(expected_provider)
+    );
+    assert_eq!(result["members"][0]["reasoning_effort"], json!("low"));
+    assert!(manager.captured_ops().is_empty());
+    assert!(get_team_record(session.conversation_id, &team_id).is_err());
+    assert!(
+        tokio::fs::metadata(team_dir(
+            turn.config.codex_home.as_path(),
+            session.conversation_id,
+            &team_id
+        ))
+        .await
+        .is_err()
+    );
+
+    let Err(err) = MultiAgentHandler
+        .handle(invocation(
+            session,
+            turn,
+            "create_team",
+            function_payload(json!({
+                "dry_run": true,
+                "members": [
+                    {"name": "broken", "task": "work", "model_provider": "missing-provider"}
+                ]
+            })),
+        ))
+        .await
+    else {
+        panic!("dry run should report provider errors");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(
+            "model_provider `missing-provider` not found".to_string()
+        )
+    );
+}
+
+#[tokio::test]
+async fn create_team_applies_team_defaults_to_members_without_overrides() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+    let provider = turn.config.model_provider_id.clone();
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "create_team",
+            function_payload(json!({
+                "dry_run": true,
+                "default_model": "team-model",
+                "default_model_provider": provider,
+                "default_agent_type": "team-role",
+                "members": [
+                    {"name": "uniform", "task": "follow the team defaults"},
+                    {
+                        "name": "custom",
+                        "task": "use member overrides",
+                        "model": "member-model",
+                        "agent_type": "member-role"
+                    }
+                ]
+            })),
+        ))
+        .await
+        .expect("dry run should succeed");
     let ToolOutput::Function {
-        body: FunctionCallOutputBody::Text(spawn_content),
-        success: spawn_success,
+        body: FunctionCallOutputBody::Text(content),
         ..
-    } = spawn_output
+    } = output
     else {
         panic!("expected function output");
     };
-    let spawn_result: SpawnTeamResult =
-        serde_json::from_str(&spawn_content).expect("spawn_team result should be json");
-    assert_eq!(spawn_success, Some(true));
-    assert_eq!(spawn_result.team_id, team_id);
-    assert_eq!(spawn_result.members.len(), 1);
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("dry run result should be json");
+    assert_eq!(result["members"][0]["model"], json!("team-model"));
+    assert_eq!(result["members"][0]["agent_type"], json!("team-role"));
+    assert_eq!(result["members"][0]["model_provider"], json!(provider));
+    assert_eq!(result["members"][1]["model"], json!("member-model"));
+    assert_eq!(result["members"][1]["agent_type"], json!("member-role"));
 
-    let delete_invocation = invocation(
-        session,
-        turn,
-        "delete_team",
-        function_payload(json!({
-            "team_id": spawn_result.team_id
-        })),
-    );
-    let delete_output = MultiAgentHandler
-        .handle(delete_invocation)
+    let Err(err) = MultiAgentHandler
+        .handle(invocation(
+            session,
+            turn,
+            "create_team",
+            function_payload(json!({
+                "dry_run": true,
+                "default_model_provider": "missing-provider",
+                "members": [{"name": "broken", "task": "work"}]
+            })),
+        ))
         .await
-        .expect("delete_team should succeed");
-    let ToolOutput::Function {
-        success: delete_success,
-        ..
-    } = delete_output
     else {
-        panic!("expected function output");
+        panic!("team default provider should be validated like a member provider");
     };
-    assert_eq!(delete_success, Some(true));
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(
+            "model_provider `missing-provider` not found".to_string()
+        )
+    );
 }
 
 #[tokio::test]
@@ -3062,7 +5282,7 @@ async fn spawn_team_background_member_auto_closes_after_shutdown() {
 }
 
 #[tokio::test]
-async fn spawn_team_worktree_failure_cleans_already_spawned_members() {
+async fn spawn_team_worktree_precheck_fails_before_spawning_members() {
     let (mut session, mut turn) = make_session_and_context().await;
     let manager = thread_manager();
     session.services.agent_control = manager.agent_control();
@@ -3095,21 +5315,25 @@ async fn spawn_team_worktree_failure_cleans_already_spawned_members() {
         )
     );
 
-    let shutdown_submitted = manager
-        .captured_ops()
-        .iter()
-        .any(|(_, op)| matches!(op, Op::Shutdown));
-    assert_eq!(shutdown_submitted, true);
+    assert!(manager.captured_ops().is_empty());
     assert_eq!(
-        tokio::fs::metadata(team_dir(codex_home.as_path(), &team_id))
-            .await
-            .is_err(),
+        tokio::fs::metadata(team_dir(
+            codex_home.as_path(),
+            session.conversation_id,
+            &team_id
+        ))
+        .await
+        .is_err(),
         true
     );
     assert_eq!(
-        tokio::fs::metadata(team_tasks_dir(codex_home.as_path(), &team_id))
-            .await
-            .is_err(),
+        tokio::fs::metadata(team_tasks_dir(
+            codex_home.as_path(),
+            session.conversation_id,
+            &team_id
+        ))
+        .await
+        .is_err(),
         true
     );
 
@@ -3226,6 +5450,14 @@ async fn delete_team_cleans_worktree_leases_for_worktree_members() {
         assert_eq!(member.ok, true);
         assert_eq!(member.error, None);
     }
+    let mut removed_worktrees = delete_result.removed_worktrees.clone();
+    removed_worktrees.sort();
+    let mut expected_worktrees = worktree_paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>();
+    expected_worktrees.sort();
+    assert_eq!(removed_worktrees, expected_worktrees);
     for worktree_path in worktree_paths {
         assert_eq!(std::fs::metadata(worktree_path).is_err(), true);
     }
@@ -3235,6 +5467,82 @@ async fn delete_team_cleans_worktree_leases_for_worktree_members() {
     );
 }
 
+#[tokio::test]
+async fn create_team_shared_worktree_is_removed_after_last_member() {
+    let (mut session, mut turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let repo_dir = tempfile::tempdir().expect("temp dir");
+    turn.cwd = repo_dir.path().to_path_buf();
+
+    init_git_repo(turn.cwd.as_path());
+    let lead_thread_id = session.conversation_id;
+    let codex_home = turn.config.codex_home.clone();
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+
+    let spawn_invocation = invocation(
+        session.clone(),
+        turn.clone(),
+        "create_team",
+        function_payload(json!({
+            "shared_worktree": true,
+            "members": [
+                {"name": "planner", "task": "plan"},
+                {"name": "worker", "task": "work", "worktree": true}
+            ]
+        })),
+    );
+    let spawn_output = MultiAgentHandler
+        .handle(spawn_invocation)
+        .await
+        .expect("create_team with a shared worktree should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(spawn_content),
+        ..
+    } = spawn_output
+    else {
+        panic!("expected function output");
+    };
+    let spawn_result: serde_json::Value =
+        serde_json::from_str(&spawn_content).expect("create_team result should be json");
+    let members = spawn_result["members"]
+        .as_array()
+        .expect("members should be an array");
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0]["cwd"], members[1]["cwd"]);
+    assert_eq!(members[0]["worktree_path"], members[1]["worktree_path"]);
+
+    let worktree_paths = list_worktree_paths(codex_home.as_path(), lead_thread_id);
+    assert_eq!(worktree_paths.len(), 1);
+    let worktree_path = worktree_paths[0].clone();
+
+    let first_member = agent_id(members[0]["agent_id"].as_str().expect("agent id"))
+        .expect("member agent id should be valid");
+    cleanup_agent_worktree(session.as_ref(), turn.as_ref(), first_member)
+        .await
+        .expect("releasing one member should succeed");
+    assert_eq!(worktree_path.exists(), true);
+
+    let delete_invocation = invocation(
+        session,
+        turn,
+        "delete_team",
+        function_payload(json!({
+            "team_id": spawn_result["team_id"]
+        })),
+    );
+    MultiAgentHandler
+        .handle(delete_invocation)
+        .await
+        .expect("delete_team should succeed");
+    assert_eq!(worktree_path.exists(), false);
+    assert_eq!(
+        list_worktree_paths(codex_home.as_path(), lead_thread_id).is_empty(),
+        true
+    );
+}
+
 #[tokio::test]
 async fn delete_team_removes_worktrees_when_members_are_already_shutdown() {
     let (mut session, mut turn) = make_session_and_context().await;
@@ -3333,6 +5641,7 @@ async fn delete_team_removes_worktrees_when_members_are_already_shutdown() {
     assert_eq!(
         tokio::fs::metadata(team_config_path(
             codex_home.as_path(),
+            lead_thread_id,
             &delete_result.team_id
         ))
         .await
@@ -3340,9 +5649,13 @@ async fn delete_team_removes_worktrees_when_members_are_already_shutdown() {
         true
     );
     assert_eq!(
-        tokio::fs::metadata(team_tasks_dir(codex_home.as_path(), &delete_result.team_id))
-            .await
-            .is_err(),
+        tokio::fs::metadata(team_tasks_dir(
+            codex_home.as_path(),
+            lead_thread_id,
+            &delete_result.team_id
+        ))
+        .await
+        .is_err(),
         true
     );
 }
@@ -3510,57 +5823,327 @@ async fn send_message_team_and_broadcast_send_inputs() {
         serde_json::from_str(&broadcast_content).expect("send_message result should be json");
     assert_eq!(broadcast_result["route"].as_str(), Some("broadcast"));
     assert_eq!(
-        broadcast_result["sent"]
-            .as_array()
-            .map(Vec::len)
-            .unwrap_or(0)
-            + broadcast_result["failed"]
-                .as_array()
-                .map(Vec::len)
-                .unwrap_or(0),
-        spawn_result.members.len()
+        broadcast_result["sent"]
+            .as_array()
+            .map(Vec::len)
+            .unwrap_or(0)
+            + broadcast_result["failed"]
+                .as_array()
+                .map(Vec::len)
+                .unwrap_or(0),
+        spawn_result.members.len()
+    );
+    for sent in broadcast_result["sent"]
+        .as_array()
+        .expect("sent should be an array")
+    {
+        assert_eq!(sent["member_name"].as_str().map(str::is_empty), Some(false));
+        assert_eq!(sent["agent_id"].as_str().map(str::is_empty), Some(false));
+        assert_eq!(
+            sent["submission_id"].as_str().map(str::is_empty),
+            Some(false)
+        );
+    }
+    for failed in broadcast_result["failed"]
+        .as_array()
+        .expect("failed should be an array")
+    {
+        assert_eq!(
+            failed["member_name"].as_str().map(str::is_empty),
+            Some(false)
+        );
+        assert_eq!(failed["agent_id"].as_str().map(str::is_empty), Some(false));
+        assert_eq!(failed["error"].as_str().map(str::is_empty), Some(false));
+    }
+
+    let user_input_count = manager
+        .captured_ops()
+        .iter()
+        .filter(|(id, op)| member_ids.contains(id) && matches!(op, Op::UserInput { .. }))
+        .count();
+    assert_eq!(user_input_count > 0, true);
+
+    MultiAgentHandler
+        .handle(invocation(
+            session,
+            turn,
+            "delete_team",
+            function_payload(json!({
+                "team_id": spawn_result.team_id
+            })),
+        ))
+        .await
+        .expect("delete_team should succeed");
+}
+
+#[tokio::test]
+async fn send_message_broadcast_limits_delivery_to_selected_members() {
+    let (session, turn) = make_session_and_context().await;
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+    let worker_id = ThreadId::new();
+    insert_team_record(
+        session.conversation_id,
+        "team-subset".to_string(),
+        TeamRecord {
+            members: vec![
+                TeamMember {
+                    name: "planner".to_string(),
+                    agent_id: ThreadId::new(),
+                    agent_type: None,
+                    spawned_at: 0,
+                },
+                TeamMember {
+                    name: "worker".to_string(),
+                    agent_id: worker_id,
+                    agent_type: None,
+                    spawned_at: 0,
+                },
+            ],
+            created_at: 0,
+        },
+    )
+    .expect("team insert should succeed");
+
+    let Err(err) = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "send_message",
+            function_payload(json!({
+                "team_id": "team-subset",
+                "to": "*",
+                "message": "status update",
+                "broadcast": true,
+                "members": ["reviewer"]
+            })),
+        ))
+        .await
+    else {
+        panic!("unknown broadcast member should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(
+            "member `reviewer` not found in team `team-subset`".to_string()
+        )
+    );
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn,
+            "send_message",
+            function_payload(json!({
+                "team_id": "team-subset",
+                "to": "*",
+                "message": "status update",
+                "broadcast": true,
+                "members": ["worker"]
+            })),
+        ))
+        .await
+        .expect("send_message broadcast should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("send_message result should be json");
+    let recipients = ["sent", "failed"]
+        .iter()
+        .flat_map(|key| {
+            result[*key]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+        })
+        .map(|entry| {
+            entry["member_name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(recipients, vec!["worker".to_string()]);
+
+    remove_team_record(session.conversation_id, "team-subset")
+        .expect("team record should be removed");
+}
+
+#[tokio::test]
+async fn send_message_broadcast_filters_members_by_agent_type() {
+    let (session, turn) = make_session_and_context().await;
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+    insert_team_record(
+        session.conversation_id,
+        "team-roles".to_string(),
+        TeamRecord {
+            members: vec![
+                TeamMember {
+                    name: "planner".to_string(),
+                    agent_id: ThreadId::new(),
+                    agent_type: None,
+                    spawned_at: 0,
+                },
+                TeamMember {
+                    name: "reviewer-a".to_string(),
+                    agent_id: ThreadId::new(),
+                    agent_type: Some("reviewer".to_string()),
+                    spawned_at: 0,
+                },
+                TeamMember {
+                    name: "reviewer-b".to_string(),
+                    agent_id: ThreadId::new(),
+                    agent_type: Some(" Reviewer ".to_string()),
+                    spawned_at: 0,
+                },
+            ],
+            created_at: 0,
+        },
+    )
+    .expect("team insert should succeed");
+
+    let broadcast = |args: serde_json::Value| {
+        let session = session.clone();
+        let turn = turn.clone();
+        async move {
+            let output = MultiAgentHandler
+                .handle(invocation(
+                    session,
+                    turn,
+                    "send_message",
+                    function_payload(args),
+                ))
+                .await?;
+            let ToolOutput::Function {
+                body: FunctionCallOutputBody::Text(content),
+                ..
+            } = output
+            else {
+                panic!("expected function output");
+            };
+            let result: serde_json::Value =
+                serde_json::from_str(&content).expect("send_message result should be json");
+            let mut recipients = ["sent", "failed"]
+                .iter()
+                .flat_map(|key| result[*key].as_array().cloned().unwrap_or_default())
+                .map(|entry| {
+                    entry["member_name"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+            recipients.sort();
+            Ok::<_, FunctionCallError>(recipients)
+        }
+    };
+
+    let recipients = broadcast(json!({
+        "team_id": "team-roles",
+        "to": "*",
+        "message": "please review",
+        "broadcast": true,
+        "agent_type": "REVIEWER"
+    }))
+    .await
+    .expect("agent_type broadcast should succeed");
+    assert_eq!(
+        recipients,
+        vec!["reviewer-a".to_string(), "reviewer-b".to_string()]
+    );
+
+    let recipients = broadcast(json!({
+        "team_id": "team-roles",
+        "to": "*",
+        "message": "please review",
+        "broadcast": true,
+        "members": ["planner", "reviewer-b"],
+        "agent_type": "reviewer"
+    }))
+    .await
+    .expect("intersected broadcast should succeed");
+    assert_eq!(recipients, vec!["reviewer-b".to_string()]);
+
+    let recipients = broadcast(json!({
+        "team_id": "team-roles",
+        "to": "*",
+        "message": "plan",
+        "broadcast": true,
+        "agent_type": "default"
+    }))
+    .await
+    .expect("default agent_type broadcast should succeed");
+    assert_eq!(recipients, vec!["planner".to_string()]);
+
+    let err = broadcast(json!({
+        "team_id": "team-roles",
+        "to": "*",
+        "message": "hello",
+        "broadcast": true,
+        "agent_type": "tester"
+    }))
+    .await
+    .expect_err("unmatched agent_type should be rejected");
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(
+            "no selected members of team `team-roles` have agent_type `tester`".to_string()
+        )
     );
-    for sent in broadcast_result["sent"]
-        .as_array()
-        .expect("sent should be an array")
-    {
-        assert_eq!(sent["member_name"].as_str().map(str::is_empty), Some(false));
-        assert_eq!(sent["agent_id"].as_str().map(str::is_empty), Some(false));
-        assert_eq!(
-            sent["submission_id"].as_str().map(str::is_empty),
-            Some(false)
-        );
-    }
-    for failed in broadcast_result["failed"]
-        .as_array()
-        .expect("failed should be an array")
-    {
-        assert_eq!(
-            failed["member_name"].as_str().map(str::is_empty),
-            Some(false)
-        );
-        assert_eq!(failed["agent_id"].as_str().map(str::is_empty), Some(false));
-        assert_eq!(failed["error"].as_str().map(str::is_empty), Some(false));
-    }
 
-    let user_input_count = manager
-        .captured_ops()
-        .iter()
-        .filter(|(id, op)| member_ids.contains(id) && matches!(op, Op::UserInput { .. }))
-        .count();
-    assert_eq!(user_input_count > 0, true);
+    remove_team_record(session.conversation_id, "team-roles")
+        .expect("team record should be removed");
+}
 
-    MultiAgentHandler
+#[tokio::test]
+async fn team_message_wait_rejects_unknown_member() {
+    let (session, turn) = make_session_and_context().await;
+    let session = Arc::new(session);
+    insert_team_record(
+        session.conversation_id,
+        "team-message-wait".to_string(),
+        TeamRecord {
+            members: vec![TeamMember {
+                name: "worker".to_string(),
+                agent_id: ThreadId::new(),
+                agent_type: None,
+                spawned_at: 0,
+            }],
+            created_at: 0,
+        },
+    )
+    .expect("team insert should succeed");
+
+    let Err(err) = MultiAgentHandler
         .handle(invocation(
-            session,
-            turn,
-            "delete_team",
+            session.clone(),
+            Arc::new(turn),
+            "team_message_wait",
             function_payload(json!({
-                "team_id": spawn_result.team_id
+                "team_id": "team-message-wait",
+                "to": "reviewer",
+                "message": "review this"
             })),
         ))
         .await
-        .expect("delete_team should succeed");
+    else {
+        panic!("unknown member should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(
+            "member `reviewer` not found in team `team-message-wait`".to_string()
+        )
+    );
+    remove_team_record(session.conversation_id, "team-message-wait")
+        .expect("team record should be removed");
 }
 
 #[tokio::test]
@@ -4096,3 +6679,373 @@ async fn build_agent_resume_config_clears_base_instructions() {
         .expect("sandbox policy set");
     assert_eq!(config, expected);
 }
+
+#[tokio::test]
+async fn list_persisted_teams_orders_by_creation_and_skips_corrupt_configs() {
+    let codex_home = tempfile::tempdir().expect("temp dir");
+    let team = |name: &str, created_at: i64| PersistedTeamConfig {
+        team_name: name.to_string(),
+        lead_thread_id: ThreadId::new().to_string(),
+        created_at,
+        members: vec![PersistedTeamMember {
+            name: "worker".to_string(),
+            agent_id: ThreadId::new().to_string(),
+            agent_type: None,
+            spawned_at: created_at,
+        }],
+    };
+    let later = team("later", 20);
+    let later_lead_thread_id =
+        ThreadId::from_string(&later.lead_thread_id).expect("valid lead thread id");
+    write_json_atomic(
+        &team_config_path(codex_home.path(), later_lead_thread_id, &later.team_name),
+        &later,
+    )
+    .await
+    .expect("write team config");
+    // Teams persisted before the lead namespace was introduced are still listed.
+    let earlier = team("earlier", 10);
+    write_json_atomic(
+        &legacy_team_dir(codex_home.path(), &earlier.team_name).join("config.json"),
+        &earlier,
+    )
+    .await
+    .expect("write legacy team config");
+    let corrupt_path = team_config_path(codex_home.path(), ThreadId::new(), "corrupt");
+    tokio::fs::create_dir_all(corrupt_path.parent().expect("team dir"))
+        .await
+        .expect("create corrupt team dir");
+    tokio::fs::write(&corrupt_path, "not json")
+        .await
+        .expect("write corrupt config");
+
+    let teams = list_persisted_teams(codex_home.path())
+        .await
+        .expect("list teams");
+
+    assert_eq!(
+        teams
+            .iter()
+            .map(|team| team.team_name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["earlier", "later"]
+    );
+}
+
+fn single_worker_team(created_at: i64) -> TeamRecord {
+    TeamRecord {
+        members: vec![TeamMember {
+            name: "worker".to_string(),
+            agent_id: ThreadId::new(),
+            agent_type: None,
+            spawned_at: created_at,
+        }],
+        created_at,
+    }
+}
+
+#[tokio::test]
+async fn persisted_teams_with_same_id_are_isolated_per_lead() {
+    let codex_home = tempfile::tempdir().expect("temp dir");
+    let lead_a = ThreadId::new();
+    let lead_b = ThreadId::new();
+    persist_team_state(codex_home.path(), lead_a, "shared", &single_worker_team(1))
+        .await
+        .expect("persist lead a team");
+    persist_team_state(codex_home.path(), lead_b, "shared", &single_worker_team(2))
+        .await
+        .expect("persist lead b team");
+
+    let team_a = read_persisted_team_config(codex_home.path(), lead_a, "shared")
+        .await
+        .expect("lead a team");
+    let team_b = read_persisted_team_config(codex_home.path(), lead_b, "shared")
+        .await
+        .expect("lead b team");
+    assert_eq!(team_a.lead_thread_id, lead_a.to_string());
+    assert_eq!(team_b.lead_thread_id, lead_b.to_string());
+
+    remove_team_persistence(codex_home.path(), lead_a, "shared")
+        .await
+        .expect("remove lead a team");
+    assert!(
+        find_persisted_team_config(codex_home.path(), lead_a, "shared")
+            .await
+            .expect("lookup lead a team")
+            .is_none()
+    );
+    assert_eq!(
+        read_persisted_team_config(codex_home.path(), lead_b, "shared")
+            .await
+            .expect("lead b team survives")
+            .created_at,
+        2
+    );
+}
+
+#[tokio::test]
+async fn persist_team_state_migrates_legacy_layout_for_owning_lead() {
+    let codex_home = tempfile::tempdir().expect("temp dir");
+    let lead_thread_id = ThreadId::new();
+    let team = single_worker_team(1);
+    let legacy_config_path = legacy_team_dir(codex_home.path(), "legacy").join("config.json");
+    write_json_atomic(
+        &legacy_config_path,
+        &persisted_team_config(lead_thread_id, "legacy", &team),
+    )
+    .await
+    .expect("write legacy team config");
+    let legacy_task_path = legacy_team_tasks_dir(codex_home.path(), "legacy").join("1.json");
+    write_json_atomic(&legacy_task_path, &json!({"id": "1"}))
+        .await
+        .expect("write legacy task");
+
+    assert_eq!(
+        find_persisted_team_config(codex_home.path(), ThreadId::new(), "legacy")
+            .await
+            .expect("lookup from another lead")
+            .is_none(),
+        true
+    );
+    assert_eq!(
+        read_persisted_team_config(codex_home.path(), lead_thread_id, "legacy")
+            .await
+            .expect("legacy team is readable by its lead")
+            .team_name,
+        "legacy"
+    );
+
+    persist_team_state(codex_home.path(), lead_thread_id, "legacy", &team)
+        .await
+        .expect("persist team");
+
+    assert_eq!(legacy_config_path.exists(), false);
+    assert_eq!(
+        team_config_path(codex_home.path(), lead_thread_id, "legacy").exists(),
+        true
+    );
+    assert_eq!(
+        team_tasks_dir(codex_home.path(), lead_thread_id, "legacy")
+            .join("1.json")
+            .exists(),
+        true
+    );
+    assert_eq!(
+        legacy_team_tasks_dir(codex_home.path(), "legacy").exists(),
+        false
+    );
+}
+
+#[tokio::test]
+async fn list_persisted_teams_without_teams_dir_is_empty() {
+    let codex_home = tempfile::tempdir().expect("temp dir");
+
+    let teams = list_persisted_teams(codex_home.path())
+        .await
+        .expect("list teams");
+
+    assert!(teams.is_empty());
+}
+
+#[test]
+fn normalize_wait_timeout_supports_unbounded_sentinel() {
+    assert_eq!(
+        normalize_wait_timeout(Some(UNBOUNDED_WAIT_TIMEOUT_MS)),
+        Ok(None)
+    );
+    assert_eq!(
+        normalize_wait_timeout(Some(1)),
+        Ok(Some(MIN_WAIT_TIMEOUT_MS))
+    );
+    assert_eq!(
+        normalize_wait_timeout(Some(i64::MAX)),
+        Ok(Some(MAX_WAIT_TIMEOUT_MS))
+    );
+    assert_eq!(
+        normalize_wait_timeout(None),
+        Ok(Some(DEFAULT_WAIT_TIMEOUT_MS))
+    );
+    assert_eq!(
+        normalize_wait_timeout(Some(-2)),
+        Err(FunctionCallError::RespondToModel(
+            "timeout_ms must be greater than zero".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn apply_member_model_overrides_uses_first_configured_fallback() {
+    let (_session, turn) = make_session_and_context().await;
+    let mut config = turn.config.as_ref().clone();
+    let fallback_id = config
+        .model_providers
+        .keys()
+        .next()
+        .cloned()
+        .expect("built-in model provider");
+
+    apply_member_model_overrides(
+        &mut config,
+        Some("missing-provider"),
+        &["also-missing".to_string(), fallback_id.clone()],
+        None,
+    )
+    .expect("fallback provider should resolve");
+    assert_eq!(config.model_provider_id, fallback_id);
+
+    let err = apply_member_model_overrides(
+        &mut config,
+        Some("missing-provider"),
+        &["also-missing".to_string()],
+        None,
+    )
+    .expect_err("unresolvable providers should fail");
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel(
+            "none of the model providers were found: `missing-provider`, `also-missing`"
+                .to_string()
+        )
+    );
+}
+
+#[tokio::test]
+async fn delete_team_drain_reports_per_member_result() {
+    let (mut session, turn) = make_session_and_context().await;
+    let manager = thread_manager();
+    session.services.agent_control = manager.agent_control();
+    let missing_id = ThreadId::new();
+    let session = Arc::new(session);
+    let turn = Arc::new(turn);
+    insert_team_record(
+        session.conversation_id,
+        "team-drain".to_string(),
+        TeamRecord {
+            members: vec![TeamMember {
+                name: "gone".to_string(),
+                agent_id: missing_id,
+                agent_type: None,
+                spawned_at: 0,
+            }],
+            created_at: 0,
+        },
+    )
+    .expect("team insert should succeed");
+
+    let Err(err) = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn.clone(),
+            "delete_team",
+            function_payload(json!({"team_id": "team-drain", "drain_timeout_ms": 1000})),
+        ))
+        .await
+    else {
+        panic!("drain_timeout_ms without drain should be rejected");
+    };
+    assert_eq!(
+        err,
+        FunctionCallError::RespondToModel("drain_timeout_ms requires drain to be true".to_string())
+    );
+
+    let output = MultiAgentHandler
+        .handle(invocation(
+            session.clone(),
+            turn,
+            "delete_team",
+            function_payload(json!({
+                "team_id": "team-drain",
+                "drain": true,
+                "drain_timeout_ms": 1000
+            })),
+        ))
+        .await
+        .expect("delete_team should succeed");
+    let ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        ..
+    } = output
+    else {
+        panic!("expected function output");
+    };
+    let result: serde_json::Value =
+        serde_json::from_str(&content).expect("delete_team result should be json");
+    assert_eq!(result["closed"][0]["name"], json!("gone"));
+    assert_eq!(result["closed"][0]["drained"], json!(true));
+}
+
+#[tokio::test]
+async fn wait_for_final_status_skips_intermediate_status_churn() {
+    let (session, _turn) = make_session_and_context().await;
+    let session = Arc::new(session);
+    let thread_id = ThreadId::new();
+    let (status_tx, status_rx) = tokio::sync::watch::channel(AgentStatus::PendingInit);
+
+    let waiter = tokio::spawn(wait_for_final_status(session, thread_id, status_rx));
+    for _ in 0..100 {
+        status_tx
+            .send(AgentStatus::Running)
+            .expect("status receiver should be alive");
+        status_tx
+            .send(AgentStatus::PendingInit)
+            .expect("status receiver should be alive");
+    }
+    status_tx
+        .send(AgentStatus::Completed(Some("done".to_string())))
+        .expect("status receiver should be alive");
+
+    let result = timeout(Duration::from_secs(5), waiter)
+        .await
+        .expect("waiter should finish")
+        .expect("waiter should not panic");
+    assert_eq!(
+        result,
+        Some((thread_id, AgentStatus::Completed(Some("done".to_string()))))
+    );
+}
+
+#[tokio::test]
+async fn wait_for_status_resolves_on_requested_non_final_status() {
+    let (session, _turn) = make_session_and_context().await;
+    let session = Arc::new(session);
+    let thread_id = ThreadId::new();
+    let (status_tx, status_rx) = tokio::sync::watch::channel(AgentStatus::PendingInit);
+
+    let waiter = tokio::spawn(async move {
+        wait_for_status(session, thread_id, status_rx, Some(&AgentStatus::Running)).await
+    });
+    status_tx
+        .send(AgentStatus::Running)
+        .expect("status receiver should be alive");
+
+    let result = timeout(Duration::from_secs(5), waiter)
+        .await
+        .expect("waiter should finish")
+        .expect("waiter should not panic");
+    assert_eq!(result, Some((thread_id, AgentStatus::Running)));
+    assert!(wait_target_reached(
+        &AgentStatus::Errored("boom".to_string()),
+        Some(&AgentStatus::Running)
+    ));
+    assert!(!wait_target_reached(
+        &AgentStatus::PendingInit,
+        Some(&AgentStatus::Running)
+    ));
+}
+
+#[test]
+fn input_preview_truncates_long_inputs_on_char_boundaries() {
+    let items = vec![
+        UserInput::Text {
+            text: "héllo wörld".to_string(),
+            text_elements: Vec::new(),
+        },
+        UserInput::Text {
+            text: "second".to_string(),
+            text_elements: Vec::new(),
+        },
+    ];
+
+    assert_eq!(input_preview(&items, 5), "héllo...");
+    assert_eq!(input_preview(&items, 18), "héllo wörld\nsecond");
+}
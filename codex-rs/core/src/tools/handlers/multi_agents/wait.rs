@@ -3,10 +3,11 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 enum WaitModeArg {
     Any,
     All,
+    FirstError,
 }
 
 #[derive(Debug, Deserialize)]
@@ -15,12 +16,28 @@ struct WaitArgs {
     team_id: Option<String>,
     mode: Option<WaitModeArg>,
     timeout_ms: Option<i64>,
+    member_timeouts: Option<HashMap<String, i64>>,
+    /// Also resolve an agent once it reaches this status, not only a final one.
+    until_status: Option<AgentStatus>,
 }
 
 #[derive(Debug, Serialize)]
 struct WaitResult {
     status: HashMap<ThreadId, AgentStatus>,
     timed_out: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    timed_out_ids: Vec<ThreadId>,
+    /// Wall-clock duration of the wait itself.
+    elapsed_ms: u64,
+    /// Agents that were already final (or at `until_status`) before the wait began, as opposed
+    /// to resolving during it.
+    already_final: Vec<ThreadId>,
+    /// The agent whose final status ended the wait; for `all` this is the last one to finish.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    triggered_agent_id: Option<ThreadId>,
+    /// Team member name of `triggered_agent_id`, for team waits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    triggered_member: Option<String>,
 }
 
 pub async fn handle(
@@ -33,47 +50,73 @@ pub async fn handle(
     let wait_mode = match args.mode {
         Some(WaitModeArg::Any) => WaitMode::Any,
         Some(WaitModeArg::All) => WaitMode::All,
+        Some(WaitModeArg::FirstError) => WaitMode::FirstError,
         None if args.team_id.is_some() => WaitMode::All,
         None => WaitMode::Any,
     };
 
-    let (receiver_thread_ids, event_call_id, receiver_agents_from_team, team_id) =
-        if let Some(team_id) = args.team_id.as_deref() {
-            if args.ids.is_some() {
-                return Err(FunctionCallError::RespondToModel(
-                    "ids must not be provided when team_id is set".to_string(),
-                ));
-            }
-            let team_id = normalized_team_id(team_id)?;
-            let team = get_team_record(session.conversation_id, &team_id)?;
-            if team.members.is_empty() {
-                return Err(FunctionCallError::RespondToModel(format!(
-                    "team `{team_id}` has no members"
-                )));
-            }
-            (
-                team.members.iter().map(|member| member.agent_id).collect(),
-                prefixed_team_call_id(TEAM_WAIT_CALL_PREFIX, &call_id),
-                team_member_refs(&team.members),
-                Some(team_id),
-            )
-        } else {
-            let Some(ids) = args.ids.as_ref() else {
-                return Err(FunctionCallError::RespondToModel(
-                    "ids must be non-empty".to_owned(),
-                ));
-            };
-            if ids.is_empty() {
-                return Err(FunctionCallError::RespondToModel(
-                    "ids must be non-empty".to_owned(),
-                ));
-            }
-            let receiver_thread_ids = ids
-                .iter()
-                .map(|id| agent_id(id))
-                .collect::<Result<Vec<_>, _>>()?;
-            (receiver_thread_ids, call_id.clone(), Vec::new(), None)
+    let (
+        receiver_thread_ids,
+        event_call_id,
+        receiver_agents_from_team,
+        team_id,
+        member_timeouts_ms,
+    ) = if let Some(team_id) = args.team_id.as_deref() {
+        if args.ids.is_some() {
+            return Err(FunctionCallError::RespondToModel(
+                "ids must not be provided when team_id is set".to_string(),
+            ));
+        }
+        let team_id = normalized_team_id(team_id)?;
+        let team = get_team_record(session.conversation_id, &team_id)?;
+        if team.members.is_empty() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "team `{team_id}` has no members"
+            )));
+        }
+        let member_timeouts_ms =
+            resolve_member_timeouts(&team_id, &team, args.member_timeouts.as_ref())?;
+        (
+            team.members.iter().map(|member| member.agent_id).collect(),
+            prefixed_team_call_id(TEAM_WAIT_CALL_PREFIX, &call_id),
+            team_member_refs(&team.members),
+            Some(team_id),
+            member_timeouts_ms,
+        )
+    } else {
+        if args.member_timeouts.is_some() {
+            return Err(FunctionCallError::RespondToModel(
+                "member_timeouts requires team_id".to_string(),
+            ));
+        }
+        let Some(ids) = args.ids.as_ref() else {
+            return Err(FunctionCallError::RespondToModel(
+                "ids must be non-empty".to_owned(),
+            ));
         };
+        if ids.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "ids must be non-empty".to_owned(),
+            ));
+        }
+        let receiver_thread_ids = ids
+            .iter()
+            .map(|id| agent_id(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        if receiver_thread_ids.contains(&session.conversation_id) {
+            return Err(FunctionCallError::RespondToModel(
+                "an agent cannot wait on itself; ids must not include your own agent id"
+                    .to_string(),
+            ));
+        }
+        (
+            receiver_thread_ids,
+            call_id.clone(),
+            Vec::new(),
+            None,
+            HashMap::new(),
+        )
+    };
 
     let timeout_ms = normalize_wait_timeout(args.timeout_ms)?;
 
@@ -110,47 +153,62 @@ pub async fn handle(
         )
         .await;
 
-    let wait_result =
-        match wait_for_agents(session.clone(), &receiver_thread_ids, timeout_ms, wait_mode).await {
-            Ok(result) => result,
-            Err((id, err)) => {
-                let status = session.services.agent_control.get_status(id).await;
-                let (agent_nickname, agent_role) = session
-                    .services
-                    .agent_control
-                    .get_agent_nickname_and_role(id)
-                    .await
-                    .unwrap_or((None, None));
-                let statuses = HashMap::from([(id, status.clone())]);
-                let agent_statuses = vec![CollabAgentStatusEntry {
-                    thread_id: id,
-                    agent_nickname,
-                    agent_role,
-                    status,
-                }];
-                session
-                    .send_event(
-                        &turn,
-                        CollabWaitingEndEvent {
-                            sender_thread_id: session.conversation_id,
-                            call_id: event_call_id.clone(),
-                            agent_statuses,
-                            statuses,
-                        }
-                        .into(),
-                    )
-                    .await;
-                return Err(collab_agent_error(id, err));
-            }
-        };
+    let wait_result = match wait_for_agents(
+        session.clone(),
+        &receiver_thread_ids,
+        timeout_ms,
+        &member_timeouts_ms,
+        wait_mode,
+        args.until_status.as_ref(),
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err((id, err)) => {
+            let status = session.services.agent_control.get_status(id).await;
+            let (agent_nickname, agent_role) = session
+                .services
+                .agent_control
+                .get_agent_nickname_and_role(id)
+                .await
+                .unwrap_or((None, None));
+            let statuses = HashMap::from([(id, status.clone())]);
+            let agent_statuses = vec![CollabAgentStatusEntry {
+                thread_id: id,
+                agent_nickname,
+                agent_role,
+                status,
+            }];
+            session
+                .send_event(
+                    &turn,
+                    CollabWaitingEndEvent {
+                        sender_thread_id: session.conversation_id,
+                        call_id: event_call_id.clone(),
+                        agent_statuses,
+                        statuses,
+                    }
+                    .into(),
+                )
+                .await;
+            return Err(collab_agent_error(id, err));
+        }
+    };
 
     let statuses_map = wait_result
         .statuses
         .iter()
         .cloned()
         .collect::<HashMap<_, _>>();
+    let mut triggered_member = None;
     let (reported_statuses, agent_statuses) = if let Some(team_id) = team_id.as_deref() {
         let team = get_team_record(session.conversation_id, team_id)?;
+        triggered_member = wait_result.triggered_by.and_then(|agent_id| {
+            team.members
+                .iter()
+                .find(|member| member.agent_id == agent_id)
+                .map(|member| member.name.clone())
+        });
         let mut reported_statuses = statuses_map.clone();
         for member in &team.members {
             if reported_statuses.contains_key(&member.agent_id) {
@@ -212,6 +270,11 @@ pub async fn handle(
     let result = WaitResult {
         status: reported_statuses.clone(),
         timed_out: wait_result.timed_out,
+        timed_out_ids: wait_result.timed_out_ids,
+        elapsed_ms: wait_result.elapsed_ms,
+        already_final: wait_result.already_final,
+        triggered_agent_id: wait_result.triggered_by,
+        triggered_member,
     };
 
     // Final event emission.
@@ -237,3 +300,25 @@ pub async fn handle(
         success: None,
     })
 }
+
+/// Resolves `member_timeouts` (keyed by member name) into per-agent timeouts.
+fn resolve_member_timeouts(
+    team_id: &str,
+    team: &TeamRecord,
+    member_timeouts: Option<&HashMap<String, i64>>,
+) -> Result<HashMap<ThreadId, Option<i64>>, FunctionCallError> {
+    let Some(member_timeouts) = member_timeouts else {
+        return Ok(HashMap::new());
+    };
+    let mut resolved = HashMap::with_capacity(member_timeouts.len());
+    for (name, timeout_ms) in member_timeouts {
+        let member = find_team_member(team, team_id, name)?;
+        let timeout_ms = normalize_wait_timeout(Some(*timeout_ms)).map_err(|_| {
+            FunctionCallError::RespondToModel(format!(
+                "member_timeouts.{name} must be greater than zero, or {UNBOUNDED_WAIT_TIMEOUT_MS} for no deadline"
+            ))
+        })?;
+        resolved.insert(member.agent_id, timeout_ms);
+    }
+    Ok(resolved)
+}
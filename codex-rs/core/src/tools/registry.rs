@@ -319,6 +319,12 @@ impl ToolRegistryBuilder {
     //     }
     // }
 
+    /// Drops every spec and handler whose tool name fails `keep`.
+    pub fn retain_tools(&mut self, keep: impl Fn(&str) -> bool) {
+        self.specs.retain(|configured| keep(configured.spec.name()));
+        self.handlers.retain(|name, _| keep(name));
+    }
+
     pub fn build(self) -> (Vec<ConfiguredToolSpec>, ToolRegistry) {
         let registry = ToolRegistry::new(self.handlers);
         (self.specs, registry)
@@ -653,6 +659,7 @@ mod tests {
     use crate::turn_diff_tracker::TurnDiffTracker;
     use codex_hooks::CommandHookConfig;
     use codex_hooks::CommandHooksConfig;
+    use codex_hooks::HookMatcherConfig;
     use codex_hooks::Hooks;
     use codex_hooks::HooksConfig;
 
@@ -697,6 +704,55 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn pre_tool_use_hook_blocks_only_matching_tool_name() {
+        let (mut session, turn) = make_session_and_context().await;
+        session.services.hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                pre_tool_use: vec![CommandHookConfig {
+                    matcher: HookMatcherConfig {
+                        tool_name: Some("create_team".to_string()),
+                        ..Default::default()
+                    },
+                    command: vec![
+                        "python3".to_string(),
+                        "-c".to_string(),
+                        r#"import json,sys; json.load(sys.stdin); print(json.dumps({"decision":"deny","reason":"teams disabled"}))"#.to_string(),
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        });
+        let handler = Arc::new(DummyHandler {
+            output: super::ToolOutput::Function {
+                body: codex_protocol::models::FunctionCallOutputBody::Text("ok".to_string()),
+                success: Some(true),
+            },
+        }) as Arc<dyn ToolHandler>;
+        let registry = ToolRegistry::new(HashMap::from([
+            ("dummy".to_string(), Arc::clone(&handler)),
+            ("create_team".to_string(), handler),
+        ]));
+        let session = Arc::new(session);
+        let turn = Arc::new(turn);
+
+        registry
+            .dispatch(invocation(Arc::clone(&session), Arc::clone(&turn)))
+            .await
+            .expect("non-matching tool should run");
+
+        let mut blocked = invocation(session, turn);
+        blocked.tool_name = "create_team".to_string();
+        let Err(crate::function_tool::FunctionCallError::RespondToModel(message)) =
+            registry.dispatch(blocked).await
+        else {
+            panic!("expected RespondToModel error");
+        };
+        assert!(message.contains("blocked tool 'create_team'"));
+        assert!(message.contains("teams disabled"));
+    }
+
     #[tokio::test]
     async fn post_tool_use_hook_can_block_tool_output() {
         let (mut session, turn) = make_session_and_context().await;
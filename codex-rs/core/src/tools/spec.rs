@@ -19,6 +19,7 @@ use crate::tools::handlers::apply_patch::create_apply_patch_json_tool;
 use crate::tools::handlers::multi_agents::DEFAULT_WAIT_TIMEOUT_MS;
 use crate::tools::handlers::multi_agents::MAX_WAIT_TIMEOUT_MS;
 use crate::tools::handlers::multi_agents::MIN_WAIT_TIMEOUT_MS;
+use crate::tools::handlers::multi_agents::UNBOUNDED_WAIT_TIMEOUT_MS;
 use crate::tools::handlers::request_permissions_tool_description;
 use crate::tools::handlers::request_user_input_tool_description;
 use crate::tools::registry::ToolRegistryBuilder;
@@ -38,6 +39,7 @@ use serde::Serialize;
 use serde_json::Value as JsonValue;
 use serde_json::json;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 
 const SEARCH_TOOL_BM25_DESCRIPTION_TEMPLATE: &str =
@@ -80,6 +82,10 @@ pub(crate) struct ToolsConfig {
     pub experimental_supported_tools: Vec<String>,
     pub agent_jobs_tools: bool,
     pub agent_jobs_worker_tools: bool,
+    /// `allowed_tools` from config: when set, every other tool is dropped from the registry.
+    pub allowed_tools: Option<Vec<String>>,
+    /// `denied_tools` from config, applied after `allowed_tools`.
+    pub denied_tools: Vec<String>,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -188,6 +194,8 @@ impl ToolsConfig {
             experimental_supported_tools: model_info.experimental_supported_tools.clone(),
             agent_jobs_tools,
             agent_jobs_worker_tools,
+            allowed_tools: None,
+            denied_tools: Vec::new(),
         }
     }
 
@@ -205,6 +213,35 @@ impl ToolsConfig {
         self.web_search_config = web_search_config;
         self
     }
+
+    pub fn with_tool_filter(
+        mut self,
+        allowed_tools: Option<Vec<String>>,
+        denied_tools: Vec<String>,
+    ) -> Self {
+        self.allowed_tools = allowed_tools;
+        self.denied_tools = denied_tools;
+        self
+    }
+
+    fn allows_tool(&self, name: &str) -> bool {
+        let allowed = self
+            .allowed_tools
+            .as_ref()
+            .is_none_or(|allowed| allowed.iter().any(|tool| tool == name));
+        allowed && !self.denied_tools.iter().any(|tool| tool == name)
+    }
+}
+
+/// Names of the tools `config` would register before `allowed_tools`/`denied_tools` are applied,
+/// excluding MCP and dynamic tools (which depend on the live session).
+pub(crate) fn builtin_tool_names(config: &ToolsConfig) -> BTreeSet<String> {
+    let unfiltered = config.clone().with_tool_filter(None, Vec::new());
+    let (specs, _) = build_specs(&unfiltered, None, None, &[]).build();
+    specs
+        .into_iter()
+        .map(|configured| configured.spec.name().to_string())
+        .collect()
 }
 
 fn supports_image_generation(model_info: &ModelInfo) -> bool {
@@ -834,6 +871,15 @@ fn create_spawn_agent_tool(config: &ToolsConfig) -> ToolSpec {
                 )),
             },
         ),
+        (
+            "nickname".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional human-friendly name shown for the new agent in events and list_agents. Defaults to an automatically chosen name."
+                        .to_string(),
+                ),
+            },
+        ),
         (
             "fork_context".to_string(),
             JsonSchema::Boolean {
@@ -843,6 +889,56 @@ fn create_spawn_agent_tool(config: &ToolsConfig) -> ToolSpec {
                 ),
             },
         ),
+        (
+            "seed_history".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::Object {
+                    properties: BTreeMap::new(),
+                    required: None,
+                    additional_properties: Some(true.into()),
+                }),
+                description: Some(
+                    "Optional prior conversation turns (Responses API items such as {\"type\":\"message\",\"role\":\"user\",\"content\":[{\"type\":\"input_text\",\"text\":\"...\"}]}) to start the new agent from. The initial message is delivered after them. Cannot be combined with fork_context."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "max_depth".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Optional spawn depth limit for the new agent's subtree. Can only tighten the current limit, never raise it."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "reasoning_effort".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional reasoning effort for the new agent: none, minimal, low, medium, high, or xhigh. Defaults to your own."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "reasoning_summary".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional reasoning summary mode for the new agent: auto, concise, detailed, or none."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "sandbox".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional sandbox for the new agent: read-only, workspace-write, or danger-full-access. Defaults to your own sandbox. Can only tighten it, never loosen it."
+                        .to_string(),
+                ),
+            },
+        ),
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
@@ -1044,20 +1140,47 @@ fn create_send_message_tool() -> ToolSpec {
             },
         ),
         (
-            "interrupt".to_string(),
-            JsonSchema::Boolean {
+            "members".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
                 description: Some(
-                    "When true, stop the agent's current task and handle this immediately. When false (default), queue this message."
+                    "Optional member names to limit a broadcast to. Defaults to every team member."
                         .to_string(),
                 ),
             },
         ),
+        (
+            "agent_type".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional agent_type to limit a broadcast to (case-insensitive; members spawned without one match `default`). Intersected with members when both are given."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "interrupt_mode".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "How to treat the agent's current task: `none` (default) queues this message; `soft` stops the current task and handles this immediately, resending any input still queued for it first; `hard` stops the current task and drops any input still queued for it."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "response_schema".to_string(),
+            JsonSchema::Object {
+                properties: BTreeMap::new(),
+                required: None,
+                additional_properties: None,
+            },
+        ),
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
         name: "send_message".to_string(),
         description:
-            "Send a message to an agent directly by id, message a team member by name, ask the team lead, or broadcast to a team."
+            "Send a message to an agent directly by id, message a team member by name, ask the team lead, or broadcast to a team. Pass response_schema (a JSON Schema object) to request a structured final answer for that turn."
                 .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
@@ -1116,7 +1239,7 @@ fn create_wait_tool() -> ToolSpec {
         "mode".to_string(),
         JsonSchema::String {
             description: Some(
-                "Wait mode: `any` (default) returns after the first agent reaches a final state, `all` waits for every agent."
+                "Wait mode: `any` (default) returns after the first agent reaches a final state, `all` waits for every agent, `first_error` returns as soon as one agent errors and otherwise waits for every agent. Only `errored` counts as a failure; `shutdown` and `not_found` do not."
                     .to_string(),
             ),
         },
@@ -1125,14 +1248,39 @@ fn create_wait_tool() -> ToolSpec {
         "timeout_ms".to_string(),
         JsonSchema::Number {
             description: Some(format!(
-                "Optional timeout in milliseconds. Defaults to {DEFAULT_WAIT_TIMEOUT_MS}, min {MIN_WAIT_TIMEOUT_MS}, max {MAX_WAIT_TIMEOUT_MS}. Prefer longer waits (minutes) to avoid busy polling."
+                "Optional timeout in milliseconds. Defaults to {DEFAULT_WAIT_TIMEOUT_MS}, min {MIN_WAIT_TIMEOUT_MS}, max {MAX_WAIT_TIMEOUT_MS}. Prefer longer waits (minutes) to avoid busy polling. Pass {UNBOUNDED_WAIT_TIMEOUT_MS} to wait with no deadline; this blocks you until the agents finish, so only use it when nothing else needs your attention. With team_id, member_timeouts can override it per member."
             )),
         },
     );
+    properties.insert(
+        "member_timeouts".to_string(),
+        JsonSchema::Object {
+            properties: BTreeMap::new(),
+            required: None,
+            additional_properties: Some(
+                JsonSchema::Number {
+                    description: Some(
+                        "Timeout in milliseconds for this team member, clamped like timeout_ms (-1 for no deadline)."
+                            .to_string(),
+                    ),
+                }
+                .into(),
+            ),
+        },
+    );
+    properties.insert(
+        "until_status".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional status (e.g. `running`) that also counts as reaching the target, in addition to any final status. An agent that never reaches it still times out."
+                    .to_string(),
+            ),
+        },
+    );
 
     ToolSpec::Function(ResponsesApiTool {
         name: "wait".to_string(),
-        description: "Wait for agents to reach a final status. Provide either ids or team_id. Completed statuses may include the agent's final message. Returns empty status when timed out. Once the agent reaches a final status, a notification message will be received containing the same completed status."
+        description: "Wait for agents to reach a final status. Provide either ids or team_id. Completed statuses may include the agent's final message. Returns empty status when timed out. The result also reports elapsed_ms and already_final (agents that were final before the wait began), plus triggered_agent_id (and triggered_member for team waits): the agent whose final status ended the wait, which for mode \"all\" is the last one to finish. Once the agent reaches a final status, a notification message will be received containing the same completed status."
             .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
@@ -1260,6 +1408,14 @@ fn create_close_agent_tool() -> ToolSpec {
             description: Some("Agent id to close (from spawn_agent).".to_string()),
         },
     );
+    properties.insert(
+        "drain_timeout_ms".to_string(),
+        JsonSchema::Number {
+            description: Some(format!(
+                "Optional graceful drain in milliseconds (max {MAX_WAIT_TIMEOUT_MS}). When set, the agent is interrupted first and only shut down after it settles or the timeout elapses."
+            )),
+        },
+    );
 
     ToolSpec::Function(ResponsesApiTool {
         name: "close_agent".to_string(),
@@ -1274,14 +1430,23 @@ fn create_close_agent_tool() -> ToolSpec {
     })
 }
 
-fn create_team_tool(config: &ToolsConfig) -> ToolSpec {
-    let member_properties = BTreeMap::from([
+fn create_team_member_properties(config: &ToolsConfig) -> BTreeMap<String, JsonSchema> {
+    BTreeMap::from([
         (
             "name".to_string(),
             JsonSchema::String {
                 description: Some("Unique member name within the team.".to_string()),
             },
         ),
+        (
+            "nickname".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional display name for this member's agent thread. Defaults to name."
+                        .to_string(),
+                ),
+            },
+        ),
         (
             "task".to_string(),
             JsonSchema::String {
@@ -1302,12 +1467,40 @@ fn create_team_tool(config: &ToolsConfig) -> ToolSpec {
                 description: Some("Optional model provider id override for this member.".to_string()),
             },
         ),
+        (
+            "model_provider_fallbacks".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
+                description: Some(
+                    "Optional provider ids to try in order when model_provider is not configured on this machine."
+                        .to_string(),
+                ),
+            },
+        ),
         (
             "model".to_string(),
             JsonSchema::String {
                 description: Some("Optional model override for this member.".to_string()),
             },
         ),
+        (
+            "reasoning_effort".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional reasoning effort for this member: none, minimal, low, medium, high, or xhigh. Defaults to your own."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "reasoning_summary".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional reasoning summary mode for this member: auto, concise, detailed, or none."
+                        .to_string(),
+                ),
+            },
+        ),
         (
             "worktree".to_string(),
             JsonSchema::Boolean {
@@ -1324,7 +1517,20 @@ fn create_team_tool(config: &ToolsConfig) -> ToolSpec {
                 ),
             },
         ),
-    ]);
+        (
+            "max_depth".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Optional spawn depth limit for this member's subtree. Can only tighten the current limit, never raise it."
+                        .to_string(),
+                ),
+            },
+        ),
+    ])
+}
+
+fn create_team_tool(config: &ToolsConfig) -> ToolSpec {
+    let member_properties = create_team_member_properties(config);
 
     let properties = BTreeMap::from([
         (
@@ -1348,6 +1554,67 @@ fn create_team_tool(config: &ToolsConfig) -> ToolSpec {
                 ),
             },
         ),
+        (
+            "shared_worktree".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "When true, create one git worktree and run every member in it (per-member worktree is ignored). It is removed once the last member closes. Members edit the same files concurrently, so give them disjoint write sets."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "best_effort".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "When true, members that fail to spawn are reported under `failed` ({name, error}) and the team is created from the rest. Defaults to false: any failure tears down the whole team."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "spawn_timeout_ms".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Optional upper bound in milliseconds on the time to bring every member up. When it expires, members that already spawned are shut down and the call fails, even with best_effort."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "default_agent_type".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional agent_type for members that do not set their own.".to_string(),
+                ),
+            },
+        ),
+        (
+            "default_model".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional model for members that do not set their own.".to_string(),
+                ),
+            },
+        ),
+        (
+            "default_model_provider".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional model provider id for members that do not set their own."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "dry_run".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "When true, validate names, tasks, roles, and model providers and return the resolved member plan without spawning agents, creating worktrees, or persisting the team. Reports the same errors a real create_team would."
+                        .to_string(),
+                ),
+            },
+        ),
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
@@ -1362,6 +1629,129 @@ fn create_team_tool(config: &ToolsConfig) -> ToolSpec {
     })
 }
 
+fn create_team_add_member_tool(config: &ToolsConfig) -> ToolSpec {
+    let mut properties = create_team_member_properties(config);
+    properties.insert(
+        "team_id".to_string(),
+        JsonSchema::String {
+            description: Some("Team id returned by create_team.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "team_add_member".to_string(),
+        description: "Spawn one more member into an existing team. The member name must be unique within the team and the member receives its own task.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec![
+                "team_id".to_string(),
+                "name".to_string(),
+                "task".to_string(),
+            ]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_team_message_wait_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "team_id".to_string(),
+            JsonSchema::String {
+                description: Some("Team id returned by create_team.".to_string()),
+            },
+        ),
+        (
+            "to".to_string(),
+            JsonSchema::String {
+                description: Some("Team member name to message.".to_string()),
+            },
+        ),
+        (
+            "message".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Plain-text message to send. Use either message or items.".to_string(),
+                ),
+            },
+        ),
+        ("items".to_string(), create_collab_input_items_schema()),
+        (
+            "interrupt_mode".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "How to treat the member's current task: `none` (default), `soft`, or `hard`, as in send_message."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "response_schema".to_string(),
+            JsonSchema::Object {
+                properties: BTreeMap::new(),
+                required: None,
+                additional_properties: None,
+            },
+        ),
+        (
+            "timeout_ms".to_string(),
+            JsonSchema::Number {
+                description: Some(format!(
+                    "Optional timeout in milliseconds. Defaults to {DEFAULT_WAIT_TIMEOUT_MS}, min {MIN_WAIT_TIMEOUT_MS}, max {MAX_WAIT_TIMEOUT_MS}. Pass {UNBOUNDED_WAIT_TIMEOUT_MS} to wait until the member finishes, however long that takes."
+                )),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "team_message_wait".to_string(),
+        description: "Message one team member and wait for it to finish handling that message. Returns the submission id and the member's final status (or its current status with timed_out=true)."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["team_id".to_string(), "to".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_list_spawned_agents_tool() -> ToolSpec {
+    ToolSpec::Function(ResponsesApiTool {
+        name: "list_spawned_agents".to_string(),
+        description: "List the agents you spawned with spawn_agent in this session, including before a restart, with agent_id, agent_type, model, spawned_at (unix seconds), and current status. Agents you close are dropped from the list; agents reported as not_found stopped with an earlier session and can be restored with resume_agent."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties: BTreeMap::new(),
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_get_agent_output_tool() -> ToolSpec {
+    let properties = BTreeMap::from([(
+        "id".to_string(),
+        JsonSchema::String {
+            description: Some("Agent id to read output from (from spawn_agent).".to_string()),
+        },
+    )]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "get_agent_output".to_string(),
+        description: "Return the last assistant message recorded by an agent, read from its rollout. Works for closed agents too. When the agent is still running, returns the latest message available so far with partial=true."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_delete_team_tool() -> ToolSpec {
     let properties = BTreeMap::from([
         (
@@ -1378,11 +1768,28 @@ fn create_delete_team_tool() -> ToolSpec {
                 ),
             },
         ),
+        (
+            "drain".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "When true, interrupt members and wait for each to reach a final status before shutting it down. Defaults to false (immediate shutdown)."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "drain_timeout_ms".to_string(),
+            JsonSchema::Number {
+                description: Some(format!(
+                    "Optional drain timeout in milliseconds when drain is true. Defaults to {DEFAULT_WAIT_TIMEOUT_MS}, max {MAX_WAIT_TIMEOUT_MS}. Members still running afterwards are shut down with drained=false."
+                )),
+            },
+        ),
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
         name: "delete_team".to_string(),
-        description: "Close all team members, remove the team from the registry, and optionally delete persisted team files."
+        description: "Close all team members, remove the team from the registry, and optionally delete persisted team files. The result lists removed_worktrees with the paths of the member worktrees that were deleted."
             .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
@@ -1393,6 +1800,96 @@ fn create_delete_team_tool() -> ToolSpec {
     })
 }
 
+fn create_team_status_tool() -> ToolSpec {
+    let properties = BTreeMap::from([(
+        "team_id".to_string(),
+        JsonSchema::String {
+            description: Some("Team id returned by create_team.".to_string()),
+        },
+    )]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "team_status".to_string(),
+        description: "Return a non-blocking snapshot of a team: created_at plus each member's name, agent_id, agent_type, spawned_at (unix seconds), and current status. Use wait to block on members."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["team_id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_team_gc_tool() -> ToolSpec {
+    let properties = BTreeMap::from([(
+        "team_id".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional team id to reconcile. Omit to check every persisted team you lead."
+                    .to_string(),
+            ),
+        },
+    )]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "team_gc".to_string(),
+        description: "Reconcile the persisted state of teams you lead with live agents, e.g. after a crash. Teams with no live members are removed from disk; teams whose members are still alive are registered again. Teams of other leads are never touched. Returns each team_id with its outcome: active, reclaimed, foreign, or removed."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_team_rename_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "team_id".to_string(),
+            JsonSchema::String {
+                description: Some("Current team id.".to_string()),
+            },
+        ),
+        (
+            "new_team_id".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "New team id. Must not already be used by another team.".to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "team_rename".to_string(),
+        description: "Rename a team you lead. Members keep running; use new_team_id in every later team call. If the rename fails, the team keeps its old id."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["team_id".to_string(), "new_team_id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_close_all_tool() -> ToolSpec {
+    ToolSpec::Function(ResponsesApiTool {
+        name: "close_all".to_string(),
+        description: "Emergency stop: immediately shut down every agent you spawned, remove their worktrees, and delete every team you lead, including persisted team state. Returns a per-agent result list. Prefer close_agent or delete_team for normal cleanup."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties: BTreeMap::new(),
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_test_sync_tool() -> ToolSpec {
     let barrier_properties = BTreeMap::from([
         (
@@ -2372,13 +2869,29 @@ pub(crate) fn build_specs(
         builder.push_spec_with_parallel_support(create_close_agent_tool(), true);
         builder.push_spec_with_parallel_support(create_team_tool(config), true);
         builder.push_spec_with_parallel_support(create_delete_team_tool(), true);
+        builder.push_spec_with_parallel_support(create_team_status_tool(), true);
+        builder.push_spec_with_parallel_support(create_team_gc_tool(), true);
+        builder.push_spec_with_parallel_support(create_team_rename_tool(), true);
+        builder.push_spec_with_parallel_support(create_close_all_tool(), true);
+        builder.push_spec_with_parallel_support(create_team_add_member_tool(config), true);
+        builder.push_spec_with_parallel_support(create_team_message_wait_tool(), true);
+        builder.push_spec_with_parallel_support(create_get_agent_output_tool(), true);
+        builder.push_spec_with_parallel_support(create_list_spawned_agents_tool(), true);
         builder.register_handler("spawn_agent", multi_agent_handler.clone());
         builder.register_handler("send_message", multi_agent_handler.clone());
         builder.register_handler("resume_agent", multi_agent_handler.clone());
         builder.register_handler("wait", multi_agent_handler.clone());
         builder.register_handler("close_agent", multi_agent_handler.clone());
         builder.register_handler("create_team", multi_agent_handler.clone());
-        builder.register_handler("delete_team", multi_agent_handler);
+        builder.register_handler("delete_team", multi_agent_handler.clone());
+        builder.register_handler("team_status", multi_agent_handler.clone());
+        builder.register_handler("team_gc", multi_agent_handler.clone());
+        builder.register_handler("team_rename", multi_agent_handler.clone());
+        builder.register_handler("close_all", multi_agent_handler.clone());
+        builder.register_handler("team_add_member", multi_agent_handler.clone());
+        builder.register_handler("team_message_wait", multi_agent_handler.clone());
+        builder.register_handler("get_agent_output", multi_agent_handler.clone());
+        builder.register_handler("list_spawned_agents", multi_agent_handler);
     }
 
     if config.agent_jobs_tools || config.agent_jobs_worker_tools {
@@ -2427,6 +2940,10 @@ pub(crate) fn build_specs(
         }
     }
 
+    if config.allowed_tools.is_some() || !config.denied_tools.is_empty() {
+        builder.retain_tools(|name| config.allows_tool(name));
+    }
+
     builder
 }
 
@@ -2694,11 +3211,49 @@ mod tests {
                 "send_message",
                 "wait",
                 "close_agent",
+                "team_status",
+                "team_gc",
+                "team_rename",
+                "close_all",
+                "team_add_member",
+                "team_message_wait",
+                "get_agent_output",
+                "list_spawned_agents",
                 "spawn_agents_on_csv",
             ],
         );
     }
 
+    #[test]
+    fn test_build_specs_applies_allowed_and_denied_tools() {
+        let config = test_config();
+        let model_info =
+            ModelsManager::construct_model_info_offline_for_tests("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        features.enable(Feature::Collab);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+            session_source: SessionSource::Cli,
+            scheduled_tasks_enabled: true,
+        })
+        .with_tool_filter(
+            Some(vec!["spawn_agent".to_string(), "wait".to_string()]),
+            vec!["wait".to_string()],
+        );
+        let (tools, registry) = build_specs(&tools_config, None, None, &[]).build();
+
+        let names = tools
+            .iter()
+            .map(|tool| tool.spec.name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["spawn_agent".to_string()]);
+        assert!(registry.handler("spawn_agent").is_some());
+        assert!(registry.handler("wait").is_none());
+        assert!(builtin_tool_names(&tools_config).contains("wait"));
+    }
+
     #[test]
     fn test_build_specs_artifact_tool_enabled() {
         let config = test_config();
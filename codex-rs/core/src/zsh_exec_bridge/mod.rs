@@ -1,3 +1,10 @@
+// No shell spawn path starts the server yet; see the module docs.
+#[cfg(unix)]
+#[cfg_attr(not(test), allow(dead_code))]
+mod server;
+#[cfg(all(test, unix))]
+mod tests;
+
 #[cfg(unix)]
 use anyhow::Context as _;
 #[cfg(unix)]
@@ -15,9 +22,96 @@ use uuid::Uuid;
 pub(crate) const ZSH_EXEC_BRIDGE_WRAPPER_SOCKET_ENV_VAR: &str =
     "CODEX_ZSH_EXEC_BRIDGE_WRAPPER_SOCKET";
 pub(crate) const ZSH_EXEC_WRAPPER_MODE_ENV_VAR: &str = "CODEX_ZSH_EXEC_WRAPPER_MODE";
+pub(crate) const BASH_EXEC_WRAPPER_MODE_ENV_VAR: &str = "CODEX_BASH_EXEC_WRAPPER_MODE";
+pub(crate) const FISH_EXEC_WRAPPER_MODE_ENV_VAR: &str = "CODEX_FISH_EXEC_WRAPPER_MODE";
 #[cfg(unix)]
 pub(crate) const EXEC_WRAPPER_ENV_VAR: &str = "EXEC_WRAPPER";
 
+/// Overrides the socket connect and read timeout, in milliseconds.
+#[cfg(unix)]
+pub(crate) const EXEC_BRIDGE_TIMEOUT_MS_ENV_VAR: &str = "CODEX_EXEC_BRIDGE_TIMEOUT_MS";
+#[cfg(unix)]
+const DEFAULT_EXEC_BRIDGE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+#[cfg(unix)]
+const EXEC_BRIDGE_CONNECT_ATTEMPTS: u32 = 3;
+#[cfg(unix)]
+const EXEC_BRIDGE_CONNECT_BACKOFF: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Path of a file listing executables that run without asking the approval server.
+#[cfg(unix)]
+pub(crate) const EXEC_BRIDGE_ALLOWLIST_ENV_VAR: &str = "CODEX_EXEC_BRIDGE_ALLOWLIST";
+
+/// Path of an append-only JSONL audit log of exec bridge decisions, normally under `codex_home`.
+#[cfg(unix)]
+pub(crate) const EXEC_BRIDGE_AUDIT_LOG_ENV_VAR: &str = "CODEX_EXEC_BRIDGE_AUDIT_LOG";
+
+/// Upper bound on the environment bytes forwarded with an exec request.
+#[cfg(unix)]
+const MAX_WRAPPER_ENV_BYTES: usize = 64 * 1024;
+
+/// Script sourced through `BASH_ENV` to gate external commands on the wrapper.
+///
+/// bash has no `EXEC_WRAPPER` hook, so a `DEBUG` trap under `extdebug` asks the wrapper (running
+/// with `CODEX_BASH_EXEC_WRAPPER_MODE` set) about each external command and skips the command
+/// when the trap returns non-zero. argv is recovered by plain word splitting of `BASH_COMMAND`,
+/// which is only used to describe the command to the approval side.
+///
+/// `BASH_COMMAND` is the command before expansion, so this is not a security boundary: commands
+/// named through a variable (`"$cmd"`), prefixed with assignments (`VAR=1 cmd`) or escaped
+/// (`\cmd`) are not recognized as external commands, and subshells and command substitutions do
+/// not inherit the trap. Nothing gates Codex's own shell commands through this script.
+#[cfg(unix)]
+pub(crate) const BASH_EXEC_WRAPPER_ENV_SCRIPT: &str = r#"shopt -s extdebug
+__codex_exec_bridge_trap() {
+  [[ -n "${__codex_exec_bridge_active:-}" ]] && return 0
+  local -a __codex_words
+  read -r -a __codex_words <<< "$BASH_COMMAND"
+  [[ ${#__codex_words[@]} -gt 0 ]] || return 0
+  [[ "$(type -t -- "${__codex_words[0]}")" == "file" ]] || return 0
+  local __codex_file
+  __codex_file="$(type -P -- "${__codex_words[0]}")"
+  __codex_exec_bridge_active=1
+  CODEX_BASH_EXEC_WRAPPER_MODE=1 "$EXEC_WRAPPER" "$__codex_file" "${__codex_words[@]}"
+  local __codex_status=$?
+  __codex_exec_bridge_active=
+  return $__codex_status
+}
+trap __codex_exec_bridge_trap DEBUG
+"#;
+
+/// Script sourced through `fish --init-command` (see [`fish_exec_bridge_init_command`]) to gate
+/// external commands on the wrapper.
+///
+/// fish is not a user shell Codex runs commands in, so nothing in this crate installs the script
+/// yet; only the wrapper side is reachable, from interactive fish sessions set up by the user.
+///
+/// fish has neither `EXEC_WRAPPER` nor a cancellable preexec event, so Enter is rebound to a
+/// function that asks the wrapper (running with `CODEX_FISH_EXEC_WRAPPER_MODE` set) about the
+/// command line and only executes it when the wrapper exits 0. argv comes from
+/// `commandline -o`, which yields fish's own unquoted tokens and is passed on as a list, so no
+/// re-quoting happens on the way to the wrapper. Like the bash trap, this only gates
+/// interactive command lines whose first word is an external command.
+#[cfg(unix)]
+#[cfg_attr(not(test), allow(dead_code))]
+pub(crate) const FISH_EXEC_WRAPPER_INIT_SCRIPT: &str = r#"function __codex_exec_bridge_execute
+    set -l __codex_words (commandline -o)
+    if test (count $__codex_words) -gt 0
+        set -l __codex_kind (type -t -- $__codex_words[1] 2>/dev/null)
+        if test "$__codex_kind" = file
+            set -l __codex_file (command -s -- $__codex_words[1])
+            if not env CODEX_FISH_EXEC_WRAPPER_MODE=1 $EXEC_WRAPPER $__codex_file $__codex_words
+                commandline -r ''
+                commandline -f repaint
+                return 1
+            end
+        end
+    end
+    commandline -f execute
+end
+bind \r __codex_exec_bridge_execute
+bind \n __codex_exec_bridge_execute
+"#;
+
 #[cfg(unix)]
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -27,6 +121,10 @@ enum WrapperIpcRequest {
         file: String,
         argv: Vec<String>,
         cwd: String,
+        /// Wrapper environment, bounded by [`MAX_WRAPPER_ENV_BYTES`]. Defaults to empty so
+        /// requests from wrappers that predate this field still parse.
+        #[serde(default)]
+        env: Vec<(String, String)>,
     },
 }
 
@@ -41,12 +139,45 @@ enum WrapperIpcResponse {
     },
 }
 
+#[cfg(unix)]
+#[derive(Debug, Serialize)]
+struct ExecBridgeAuditEntry<'a> {
+    timestamp: String,
+    file: &'a str,
+    argv: &'a [String],
+    cwd: &'a str,
+    action: &'a WrapperExecAction,
+    reason: Option<&'a str>,
+}
+
 #[cfg(unix)]
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 enum WrapperExecAction {
     Run,
     Deny,
+    /// Ask the user on the controlling terminal, using `reason` as the prompt.
+    Ask,
+}
+
+/// What the wrapper does once the approval side allows a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WrapperShell {
+    /// zsh hands the command to `EXEC_WRAPPER`, so the wrapper runs it.
+    Zsh,
+    /// bash runs the command itself after the `DEBUG` trap returns, so the wrapper only exits 0.
+    Bash,
+    /// fish runs the command line itself once the Enter binding sees exit 0.
+    Fish,
+}
+
+/// Runs this process as an exec bridge wrapper when one of the wrapper mode variables is set, and
+/// returns `Ok(false)` otherwise. A binary used as `EXEC_WRAPPER` must call this first thing in
+/// `main`, since the bridged shell re-invokes that executable as the wrapper.
+pub fn maybe_run_exec_wrapper_mode() -> anyhow::Result<bool> {
+    Ok(maybe_run_zsh_exec_wrapper_mode()?
+        || maybe_run_bash_exec_wrapper_mode()?
+        || maybe_run_fish_exec_wrapper_mode()?)
 }
 
 pub fn maybe_run_zsh_exec_wrapper_mode() -> anyhow::Result<bool> {
@@ -54,21 +185,61 @@ pub fn maybe_run_zsh_exec_wrapper_mode() -> anyhow::Result<bool> {
         return Ok(false);
     }
 
-    run_exec_wrapper_mode()?;
+    run_exec_wrapper_mode(WrapperShell::Zsh, std::env::args().collect())?;
     Ok(true)
 }
 
-fn run_exec_wrapper_mode() -> anyhow::Result<()> {
+pub fn maybe_run_bash_exec_wrapper_mode() -> anyhow::Result<bool> {
+    if std::env::var_os(BASH_EXEC_WRAPPER_MODE_ENV_VAR).is_none() {
+        return Ok(false);
+    }
+
+    run_exec_wrapper_mode(WrapperShell::Bash, std::env::args().collect())?;
+    Ok(true)
+}
+
+pub fn maybe_run_fish_exec_wrapper_mode() -> anyhow::Result<bool> {
+    if std::env::var_os(FISH_EXEC_WRAPPER_MODE_ENV_VAR).is_none() {
+        return Ok(false);
+    }
+
+    run_exec_wrapper_mode(WrapperShell::Fish, std::env::args().collect())?;
+    Ok(true)
+}
+
+/// Builds the `fish --init-command` argument that sources the script at `script_path`.
+#[cfg(unix)]
+#[cfg_attr(not(test), allow(dead_code))]
+pub(crate) fn fish_exec_bridge_init_command(script_path: &std::path::Path) -> String {
+    format!("source {}", fish_quote(&script_path.to_string_lossy()))
+}
+
+/// Quotes `arg` as a single fish word. Inside fish single quotes only `\` and `'` are special.
+#[cfg(unix)]
+#[cfg_attr(not(test), allow(dead_code))]
+fn fish_quote(arg: &str) -> String {
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('\'');
+    for ch in arg.chars() {
+        if matches!(ch, '\\' | '\'') {
+            quoted.push('\\');
+        }
+        quoted.push(ch);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// Runs the wrapper for `args`, laid out as `[wrapper, file, argv...]`.
+fn run_exec_wrapper_mode(shell: WrapperShell, args: Vec<String>) -> anyhow::Result<()> {
     #[cfg(not(unix))]
     {
-        anyhow::bail!("zsh exec wrapper mode is only supported on unix");
+        let _ = (shell, args);
+        anyhow::bail!("exec wrapper mode is only supported on unix");
     }
 
     #[cfg(unix)]
     {
-        use std::os::unix::net::UnixStream as StdUnixStream;
-
-        let args: Vec<String> = std::env::args().collect();
         if args.len() < 2 {
             anyhow::bail!("exec wrapper mode requires target executable path");
         }
@@ -82,50 +253,26 @@ fn run_exec_wrapper_mode() -> anyhow::Result<()> {
             .context("resolve wrapper cwd")?
             .to_string_lossy()
             .to_string();
-        let socket_path = std::env::var(ZSH_EXEC_BRIDGE_WRAPPER_SOCKET_ENV_VAR)
-            .context("missing wrapper socket path env var")?;
-
-        let request_id = Uuid::new_v4().to_string();
-        let request = WrapperIpcRequest::ExecRequest {
-            request_id: request_id.clone(),
-            file: file.clone(),
-            argv: argv.clone(),
-            cwd,
+        let (action, reason) = if exec_bridge_allowlist_matches(&file) {
+            (WrapperExecAction::Run, Some("allowlisted".to_string()))
+        } else {
+            request_exec_decision(&file, &argv, &cwd)?
         };
 
-        let mut stream = StdUnixStream::connect(&socket_path)
-            .with_context(|| format!("connect to wrapper socket at {socket_path}"))?;
-        let encoded = serde_json::to_string(&request).context("serialize wrapper request")?;
-        stream
-            .write_all(encoded.as_bytes())
-            .context("write wrapper request")?;
-        stream
-            .write_all(b"\n")
-            .context("write wrapper request newline")?;
-        stream
-            .shutdown(std::net::Shutdown::Write)
-            .context("shutdown wrapper write")?;
-
-        let mut response_buf = String::new();
-        stream
-            .read_to_string(&mut response_buf)
-            .context("read wrapper response")?;
-        let response: WrapperIpcResponse =
-            serde_json::from_str(response_buf.trim()).context("parse wrapper response")?;
-
-        let (response_request_id, action, reason) = match response {
-            WrapperIpcResponse::ExecResponse {
-                request_id,
-                action,
-                reason,
-            } => (request_id, action, reason),
-        };
-        if response_request_id != request_id {
-            anyhow::bail!(
-                "wrapper response request_id mismatch: expected {request_id}, got {response_request_id}"
+        let (action, reason) = resolve_ask(action, reason, &argv, prompt_on_controlling_tty);
+        if let Some(path) = std::env::var_os(EXEC_BRIDGE_AUDIT_LOG_ENV_VAR) {
+            append_audit_log_entry(
+                std::path::Path::new(&path),
+                &ExecBridgeAuditEntry {
+                    timestamp: chrono::Utc::now().to_rfc3339(),
+                    file: &file,
+                    argv: &argv,
+                    cwd: &cwd,
+                    action: &action,
+                    reason: reason.as_deref(),
+                },
             );
         }
-
         if action == WrapperExecAction::Deny {
             if let Some(reason) = reason {
                 tracing::warn!("execution denied: {reason}");
@@ -134,15 +281,305 @@ fn run_exec_wrapper_mode() -> anyhow::Result<()> {
             }
             std::process::exit(1);
         }
+        if matches!(shell, WrapperShell::Bash | WrapperShell::Fish) {
+            std::process::exit(0);
+        }
 
         let mut command = std::process::Command::new(&file);
         if argv.len() > 1 {
             command.args(&argv[1..]);
         }
         command.env_remove(ZSH_EXEC_WRAPPER_MODE_ENV_VAR);
+        command.env_remove(BASH_EXEC_WRAPPER_MODE_ENV_VAR);
+        command.env_remove(FISH_EXEC_WRAPPER_MODE_ENV_VAR);
         command.env_remove(ZSH_EXEC_BRIDGE_WRAPPER_SOCKET_ENV_VAR);
         command.env_remove(EXEC_WRAPPER_ENV_VAR);
-        let status = command.status().context("spawn wrapped executable")?;
-        std::process::exit(status.code().unwrap_or(1));
+        // Replace the wrapper instead of spawning a child: the command keeps the shell's
+        // terminal, process group and signals (so editors and pagers behave), and no wrapper
+        // process is left behind whose environment a nested exec could re-enter through.
+        let err = std::os::unix::process::CommandExt::exec(&mut command);
+        Err(err).context("exec wrapped executable")
+    }
+}
+
+/// Turns an `Ask` answer into `Run` or `Deny` by asking `confirm`, with `reason` (or a default
+/// question about `argv`) as the prompt. Other actions pass through unchanged.
+#[cfg(unix)]
+fn resolve_ask(
+    action: WrapperExecAction,
+    reason: Option<String>,
+    argv: &[String],
+    confirm: impl FnOnce(&str) -> bool,
+) -> (WrapperExecAction, Option<String>) {
+    if action != WrapperExecAction::Ask {
+        return (action, reason);
+    }
+    let prompt = reason.unwrap_or_else(|| format!("Run `{}`?", argv.join(" ")));
+    if confirm(&prompt) {
+        (WrapperExecAction::Run, None)
+    } else {
+        (
+            WrapperExecAction::Deny,
+            Some("not approved at the terminal prompt".to_string()),
+        )
+    }
+}
+
+/// Asks the approval server about one exec over the wrapper socket. Exits the process (denying
+/// the command) when the server cannot be reached or does not answer.
+#[cfg(unix)]
+fn request_exec_decision(
+    file: &str,
+    argv: &[String],
+    cwd: &str,
+) -> anyhow::Result<(WrapperExecAction, Option<String>)> {
+    let socket_path = std::env::var(ZSH_EXEC_BRIDGE_WRAPPER_SOCKET_ENV_VAR)
+        .context("missing wrapper socket path env var")?;
+
+    let request_id = Uuid::new_v4().to_string();
+    let request = WrapperIpcRequest::ExecRequest {
+        request_id: request_id.clone(),
+        file: file.to_string(),
+        argv: argv.to_vec(),
+        cwd: cwd.to_string(),
+        env: bounded_wrapper_env(std::env::vars_os().map(|(key, value)| {
+            (
+                key.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            )
+        })),
+    };
+
+    let timeout = exec_bridge_timeout();
+    let mut stream = match connect_with_retry(&socket_path, timeout) {
+        Ok(stream) => stream,
+        Err(err) => {
+            // Fail closed: never run a command the approval side could not see.
+            tracing::warn!(
+                "execution denied: wrapper socket at {socket_path} is unreachable: {err}"
+            );
+            std::process::exit(1);
+        }
+    };
+    stream
+        .set_read_timeout(Some(timeout))
+        .context("set wrapper read timeout")?;
+    stream
+        .set_write_timeout(Some(timeout))
+        .context("set wrapper write timeout")?;
+    let encoded = serde_json::to_string(&request).context("serialize wrapper request")?;
+    stream
+        .write_all(encoded.as_bytes())
+        .context("write wrapper request")?;
+    stream
+        .write_all(b"\n")
+        .context("write wrapper request newline")?;
+    stream
+        .shutdown(std::net::Shutdown::Write)
+        .context("shutdown wrapper write")?;
+
+    let mut response_buf = String::new();
+    if let Err(err) = stream.read_to_string(&mut response_buf) {
+        tracing::warn!("execution denied: failed to read wrapper response: {err}");
+        std::process::exit(1);
+    }
+    parse_exec_response(&response_buf, &request_id)
+}
+
+/// Parses the server's answer to the request with id `request_id`.
+#[cfg(unix)]
+fn parse_exec_response(
+    raw: &str,
+    request_id: &str,
+) -> anyhow::Result<(WrapperExecAction, Option<String>)> {
+    let response: WrapperIpcResponse = serde_json::from_str(raw.trim())
+        .with_context(|| format!("parse wrapper response: {}", truncate_for_error(raw.trim())))?;
+
+    let (response_request_id, action, reason) = match response {
+        WrapperIpcResponse::ExecResponse {
+            request_id,
+            action,
+            reason,
+        } => (request_id, action, reason),
+    };
+    if response_request_id.trim().is_empty() {
+        anyhow::bail!("wrapper server returned empty request_id");
+    }
+    if response_request_id != request_id {
+        anyhow::bail!(
+            "wrapper response request_id mismatch: expected {request_id}, got {response_request_id}"
+        );
+    }
+    Ok((action, reason))
+}
+
+/// Whether `file` is listed in the allowlist named by [`EXEC_BRIDGE_ALLOWLIST_ENV_VAR`].
+///
+/// The file holds one entry per line; blank lines and lines starting with `#` are ignored. A plain
+/// entry is an absolute path and matches only that exact executable path, so an allowlisted
+/// `/bin/ls` does not approve a `./ls` dropped in the workspace. An entry of the form
+/// `re:<pattern>` is a regex that must match the whole path. A missing or unreadable file
+/// allowlists nothing, and relative entries and invalid patterns are skipped.
+#[cfg(unix)]
+fn exec_bridge_allowlist_matches(file: &str) -> bool {
+    let Some(path) = std::env::var_os(EXEC_BRIDGE_ALLOWLIST_ENV_VAR) else {
+        return false;
+    };
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            tracing::debug!("failed to read exec bridge allowlist: {err}");
+            return false;
+        }
+    };
+    allowlist_matches(&contents, file)
+}
+
+/// Whether any entry of the allowlist `contents` matches `file`; see
+/// [`exec_bridge_allowlist_matches`] for the format.
+#[cfg(unix)]
+fn allowlist_matches(contents: &str, file: &str) -> bool {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty() && !entry.starts_with('#'))
+        .any(|entry| match entry.strip_prefix("re:") {
+            Some(pattern) => match regex_lite::Regex::new(&format!("^(?:{pattern})$")) {
+                Ok(regex) => regex.is_match(file),
+                Err(err) => {
+                    tracing::debug!("skipping invalid exec bridge allowlist pattern: {err}");
+                    false
+                }
+            },
+            None if !std::path::Path::new(entry).is_absolute() => {
+                tracing::debug!("skipping relative exec bridge allowlist entry: {entry}");
+                false
+            }
+            None => entry == file,
+        })
+}
+
+/// Limit on how much of a malformed wrapper response is echoed into errors.
+#[cfg(unix)]
+const MAX_ERROR_RESPONSE_CHARS: usize = 512;
+
+#[cfg(unix)]
+fn truncate_for_error(raw: &str) -> String {
+    if raw.is_empty() {
+        return "<empty response>".to_string();
+    }
+    match raw.char_indices().nth(MAX_ERROR_RESPONSE_CHARS) {
+        Some((end, _)) => format!("{}... (truncated)", &raw[..end]),
+        None => raw.to_string(),
+    }
+}
+
+/// Best-effort append to the audit log; failures are logged and never affect the decision.
+#[cfg(unix)]
+fn append_audit_log_entry(path: &std::path::Path, entry: &ExecBridgeAuditEntry<'_>) {
+    let line = match serde_json::to_string(entry) {
+        Ok(line) => line,
+        Err(err) => {
+            tracing::debug!("failed to serialize exec bridge audit entry: {err}");
+            return;
+        }
+    };
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(format!("{line}\n").as_bytes()));
+    if let Err(err) = result {
+        tracing::debug!("failed to append exec bridge audit entry: {err}");
+    }
+}
+
+#[cfg(unix)]
+fn exec_bridge_timeout() -> std::time::Duration {
+    std::env::var(EXEC_BRIDGE_TIMEOUT_MS_ENV_VAR)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|millis| *millis > 0)
+        .map_or(
+            DEFAULT_EXEC_BRIDGE_TIMEOUT,
+            std::time::Duration::from_millis,
+        )
+}
+
+/// Connects to the wrapper socket, retrying with exponential backoff until `timeout` elapses or
+/// [`EXEC_BRIDGE_CONNECT_ATTEMPTS`] attempts have failed.
+#[cfg(unix)]
+fn connect_with_retry(
+    socket_path: &str,
+    timeout: std::time::Duration,
+) -> std::io::Result<std::os::unix::net::UnixStream> {
+    let deadline = std::time::Instant::now() + timeout;
+    let mut backoff = EXEC_BRIDGE_CONNECT_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match std::os::unix::net::UnixStream::connect(socket_path) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => {
+                let now = std::time::Instant::now();
+                if attempt >= EXEC_BRIDGE_CONNECT_ATTEMPTS || now + backoff > deadline {
+                    return Err(err);
+                }
+                tracing::debug!("retrying wrapper socket connect after error: {err}");
+                std::thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Collects environment entries in order until [`MAX_WRAPPER_ENV_BYTES`] would be exceeded.
+#[cfg(unix)]
+fn bounded_wrapper_env(vars: impl Iterator<Item = (String, String)>) -> Vec<(String, String)> {
+    let mut total_bytes = 0usize;
+    let mut env = Vec::new();
+    for (key, value) in vars {
+        total_bytes += key.len() + value.len();
+        if total_bytes > MAX_WRAPPER_ENV_BYTES {
+            tracing::warn!(
+                "truncating exec wrapper environment at {} entries",
+                env.len()
+            );
+            break;
+        }
+        env.push((key, value));
+    }
+    env
+}
+
+/// Asks a yes/no question on the controlling terminal.
+///
+/// Returns `false` when there is no terminal to ask on, so `Ask` falls back to `Deny` for
+/// non-interactive shells.
+#[cfg(unix)]
+fn prompt_on_controlling_tty(prompt: &str) -> bool {
+    use std::io::BufRead;
+
+    let Ok(tty) = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+    else {
+        return false;
+    };
+    let mut writer = &tty;
+    if write!(writer, "{prompt} [y/N] ")
+        .and_then(|()| writer.flush())
+        .is_err()
+    {
+        return false;
+    }
+    let mut answer = String::new();
+    if std::io::BufReader::new(&tty)
+        .read_line(&mut answer)
+        .is_err()
+    {
+        return false;
     }
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
 }
@@ -0,0 +1,215 @@
+//! Approval side of the exec bridge: answers [`WrapperIpcRequest`]s on a Unix socket by asking an
+//! [`EscalationPolicy`], the same policy the zsh-fork backend consults for intercepted execs.
+//!
+//! Codex does not start this server for its own shell commands: the bash `DEBUG` trap only sees
+//! unexpanded commands (see [`BASH_EXEC_WRAPPER_ENV_SCRIPT`]), so it cannot gate them reliably.
+//! Wiring it into the shell runtime needs a gate that works on the resolved argv.
+
+use super::BASH_EXEC_WRAPPER_ENV_SCRIPT;
+use super::EXEC_BRIDGE_ALLOWLIST_ENV_VAR;
+use super::EXEC_BRIDGE_AUDIT_LOG_ENV_VAR;
+use super::EXEC_BRIDGE_TIMEOUT_MS_ENV_VAR;
+use super::EXEC_WRAPPER_ENV_VAR;
+use super::WrapperExecAction;
+use super::WrapperIpcRequest;
+use super::WrapperIpcResponse;
+use super::ZSH_EXEC_BRIDGE_WRAPPER_SOCKET_ENV_VAR;
+use anyhow::Context as _;
+use codex_shell_escalation::EscalationDecision;
+use codex_shell_escalation::EscalationPolicy;
+use codex_utils_absolute_path::AbsolutePathBuf;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::UnixListener;
+use tokio::net::UnixStream;
+use tokio::task::JoinHandle;
+use tokio::task::JoinSet;
+
+const SOCKET_FILE_NAME: &str = "bridge.sock";
+const BASH_ENV_FILE_NAME: &str = "bash_env.sh";
+
+/// A running exec bridge. Requests are answered until the server is dropped, which also cancels
+/// any request still waiting on the policy.
+pub(crate) struct ExecBridgeServer {
+    dir: tempfile::TempDir,
+    task: JoinHandle<()>,
+}
+
+impl ExecBridgeServer {
+    pub(crate) fn start(policy: Arc<dyn EscalationPolicy>) -> std::io::Result<Self> {
+        let dir = tempfile::Builder::new()
+            .prefix("codex-exec-bridge-")
+            .tempdir()?;
+        std::fs::write(
+            dir.path().join(BASH_ENV_FILE_NAME),
+            BASH_EXEC_WRAPPER_ENV_SCRIPT,
+        )?;
+        let listener = UnixListener::bind(dir.path().join(SOCKET_FILE_NAME))?;
+        let task = tokio::spawn(serve(listener, policy));
+        Ok(Self { dir, task })
+    }
+
+    pub(crate) fn socket_path(&self) -> PathBuf {
+        self.dir.path().join(SOCKET_FILE_NAME)
+    }
+
+    /// Environment that makes a bash child ask this server about each external command it runs,
+    /// through `wrapper_exe` started in bash wrapper mode.
+    ///
+    /// `timeout` bounds how long the wrapper waits for an answer; it should cover an approval
+    /// prompt, so callers pass the command's own timeout. The allowlist, audit log and timeout
+    /// overrides set for this process are forwarded so the wrapper sees the same settings.
+    pub(crate) fn bash_env(
+        &self,
+        wrapper_exe: &Path,
+        timeout: Duration,
+    ) -> HashMap<String, String> {
+        let mut env = HashMap::from([
+            (
+                "BASH_ENV".to_string(),
+                self.dir
+                    .path()
+                    .join(BASH_ENV_FILE_NAME)
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            (
+                EXEC_WRAPPER_ENV_VAR.to_string(),
+                wrapper_exe.to_string_lossy().into_owned(),
+            ),
+            (
+                ZSH_EXEC_BRIDGE_WRAPPER_SOCKET_ENV_VAR.to_string(),
+                self.socket_path().to_string_lossy().into_owned(),
+            ),
+            (
+                EXEC_BRIDGE_TIMEOUT_MS_ENV_VAR.to_string(),
+                timeout.as_millis().to_string(),
+            ),
+        ]);
+        for var in [
+            EXEC_BRIDGE_TIMEOUT_MS_ENV_VAR,
+            EXEC_BRIDGE_ALLOWLIST_ENV_VAR,
+            EXEC_BRIDGE_AUDIT_LOG_ENV_VAR,
+        ] {
+            if let Ok(value) = std::env::var(var) {
+                env.insert(var.to_string(), value);
+            }
+        }
+        env
+    }
+}
+
+impl Drop for ExecBridgeServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve(listener: UnixListener, policy: Arc<dyn EscalationPolicy>) {
+    // Connections live in this task's JoinSet, so aborting the task cancels them as well.
+    let mut connections = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let stream = match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(err) => {
+                        tracing::warn!("exec bridge stopped accepting connections: {err}");
+                        return;
+                    }
+                };
+                let policy = Arc::clone(&policy);
+                connections.spawn(async move {
+                    if let Err(err) = handle_connection(stream, policy.as_ref()).await {
+                        tracing::warn!("exec bridge request failed: {err:#}");
+                    }
+                });
+            }
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+        }
+    }
+}
+
+async fn handle_connection(
+    stream: UnixStream,
+    policy: &dyn EscalationPolicy,
+) -> anyhow::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    tokio::io::BufReader::new(reader)
+        .read_line(&mut line)
+        .await
+        .context("read wrapper request")?;
+    let WrapperIpcRequest::ExecRequest {
+        request_id,
+        file,
+        argv,
+        cwd,
+        env: _,
+    } = serde_json::from_str(line.trim()).context("parse wrapper request")?;
+
+    let (action, reason) = exec_decision(policy, &file, &argv, &cwd).await;
+    tracing::debug!("exec bridge decided {action:?} for {file:?} {argv:?} in {cwd:?}");
+    let mut encoded = serde_json::to_vec(&WrapperIpcResponse::ExecResponse {
+        request_id,
+        action,
+        reason,
+    })
+    .context("serialize wrapper response")?;
+    encoded.push(b'\n');
+    writer
+        .write_all(&encoded)
+        .await
+        .context("write wrapper response")?;
+    writer
+        .shutdown()
+        .await
+        .context("shutdown wrapper response")?;
+    Ok(())
+}
+
+/// Maps the policy decision onto the bridge protocol. The bridged shell keeps whatever sandbox it
+/// was started with, so an approved escalation runs the command in place like `Run`.
+///
+/// This is the decision point a `pre_exec` hook would be dispatched from. There is no such hook
+/// event yet because nothing starts this server, so the hook would never fire.
+async fn exec_decision(
+    policy: &dyn EscalationPolicy,
+    file: &str,
+    argv: &[String],
+    cwd: &str,
+) -> (WrapperExecAction, Option<String>) {
+    let workdir = match AbsolutePathBuf::from_absolute_path(cwd) {
+        Ok(workdir) => workdir,
+        Err(err) => {
+            return (
+                WrapperExecAction::Deny,
+                Some(format!("invalid working directory `{cwd}`: {err}")),
+            );
+        }
+    };
+    let program = match AbsolutePathBuf::resolve_path_against_base(file, workdir.as_path()) {
+        Ok(program) => program,
+        Err(err) => {
+            return (
+                WrapperExecAction::Deny,
+                Some(format!("invalid executable `{file}`: {err}")),
+            );
+        }
+    };
+    match policy.determine_action(&program, argv, &workdir).await {
+        Ok(EscalationDecision::Run | EscalationDecision::Escalate(_)) => {
+            (WrapperExecAction::Run, None)
+        }
+        Ok(EscalationDecision::Deny { reason }) => (WrapperExecAction::Deny, reason),
+        Err(err) => (
+            WrapperExecAction::Deny,
+            Some(format!("failed to evaluate exec policy: {err:#}")),
+        ),
+    }
+}
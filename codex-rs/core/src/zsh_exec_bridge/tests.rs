@@ -0,0 +1,558 @@
+use super::server::ExecBridgeServer;
+use super::*;
+use codex_shell_escalation::EscalationDecision;
+use codex_shell_escalation::EscalationExecution;
+use codex_shell_escalation::EscalationPolicy;
+use codex_utils_absolute_path::AbsolutePathBuf;
+use pretty_assertions::assert_eq;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+
+struct FixedPolicy {
+    decision: EscalationDecision,
+}
+
+#[async_trait::async_trait]
+impl EscalationPolicy for FixedPolicy {
+    async fn determine_action(
+        &self,
+        _file: &AbsolutePathBuf,
+        _argv: &[String],
+        _workdir: &AbsolutePathBuf,
+    ) -> anyhow::Result<EscalationDecision> {
+        Ok(self.decision.clone())
+    }
+}
+
+fn start_server(decision: EscalationDecision) -> ExecBridgeServer {
+    ExecBridgeServer::start(Arc::new(FixedPolicy { decision })).expect("start exec bridge")
+}
+
+fn write_executable(path: &Path, contents: &str) {
+    std::fs::write(path, contents).expect("write script");
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .expect("make script executable");
+}
+
+async fn send_raw_request(server: &ExecBridgeServer, request: &str) -> String {
+    let mut stream = tokio::net::UnixStream::connect(server.socket_path())
+        .await
+        .expect("connect to exec bridge");
+    stream
+        .write_all(format!("{request}\n").as_bytes())
+        .await
+        .expect("write request");
+    stream.shutdown().await.expect("shutdown write");
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .await
+        .expect("read response");
+    response
+}
+
+fn exec_request(request_id: &str, cwd: &Path) -> String {
+    serde_json::to_string(&WrapperIpcRequest::ExecRequest {
+        request_id: request_id.to_string(),
+        file: "/bin/ls".to_string(),
+        argv: vec!["ls".to_string(), "-l".to_string()],
+        cwd: cwd.to_string_lossy().into_owned(),
+        env: Vec::new(),
+    })
+    .expect("serialize request")
+}
+
+#[tokio::test]
+async fn server_answers_exec_requests_with_the_policy_decision() {
+    let cwd = tempfile::tempdir().expect("tempdir");
+    let server = start_server(EscalationDecision::Deny {
+        reason: Some("blocked by policy".to_string()),
+    });
+
+    let response = send_raw_request(&server, &exec_request("req-1", cwd.path())).await;
+
+    let WrapperIpcResponse::ExecResponse {
+        request_id,
+        action,
+        reason,
+    } = serde_json::from_str(response.trim()).expect("parse response");
+    assert_eq!(request_id, "req-1");
+    assert_eq!(action, WrapperExecAction::Deny);
+    assert_eq!(reason.as_deref(), Some("blocked by policy"));
+}
+
+#[tokio::test]
+async fn server_runs_approved_escalations_in_place() {
+    let cwd = tempfile::tempdir().expect("tempdir");
+    let server = start_server(EscalationDecision::Escalate(
+        EscalationExecution::Unsandboxed,
+    ));
+
+    let response = send_raw_request(&server, &exec_request("req-2", cwd.path())).await;
+
+    let WrapperIpcResponse::ExecResponse { action, reason, .. } =
+        serde_json::from_str(response.trim()).expect("parse response");
+    assert_eq!(action, WrapperExecAction::Run);
+    assert_eq!(reason, None);
+}
+
+#[tokio::test]
+async fn bash_env_points_bash_at_the_bridge() {
+    let server = start_server(EscalationDecision::Run);
+    let wrapper = Path::new("/usr/local/bin/codex");
+
+    let env = server.bash_env(wrapper, Duration::from_secs(30));
+
+    let bash_env = env.get("BASH_ENV").expect("BASH_ENV should be set");
+    assert_eq!(
+        std::fs::read_to_string(bash_env).expect("read BASH_ENV script"),
+        BASH_EXEC_WRAPPER_ENV_SCRIPT
+    );
+    assert_eq!(
+        env.get(EXEC_WRAPPER_ENV_VAR).map(String::as_str),
+        Some("/usr/local/bin/codex")
+    );
+    assert_eq!(
+        env.get(ZSH_EXEC_BRIDGE_WRAPPER_SOCKET_ENV_VAR)
+            .map(String::as_str),
+        Some(server.socket_path().to_string_lossy().as_ref())
+    );
+}
+
+#[test]
+fn bash_env_script_skips_commands_the_wrapper_denies() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let script = dir.path().join("bash_env.sh");
+    std::fs::write(&script, BASH_EXEC_WRAPPER_ENV_SCRIPT).expect("write BASH_ENV script");
+    // Stands in for the wrapper: denies `touch denied` and allows everything else.
+    let wrapper = dir.path().join("wrapper");
+    write_executable(
+        &wrapper,
+        "#!/bin/sh\n[ \"$CODEX_BASH_EXEC_WRAPPER_MODE\" = 1 ] || exit 2\n[ \"$3\" = denied ] && exit 1\nexit 0\n",
+    );
+
+    let output = std::process::Command::new("bash")
+        .arg("-c")
+        .arg("touch allowed; touch denied; echo done")
+        .current_dir(dir.path())
+        .env("BASH_ENV", &script)
+        .env(EXEC_WRAPPER_ENV_VAR, &wrapper)
+        .output()
+        .expect("run bash");
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "done\n");
+    assert!(dir.path().join("allowed").exists());
+    assert!(!dir.path().join("denied").exists());
+}
+
+#[test]
+fn ask_response_carries_the_prompt_in_reason() {
+    let WrapperIpcResponse::ExecResponse { action, reason, .. } = serde_json::from_str(
+        r#"{"type":"exec_response","request_id":"req-3","action":"ask","reason":"Delete the build dir?"}"#,
+    )
+    .expect("parse ask response");
+    assert_eq!(action, WrapperExecAction::Ask);
+    assert_eq!(reason.as_deref(), Some("Delete the build dir?"));
+}
+
+#[test]
+fn ask_runs_the_command_only_when_confirmed() {
+    let argv = vec!["rm".to_string(), "-rf".to_string(), "build".to_string()];
+
+    let mut prompts = Vec::new();
+    let approved = resolve_ask(WrapperExecAction::Ask, None, &argv, |prompt| {
+        prompts.push(prompt.to_string());
+        true
+    });
+    assert_eq!(approved, (WrapperExecAction::Run, None));
+    assert_eq!(prompts, vec!["Run `rm -rf build`?".to_string()]);
+
+    let declined = resolve_ask(
+        WrapperExecAction::Ask,
+        Some("Delete the build dir?".to_string()),
+        &argv,
+        |prompt| {
+            assert_eq!(prompt, "Delete the build dir?");
+            false
+        },
+    );
+    assert_eq!(
+        declined,
+        (
+            WrapperExecAction::Deny,
+            Some("not approved at the terminal prompt".to_string())
+        )
+    );
+
+    let denied = resolve_ask(WrapperExecAction::Deny, None, &argv, |_| {
+        panic!("only ask should prompt")
+    });
+    assert_eq!(denied, (WrapperExecAction::Deny, None));
+}
+
+#[test]
+fn bounded_wrapper_env_stops_before_the_byte_limit() {
+    let big = "x".repeat(MAX_WRAPPER_ENV_BYTES / 2);
+    let vars = vec![
+        ("A".to_string(), big.clone()),
+        ("B".to_string(), "small".to_string()),
+        ("C".to_string(), big),
+        ("D".to_string(), "after".to_string()),
+    ];
+
+    let env = bounded_wrapper_env(vars.into_iter());
+
+    let keys: Vec<&str> = env.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, vec!["A", "B"]);
+}
+
+#[test]
+fn exec_request_without_env_still_parses() {
+    let WrapperIpcRequest::ExecRequest { argv, env, .. } = serde_json::from_str(
+        r#"{"type":"exec_request","request_id":"req-4","file":"/bin/ls","argv":["ls"],"cwd":"/tmp"}"#,
+    )
+    .expect("parse request from an older wrapper");
+    assert_eq!(argv, vec!["ls".to_string()]);
+    assert_eq!(env, Vec::new());
+}
+
+const CHILD_SHELL_ENV_VAR: &str = "CODEX_EXEC_BRIDGE_TEST_CHILD_SHELL";
+const CHILD_ARGS_ENV_VAR: &str = "CODEX_EXEC_BRIDGE_TEST_CHILD_ARGS";
+
+/// Child side of [`wrapper_child_command`]: runs the wrapper in this process, which it may exit
+/// or replace. Does nothing in a normal test run.
+#[test]
+fn exec_wrapper_child() {
+    let (Ok(shell), Ok(args)) = (
+        std::env::var(CHILD_SHELL_ENV_VAR),
+        std::env::var(CHILD_ARGS_ENV_VAR),
+    ) else {
+        return;
+    };
+    let shell = match shell.as_str() {
+        "zsh" => WrapperShell::Zsh,
+        "bash" => WrapperShell::Bash,
+        "fish" => WrapperShell::Fish,
+        other => panic!("unexpected wrapper shell {other}"),
+    };
+    let args: Vec<String> = serde_json::from_str(&args).expect("parse child args");
+    run_exec_wrapper_mode(shell, args).expect("run exec wrapper");
+    panic!("the exec wrapper should exit or replace the process");
+}
+
+/// Re-runs this test binary as the wrapper for `file` and `argv`, since the wrapper exits or
+/// execs and so cannot run inside the test process.
+fn wrapper_child_command(shell: &str, file: &str, argv: &[&str]) -> std::process::Command {
+    let mut args = vec!["wrapper".to_string(), file.to_string()];
+    args.extend(argv.iter().map(ToString::to_string));
+    let mut command =
+        std::process::Command::new(std::env::current_exe().expect("locate test binary"));
+    command
+        .args([
+            "zsh_exec_bridge::tests::exec_wrapper_child",
+            "--exact",
+            "--nocapture",
+        ])
+        .env(CHILD_SHELL_ENV_VAR, shell)
+        .env(
+            CHILD_ARGS_ENV_VAR,
+            serde_json::to_string(&args).expect("serialize child args"),
+        )
+        .env_remove(EXEC_BRIDGE_ALLOWLIST_ENV_VAR)
+        .env_remove(EXEC_BRIDGE_AUDIT_LOG_ENV_VAR);
+    command
+}
+
+#[test]
+fn wrapper_denies_when_the_socket_is_unreachable() {
+    let dir = tempfile::tempdir().expect("tempdir");
+
+    let output = wrapper_child_command("zsh", "/bin/sh", &["sh", "-c", "touch ran"])
+        .current_dir(dir.path())
+        .env(
+            ZSH_EXEC_BRIDGE_WRAPPER_SOCKET_ENV_VAR,
+            dir.path().join("missing.sock"),
+        )
+        .env(EXEC_BRIDGE_TIMEOUT_MS_ENV_VAR, "200")
+        .output()
+        .expect("run wrapper child");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!dir.path().join("ran").exists());
+}
+
+#[test]
+fn connect_with_retry_reports_a_missing_socket() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let socket_path = dir.path().join("bridge.sock");
+    let socket_path = socket_path.to_string_lossy();
+
+    let err = connect_with_retry(&socket_path, Duration::from_millis(500))
+        .expect_err("nothing is listening yet");
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+
+    let _listener =
+        std::os::unix::net::UnixListener::bind(socket_path.as_ref()).expect("bind socket");
+    connect_with_retry(&socket_path, Duration::from_millis(500))
+        .expect("connect once the socket is bound");
+}
+
+#[test]
+fn audit_log_appends_one_json_line_per_decision() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let log = dir.path().join("exec_bridge_audit.jsonl");
+    let argv = vec!["rm".to_string(), "-rf".to_string(), "build".to_string()];
+
+    for (action, reason) in [
+        (WrapperExecAction::Run, None),
+        (WrapperExecAction::Deny, Some("blocked by policy")),
+    ] {
+        append_audit_log_entry(
+            &log,
+            &ExecBridgeAuditEntry {
+                timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+                file: "/bin/rm",
+                argv: &argv,
+                cwd: "/work",
+                action: &action,
+                reason,
+            },
+        );
+    }
+
+    let entries: Vec<serde_json::Value> = std::fs::read_to_string(&log)
+        .expect("read audit log")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("parse audit line"))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            serde_json::json!({
+                "timestamp": "2026-01-01T00:00:00+00:00",
+                "file": "/bin/rm",
+                "argv": ["rm", "-rf", "build"],
+                "cwd": "/work",
+                "action": "run",
+                "reason": null,
+            }),
+            serde_json::json!({
+                "timestamp": "2026-01-01T00:00:00+00:00",
+                "file": "/bin/rm",
+                "argv": ["rm", "-rf", "build"],
+                "cwd": "/work",
+                "action": "deny",
+                "reason": "blocked by policy",
+            }),
+        ]
+    );
+}
+
+#[test]
+fn audit_log_failures_are_ignored() {
+    let dir = tempfile::tempdir().expect("tempdir");
+
+    // The log path is a directory, so opening it for append fails.
+    append_audit_log_entry(
+        dir.path(),
+        &ExecBridgeAuditEntry {
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            file: "/bin/ls",
+            argv: &["ls".to_string()],
+            cwd: "/work",
+            action: &WrapperExecAction::Run,
+            reason: None,
+        },
+    );
+}
+
+#[test]
+fn parse_exec_response_checks_the_request_id() {
+    assert_eq!(
+        parse_exec_response(
+            "{\"type\":\"exec_response\",\"request_id\":\"req-5\",\"action\":\"run\",\"reason\":null}\n",
+            "req-5",
+        )
+        .expect("matching response"),
+        (WrapperExecAction::Run, None)
+    );
+
+    let empty = parse_exec_response(
+        r#"{"type":"exec_response","request_id":" ","action":"run","reason":null}"#,
+        "req-5",
+    )
+    .expect_err("empty request id");
+    assert_eq!(
+        empty.to_string(),
+        "wrapper server returned empty request_id"
+    );
+
+    let mismatch = parse_exec_response(
+        r#"{"type":"exec_response","request_id":"req-6","action":"run","reason":null}"#,
+        "req-5",
+    )
+    .expect_err("mismatched request id");
+    assert_eq!(
+        mismatch.to_string(),
+        "wrapper response request_id mismatch: expected req-5, got req-6"
+    );
+}
+
+#[test]
+fn parse_exec_response_reports_malformed_responses() {
+    let err = parse_exec_response("", "req-5").expect_err("empty response");
+    assert_eq!(err.to_string(), "parse wrapper response: <empty response>");
+
+    let garbage = "<html>".repeat(200);
+    let err = parse_exec_response(&garbage, "req-5").expect_err("garbage response");
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "parse wrapper response: {}... (truncated)",
+            &garbage[..MAX_ERROR_RESPONSE_CHARS]
+        )
+    );
+}
+
+#[test]
+fn truncate_for_error_counts_characters() {
+    let short = "é".repeat(MAX_ERROR_RESPONSE_CHARS);
+    assert_eq!(truncate_for_error(&short), short);
+
+    let long = "é".repeat(MAX_ERROR_RESPONSE_CHARS + 1);
+    assert_eq!(truncate_for_error(&long), format!("{short}... (truncated)"));
+}
+
+#[test]
+fn fish_quote_escapes_only_backslashes_and_quotes() {
+    assert_eq!(fish_quote("plain"), "'plain'");
+    assert_eq!(fish_quote("it's"), r"'it\'s'");
+    assert_eq!(fish_quote(r"C:\dir"), r"'C:\\dir'");
+    assert_eq!(fish_quote("$HOME (x) *; \"y\""), "'$HOME (x) *; \"y\"'");
+    assert_eq!(fish_quote(""), "''");
+}
+
+#[test]
+fn fish_init_command_sources_the_quoted_script() {
+    assert_eq!(
+        fish_exec_bridge_init_command(Path::new("/tmp/codex dir/it's.fish")),
+        r"source '/tmp/codex dir/it\'s.fish'"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fish_wrapper_leaves_approved_commands_to_the_shell() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let server = start_server(EscalationDecision::Run);
+
+    let output = wrapper_child_command("fish", "/bin/sh", &["sh", "-c", "touch ran"])
+        .current_dir(dir.path())
+        .env(ZSH_EXEC_BRIDGE_WRAPPER_SOCKET_ENV_VAR, server.socket_path())
+        .output()
+        .expect("run wrapper child");
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!dir.path().join("ran").exists());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn approved_command_reads_stdin_without_the_wrapper_env() {
+    let server = start_server(EscalationDecision::Run);
+
+    let mut child = wrapper_child_command(
+        "zsh",
+        "/bin/sh",
+        &[
+            "sh",
+            "-c",
+            "cat; echo; echo \"mode=${CODEX_ZSH_EXEC_WRAPPER_MODE:-unset} wrapper=${EXEC_WRAPPER:-unset}\"",
+        ],
+    )
+    .env(ZSH_EXEC_WRAPPER_MODE_ENV_VAR, "1")
+    .env(EXEC_WRAPPER_ENV_VAR, "/usr/local/bin/codex")
+    .env(ZSH_EXEC_BRIDGE_WRAPPER_SOCKET_ENV_VAR, server.socket_path())
+    .stdin(std::process::Stdio::piped())
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .expect("spawn wrapper child");
+    {
+        let mut stdin = child.stdin.take().expect("child stdin");
+        stdin
+            .write_all(b"hello from stdin")
+            .expect("write child stdin");
+    }
+    let output = child.wait_with_output().expect("wait for wrapper child");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.ends_with("hello from stdin\nmode=unset wrapper=unset\n"),
+        "unexpected stdout: {stdout}"
+    );
+}
+
+#[test]
+fn allowlist_matches_exact_paths_and_whole_path_patterns() {
+    let allowlist = "\
+# safe read-only tools
+/usr/bin/cat
+
+ls
+re:/opt/tools/[a-z]+
+re:git
+re:[unclosed
+";
+
+    assert!(allowlist_matches(allowlist, "/usr/bin/cat"));
+    assert!(allowlist_matches(allowlist, "/opt/tools/lint"));
+    assert!(allowlist_matches(allowlist, "git"));
+
+    assert!(!allowlist_matches(allowlist, "/bin/cat"));
+    assert!(!allowlist_matches(allowlist, "/opt/tools/lint2"));
+    assert!(!allowlist_matches(allowlist, "# safe read-only tools"));
+    assert!(!allowlist_matches(allowlist, "[unclosed"));
+}
+
+#[test]
+fn allowlist_does_not_match_by_file_name() {
+    let allowlist = "/bin/ls\nls\n";
+
+    assert!(allowlist_matches(allowlist, "/bin/ls"));
+    assert!(!allowlist_matches(allowlist, "ls"));
+    assert!(!allowlist_matches(allowlist, "./ls"));
+    assert!(!allowlist_matches(allowlist, "/tmp/x/ls"));
+}
+
+#[test]
+fn allowlist_patterns_must_match_the_whole_path() {
+    let allowlist = "re:git\nre:/usr/bin/git\n";
+
+    assert!(allowlist_matches(allowlist, "/usr/bin/git"));
+    assert!(!allowlist_matches(allowlist, "/tmp/evilgit-helper"));
+    assert!(!allowlist_matches(allowlist, "/usr/bin/git-evil"));
+    assert!(!allowlist_matches(allowlist, "/tmp/usr/bin/git"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn allowlisted_commands_run_without_asking_the_server() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let allowlist = dir.path().join("allowlist");
+    std::fs::write(&allowlist, "/bin/sh\n").expect("write allowlist");
+    let server = start_server(EscalationDecision::Deny {
+        reason: Some("blocked by policy".to_string()),
+    });
+
+    let output = wrapper_child_command("zsh", "/bin/sh", &["sh", "-c", "touch ran"])
+        .current_dir(dir.path())
+        .env(ZSH_EXEC_BRIDGE_WRAPPER_SOCKET_ENV_VAR, server.socket_path())
+        .env(EXEC_BRIDGE_ALLOWLIST_ENV_VAR, &allowlist)
+        .output()
+        .expect("run wrapper child");
+
+    assert!(output.status.success());
+    assert!(dir.path().join("ran").exists());
+}
@@ -259,14 +259,20 @@ async fn agent_teams_tool_flow_persists_and_cleans_up() -> Result<()> {
     assert_eq!(spawn_output["team_id"].as_str(), Some(team_id));
     assert_eq!(spawn_output["members"].as_array().map(Vec::len), Some(2));
 
+    let lead_thread_id = test.session_configured.session_id.to_string();
     let team_config_path = test
         .codex_home_path()
         .join("teams")
+        .join(&lead_thread_id)
         .join(team_id)
         .join("config.json");
     assert_eq!(team_config_path.exists(), true);
 
-    let team_tasks_dir = test.codex_home_path().join("tasks").join(team_id);
+    let team_tasks_dir = test
+        .codex_home_path()
+        .join("tasks")
+        .join(&lead_thread_id)
+        .join(team_id);
     assert_eq!(team_tasks_dir.exists(), false);
 
     let cleanup_call_id = "call-cleanup-team";
@@ -316,12 +322,18 @@ async fn agent_teams_message_and_broadcast_flow() -> Result<()> {
     let test = builder.build(&server).await?;
 
     let team_id = "e2e-team-message";
+    let lead_thread_id = test.session_configured.session_id.to_string();
     let team_config_path = test
         .codex_home_path()
         .join("teams")
+        .join(&lead_thread_id)
         .join(team_id)
         .join("config.json");
-    let team_tasks_dir = test.codex_home_path().join("tasks").join(team_id);
+    let team_tasks_dir = test
+        .codex_home_path()
+        .join("tasks")
+        .join(&lead_thread_id)
+        .join(team_id);
 
     let spawn_call_id = "call-msg-spawn-team";
     let spawn_args = json!({
@@ -1,10 +1,14 @@
 mod registry;
 mod types;
 
+pub use registry::CallbackHook;
 pub use registry::CommandHookConfig;
 pub use registry::CommandHooksConfig;
+pub use registry::HOOK_WEBHOOK_SECRET_HEADER;
+pub use registry::HookCallback;
 pub use registry::HookHandlerType;
 pub use registry::HookMatcherConfig;
+pub use registry::HookOutputMode;
 pub use registry::Hooks;
 pub use registry::HooksConfig;
 pub use registry::NonCommandHookExecutor;
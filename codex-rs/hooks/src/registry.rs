@@ -11,10 +11,12 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use regex::Regex;
+use serde::Deserialize;
 use serde_json::Value;
 use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 use tokio::sync::Mutex;
+use tokio::sync::Semaphore;
 use tokio::sync::mpsc;
 use tokio::task::JoinSet;
 
@@ -30,17 +32,49 @@ pub struct HookMatcherConfig {
     pub tool_name: Option<String>,
     pub tool_name_regex: Option<String>,
     pub prompt_regex: Option<String>,
+    /// Regex matched against the shell command in a tool event's input.
+    pub command_regex: Option<String>,
+    /// Regex matched against the file path in a tool event's input.
+    pub path_regex: Option<String>,
     pub matcher: Option<String>,
 }
 
+impl HookMatcherConfig {
+    /// Compiles every pattern, so config loaders can flag invalid matchers when the config is
+    /// loaded rather than only when the hook is first dispatched.
+    pub fn validate(&self) -> Result<(), String> {
+        CompiledMatcher::compile(self).map(|_| ())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum HookHandlerType {
     #[default]
     Command,
     Prompt,
     Agent,
+    /// POSTs the hook payload as JSON to `url` and reads the decision JSON from the reply.
+    HttpWebhook,
+}
+
+/// How a hook's `stdout` (or webhook response body) is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookOutputMode {
+    /// JSON output (the whole output or its first JSON line) is applied; any other output is
+    /// ignored, so hooks that print log lines do not leak them into the model context.
+    #[default]
+    Ignore,
+    /// Like [`HookOutputMode::Ignore`], but any other non-empty output becomes a single
+    /// additional-context entry.
+    Lenient,
+    /// Output must be empty or a single JSON object; anything else is reported as a hook error.
+    Strict,
 }
 
+/// Header carrying [`CommandHookConfig::secret`] on webhook requests.
+pub const HOOK_WEBHOOK_SECRET_HEADER: &str = "X-Codex-Hook-Secret";
+
 pub trait NonCommandHookExecutor: Send + Sync {
     fn execute_prompt(
         self: Arc<Self>,
@@ -59,6 +93,31 @@ pub trait NonCommandHookExecutor: Send + Sync {
     ) -> Pin<Box<dyn Future<Output = HookResult> + Send>>;
 }
 
+/// In-process hook handler supplied by an application embedding Codex.
+pub type HookCallback = Box<dyn Fn(HookPayload) -> HookResult + Send + Sync>;
+
+/// A named [`HookCallback`]. Unlike configured hooks it runs for every event, on the blocking
+/// thread pool, and its result is applied exactly like a synchronous command hook's; filter on
+/// `payload.hook_event` inside the callback.
+#[derive(Clone)]
+pub struct CallbackHook {
+    name: String,
+    callback: Arc<dyn Fn(HookPayload) -> HookResult + Send + Sync>,
+}
+
+impl CallbackHook {
+    pub fn new(name: impl Into<String>, callback: HookCallback) -> Self {
+        Self {
+            name: name.into(),
+            callback: Arc::from(callback),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct CommandHookConfig {
     pub name: Option<String>,
@@ -66,12 +125,22 @@ pub struct CommandHookConfig {
     pub command: Vec<String>,
     pub prompt: Option<String>,
     pub model: Option<String>,
+    /// Endpoint for [`HookHandlerType::HttpWebhook`] hooks.
+    pub url: Option<String>,
+    /// Shared secret sent in [`HOOK_WEBHOOK_SECRET_HEADER`] by webhook hooks.
+    pub secret: Option<String>,
     pub async_: bool,
     /// Timeout in seconds.
     pub timeout: Option<u64>,
+    /// Timeout in milliseconds; takes precedence over `timeout` when both are set.
+    pub timeout_ms: Option<u64>,
     pub status_message: Option<String>,
     pub once: bool,
     pub matcher: HookMatcherConfig,
+    pub output_mode: HookOutputMode,
+    /// Set by config loaders for an invalid entry; the hook reports this error when dispatched
+    /// instead of running.
+    pub config_error: Option<String>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -91,8 +160,11 @@ pub struct CommandHooksConfig {
     pub subagent_start: Vec<CommandHookConfig>,
     pub subagent_stop: Vec<CommandHookConfig>,
     pub pre_compact: Vec<CommandHookConfig>,
+    pub compact: Vec<CommandHookConfig>,
     pub worktree_create: Vec<CommandHookConfig>,
     pub worktree_remove: Vec<CommandHookConfig>,
+    /// Maximum number of hooks run at once for a single event; unbounded when unset.
+    pub max_concurrency: Option<usize>,
 }
 
 #[derive(Default, Clone)]
@@ -114,6 +186,10 @@ enum HookHandler {
         prompt: Arc<String>,
         model: Option<String>,
     },
+    HttpWebhook {
+        url: Arc<String>,
+        secret: Option<Arc<String>>,
+    },
 }
 
 #[derive(Clone)]
@@ -124,6 +200,7 @@ struct Hook {
     timeout: Option<Duration>,
     matcher: CompiledMatcher,
     once: bool,
+    output_mode: HookOutputMode,
     config_error: Option<String>,
 }
 
@@ -144,12 +221,18 @@ pub struct Hooks {
     subagent_start: Vec<Hook>,
     subagent_stop: Vec<Hook>,
     pre_compact: Vec<Hook>,
+    compact: Vec<Hook>,
     worktree_create: Vec<Hook>,
     worktree_remove: Vec<Hook>,
     ran_once: Arc<Mutex<HashSet<String>>>,
     async_results_tx: Option<mpsc::UnboundedSender<HookResponse>>,
     non_command_executor: Option<Arc<dyn NonCommandHookExecutor>>,
+    callback_hooks: Vec<CallbackHook>,
     scoped_hooks: Arc<std::sync::Mutex<HashMap<String, ScopedHooks>>>,
+    /// Per-dispatch cap on concurrently running hooks; see [`CommandHooksConfig::max_concurrency`].
+    max_concurrency: Option<usize>,
+    /// Shared by every webhook hook so dispatches reuse connections and TLS sessions.
+    http_client: reqwest::Client,
 }
 
 #[derive(Clone, Default)]
@@ -169,6 +252,7 @@ struct ScopedHooks {
     subagent_start: Vec<Hook>,
     subagent_stop: Vec<Hook>,
     pre_compact: Vec<Hook>,
+    compact: Vec<Hook>,
     worktree_create: Vec<Hook>,
     worktree_remove: Vec<Hook>,
 }
@@ -191,6 +275,7 @@ impl ScopedHooks {
             HookEvent::SubagentStart { .. } => &self.subagent_start,
             HookEvent::SubagentStop { .. } => &self.subagent_stop,
             HookEvent::PreCompact { .. } => &self.pre_compact,
+            HookEvent::Compact { .. } => &self.compact,
             HookEvent::WorktreeCreate { .. } => &self.worktree_create,
             HookEvent::WorktreeRemove { .. } => &self.worktree_remove,
         }
@@ -202,6 +287,8 @@ struct CompiledMatcher {
     tool_name: Option<String>,
     tool_name_regex: Option<Regex>,
     prompt_regex: Option<Regex>,
+    command_regex: Option<Regex>,
+    path_regex: Option<Regex>,
     matcher_regex: Option<Regex>,
 }
 
@@ -211,6 +298,9 @@ impl CompiledMatcher {
         let tool_name_regex =
             compile_optional_regex(matcher.tool_name_regex.as_deref(), "tool_name_regex")?;
         let prompt_regex = compile_optional_regex(matcher.prompt_regex.as_deref(), "prompt_regex")?;
+        let command_regex =
+            compile_optional_regex(matcher.command_regex.as_deref(), "command_regex")?;
+        let path_regex = compile_optional_regex(matcher.path_regex.as_deref(), "path_regex")?;
         let tool_name = matcher
             .tool_name
             .as_ref()
@@ -222,6 +312,8 @@ impl CompiledMatcher {
             tool_name,
             tool_name_regex,
             prompt_regex,
+            command_regex,
+            path_regex,
             matcher_regex,
         })
     }
@@ -260,6 +352,24 @@ impl CompiledMatcher {
             }
         }
 
+        if let Some(command_regex) = self.command_regex.as_ref() {
+            let Some(command) = event.command_for_matcher() else {
+                return false;
+            };
+            if !command_regex.is_match(&command) {
+                return false;
+            }
+        }
+
+        if let Some(path_regex) = self.path_regex.as_ref() {
+            let Some(path) = event.path_for_matcher() else {
+                return false;
+            };
+            if !path_regex.is_match(path) {
+                return false;
+            }
+        }
+
         true
     }
 }
@@ -306,6 +416,7 @@ enum HookEventKey {
     SubagentStart,
     SubagentStop,
     PreCompact,
+    Compact,
     WorktreeCreate,
     WorktreeRemove,
 }
@@ -328,6 +439,7 @@ impl HookEventKey {
             HookEventKey::SubagentStart => "subagent_start",
             HookEventKey::SubagentStop => "subagent_stop",
             HookEventKey::PreCompact => "pre_compact",
+            HookEventKey::Compact => "compact",
             HookEventKey::WorktreeCreate => "worktree_create",
             HookEventKey::WorktreeRemove => "worktree_remove",
         }
@@ -344,6 +456,7 @@ impl HookEventKey {
                 | HookEventKey::TaskCompleted
                 | HookEventKey::ConfigChange
                 | HookEventKey::SubagentStop
+                | HookEventKey::PreCompact
         )
     }
 
@@ -358,6 +471,7 @@ impl HookEventKey {
                 | HookEventKey::Stop
                 | HookEventKey::SubagentStop
                 | HookEventKey::ConfigChange
+                | HookEventKey::PreCompact
         )
     }
 
@@ -387,6 +501,7 @@ impl HookEventKey {
                 | HookEventKey::Notification
                 | HookEventKey::SubagentStart
                 | HookEventKey::PreCompact
+                | HookEventKey::Compact
                 | HookEventKey::SubagentStop
                 | HookEventKey::ConfigChange
         )
@@ -436,6 +551,7 @@ impl Hooks {
             subagent_start: build_hooks(command_hooks.subagent_start, HookEventKey::SubagentStart),
             subagent_stop: build_hooks(command_hooks.subagent_stop, HookEventKey::SubagentStop),
             pre_compact: build_hooks(command_hooks.pre_compact, HookEventKey::PreCompact),
+            compact: build_hooks(command_hooks.compact, HookEventKey::Compact),
             worktree_create: build_hooks(
                 command_hooks.worktree_create,
                 HookEventKey::WorktreeCreate,
@@ -447,7 +563,10 @@ impl Hooks {
             ran_once: Arc::new(Mutex::new(HashSet::new())),
             async_results_tx: None,
             non_command_executor: None,
+            callback_hooks: Vec::new(),
             scoped_hooks: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            max_concurrency: command_hooks.max_concurrency,
+            http_client: reqwest::Client::new(),
         }
     }
 
@@ -459,6 +578,15 @@ impl Hooks {
         self.non_command_executor = Some(executor);
     }
 
+    /// Registers an in-process handler that runs after the configured hooks for every event.
+    pub fn add_callback_hook(&mut self, hook: CallbackHook) {
+        self.callback_hooks.push(hook);
+    }
+
+    pub fn callback_hooks(&self) -> &[CallbackHook] {
+        &self.callback_hooks
+    }
+
     pub fn insert_scoped_command_hooks(&self, scope_id: String, command_hooks: CommandHooksConfig) {
         let scoped = build_scoped_hooks(&scope_id, command_hooks);
         let mut guard = self
@@ -500,6 +628,7 @@ impl Hooks {
             HookEvent::SubagentStart { .. } => (HookEventKey::SubagentStart, &self.subagent_start),
             HookEvent::SubagentStop { .. } => (HookEventKey::SubagentStop, &self.subagent_stop),
             HookEvent::PreCompact { .. } => (HookEventKey::PreCompact, &self.pre_compact),
+            HookEvent::Compact { .. } => (HookEventKey::Compact, &self.compact),
             HookEvent::WorktreeCreate { .. } => {
                 (HookEventKey::WorktreeCreate, &self.worktree_create)
             }
@@ -523,6 +652,14 @@ impl Hooks {
             .collect()
     }
 
+    /// Whether dispatching `hook_event` would reach any configured, scoped, or callback hook.
+    /// Matchers are not evaluated, so a `true` may still dispatch to nothing.
+    pub fn has_hooks_for(&self, hook_event: &HookEvent) -> bool {
+        !self.callback_hooks.is_empty()
+            || !self.hooks_for_event(hook_event).1.is_empty()
+            || !self.scoped_hooks_for_event(hook_event).is_empty()
+    }
+
     pub async fn dispatch(&self, hook_payload: HookPayload) -> Vec<HookResponse> {
         let (event_key, hooks) = self.hooks_for_event(&hook_payload.hook_event);
         let scoped_hooks = self.scoped_hooks_for_event(&hook_payload.hook_event);
@@ -530,6 +667,11 @@ impl Hooks {
         let mut outcomes = Vec::with_capacity(hooks.len() + scoped_hooks.len());
         let mut hook_names_by_outcome_index = Vec::with_capacity(hooks.len() + scoped_hooks.len());
         let mut join_set = JoinSet::new();
+        // A fresh semaphore per dispatch, so concurrent dispatches of other events do not
+        // compete for the same permits.
+        let concurrency_limit = self
+            .max_concurrency
+            .map(|limit| Arc::new(Semaphore::new(limit.max(1))));
 
         for hook in hooks.iter().chain(scoped_hooks.iter()) {
             if let Some(error) = hook.config_error.as_deref() {
@@ -567,10 +709,18 @@ impl Hooks {
             let hook_name = hook.name.clone();
             let handler = hook.handler.clone();
             let timeout = hook.timeout;
+            let output_mode = hook.output_mode;
             let async_results_tx = self.async_results_tx.clone();
             let non_command_executor = self.non_command_executor.clone();
+            let concurrency_limit = concurrency_limit.clone();
+            let http_client = self.http_client.clone();
 
             join_set.spawn(async move {
+                // Held for the duration of the handler; the semaphore is never closed.
+                let _permit = match concurrency_limit.as_ref() {
+                    Some(limit) => limit.acquire().await.ok(),
+                    None => None,
+                };
                 let result = match handler {
                     HookHandler::Command { argv, async_ } => {
                         if async_ {
@@ -579,9 +729,14 @@ impl Hooks {
                                 let hook_name = hook_name.clone();
                                 let argv = Arc::clone(&argv);
                                 tokio::spawn(async move {
-                                    let mut result =
-                                        execute_command_hook(&payload, &argv, timeout, event_key)
-                                            .await;
+                                    let mut result = execute_command_hook(
+                                        &payload,
+                                        &argv,
+                                        timeout,
+                                        event_key,
+                                        output_mode,
+                                    )
+                                    .await;
                                     result.control = HookResultControl::Continue;
                                     result.permission_decision = None;
                                     result.permission_decision_reason = None;
@@ -590,7 +745,8 @@ impl Hooks {
                             }
                             HookResult::success()
                         } else {
-                            execute_command_hook(&payload, &argv, timeout, event_key).await
+                            execute_command_hook(&payload, &argv, timeout, event_key, output_mode)
+                                .await
                         }
                     }
                     HookHandler::Prompt { prompt, model } => match non_command_executor {
@@ -625,12 +781,48 @@ impl Hooks {
                             ..HookResult::success()
                         },
                     },
+                    HookHandler::HttpWebhook { url, secret } => {
+                        execute_http_webhook_hook(
+                            &http_client,
+                            &payload,
+                            url.as_str(),
+                            secret.as_deref().map(String::as_str),
+                            timeout,
+                            event_key,
+                            output_mode,
+                        )
+                        .await
+                    }
                 };
 
                 (outcome_index, HookResponse { hook_name, result })
             });
         }
 
+        for hook in &self.callback_hooks {
+            let outcome_index = outcomes.len();
+            outcomes.push(None);
+            hook_names_by_outcome_index.push(Some(hook.name.clone()));
+
+            let payload = hook_payload.clone();
+            let hook_name = hook.name.clone();
+            let callback = Arc::clone(&hook.callback);
+            let concurrency_limit = concurrency_limit.clone();
+            join_set.spawn(async move {
+                let _permit = match concurrency_limit.as_ref() {
+                    Some(limit) => limit.acquire().await.ok(),
+                    None => None,
+                };
+                let result = tokio::task::spawn_blocking(move || callback(payload))
+                    .await
+                    .unwrap_or_else(|err| HookResult {
+                        error: Some(format!("hook callback failed: {err}")),
+                        ..HookResult::success()
+                    });
+                (outcome_index, HookResponse { hook_name, result })
+            });
+        }
+
         while let Some(joined) = join_set.join_next().await {
             if let Ok((index, response)) = joined
                 && let Some(slot) = outcomes.get_mut(index)
@@ -681,7 +873,10 @@ fn hook_from_config(
         _ => base_once_key,
     };
     let name = config.name.unwrap_or_else(|| once_key.clone());
-    let timeout = config.timeout.map(Duration::from_secs);
+    let timeout = config
+        .timeout_ms
+        .map(Duration::from_millis)
+        .or_else(|| config.timeout.map(Duration::from_secs));
     let (matcher, config_error) = if event_key.supports_matchers() {
         match CompiledMatcher::compile(&config.matcher) {
             Ok(matcher) => (matcher, None),
@@ -775,9 +970,23 @@ fn hook_from_config(
                 )
             }
         }
+        HookHandlerType::HttpWebhook => {
+            let url = config
+                .url
+                .as_deref()
+                .map(str::trim)
+                .filter(|url| !url.is_empty())?;
+            (
+                HookHandler::HttpWebhook {
+                    url: Arc::new(url.to_string()),
+                    secret: config.secret.map(Arc::new),
+                },
+                None,
+            )
+        }
     };
 
-    let config_error = config_error.or(handler_error);
+    let config_error = config.config_error.or(config_error).or(handler_error);
     let handler_identity = hook_handler_identity(event_key, &handler, timeout, once);
 
     Some(Hook {
@@ -787,6 +996,7 @@ fn hook_from_config(
         timeout,
         matcher,
         once,
+        output_mode: config.output_mode,
         config_error,
     })
 }
@@ -797,8 +1007,10 @@ fn hook_handler_identity(
     timeout: Option<Duration>,
     once: bool,
 ) -> String {
-    let timeout_secs = timeout.map(|duration| duration.as_secs());
-    let timeout_key = timeout_secs.map_or_else(|| "none".to_string(), |secs| secs.to_string());
+    let timeout_key = timeout.map_or_else(
+        || "none".to_string(),
+        |duration| format!("{}ms", duration.as_millis()),
+    );
 
     match handler {
         HookHandler::Command { argv, async_ } => {
@@ -825,6 +1037,14 @@ fn hook_handler_identity(
                 event_key.as_str(),
             )
         }
+        HookHandler::HttpWebhook { url, secret } => {
+            let url_json = serde_json::to_string(url.as_ref()).unwrap_or_default();
+            let has_secret = secret.is_some();
+            format!(
+                "{}|http_webhook|timeout={timeout_key}|once={once}|secret={has_secret}|url={url_json}",
+                event_key.as_str(),
+            )
+        }
     }
 }
 
@@ -901,6 +1121,7 @@ fn build_scoped_hooks(scope_id: &str, command_hooks: CommandHooksConfig) -> Scop
             command_hooks.pre_compact,
             HookEventKey::PreCompact,
         ),
+        compact: build_hooks_with_prefix(scope_id, command_hooks.compact, HookEventKey::Compact),
         worktree_create: build_hooks_with_prefix(
             scope_id,
             command_hooks.worktree_create,
@@ -931,6 +1152,7 @@ async fn execute_command_hook(
     argv: &Arc<Vec<String>>,
     timeout: Option<Duration>,
     event_key: HookEventKey,
+    output_mode: HookOutputMode,
 ) -> HookResult {
     let mut command = match command_from_argv(argv.as_ref()) {
         Some(command) => command,
@@ -966,7 +1188,7 @@ async fn execute_command_hook(
         Err(error) => return result_with_error(error),
     };
 
-    result_from_output(event_key, &output)
+    result_from_output(event_key, output_mode, &output)
 }
 
 async fn wait_for_output(
@@ -981,7 +1203,82 @@ async fn wait_for_output(
     }
 }
 
-fn result_from_output(event_key: HookEventKey, output: &Output) -> HookResult {
+async fn execute_http_webhook_hook(
+    client: &reqwest::Client,
+    payload: &HookPayload,
+    url: &str,
+    secret: Option<&str>,
+    timeout: Option<Duration>,
+    event_key: HookEventKey,
+    output_mode: HookOutputMode,
+) -> HookResult {
+    let payload_json = match serde_json::to_string(payload) {
+        Ok(payload_json) => payload_json,
+        Err(error) => return result_with_error(io::Error::other(error)),
+    };
+
+    let mut request = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(payload_json);
+    if let Some(secret) = secret {
+        request = request.header(HOOK_WEBHOOK_SECRET_HEADER, secret);
+    }
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(error) if error.is_timeout() => {
+            return result_with_error(io::Error::new(
+                ErrorKind::TimedOut,
+                "hook webhook timed out",
+            ));
+        }
+        Err(error) => {
+            return HookResult {
+                error: Some(format!("hook webhook request failed: {error}")),
+                ..HookResult::success()
+            };
+        }
+    };
+    let status = response.status();
+    let body = match response.bytes().await {
+        Ok(body) => body,
+        Err(error) => {
+            return HookResult {
+                error: Some(format!("failed to read hook webhook response: {error}")),
+                ..HookResult::success()
+            };
+        }
+    };
+
+    if !status.is_success() {
+        let body_preview = preview_bytes(&body);
+        let message = if body_preview.is_empty() {
+            format!("hook webhook returned {status}")
+        } else {
+            format!("hook webhook returned {status}: {body_preview}")
+        };
+        return HookResult {
+            error: Some(message),
+            ..HookResult::success()
+        };
+    }
+
+    if matches!(event_key, HookEventKey::WorktreeCreate) {
+        return result_from_worktree_create_stdout(&body);
+    }
+
+    result_from_stdout(event_key, output_mode, &body)
+}
+
+fn result_from_output(
+    event_key: HookEventKey,
+    output_mode: HookOutputMode,
+    output: &Output,
+) -> HookResult {
     let Some(code) = output.status.code() else {
         return HookResult {
             error: Some("hook command terminated by signal".to_string()),
@@ -1031,11 +1328,40 @@ fn result_from_output(event_key: HookEventKey, output: &Output) -> HookResult {
         return result_from_worktree_create_stdout(&output.stdout);
     }
 
-    let Some(stdout_json) = parse_stdout_json(&output.stdout) else {
-        return HookResult::success();
-    };
+    result_from_stdout(event_key, output_mode, &output.stdout)
+}
 
-    apply_stdout_json(event_key, stdout_json)
+fn result_from_stdout(
+    event_key: HookEventKey,
+    output_mode: HookOutputMode,
+    stdout: &[u8],
+) -> HookResult {
+    match output_mode {
+        HookOutputMode::Ignore => parse_stdout_json(stdout)
+            .map_or_else(HookResult::success, |stdout_json| {
+                apply_stdout_json(event_key, stdout_json)
+            }),
+        HookOutputMode::Lenient => {
+            if let Some(stdout_json) = parse_stdout_json(stdout) {
+                return apply_stdout_json(event_key, stdout_json);
+            }
+            let text = String::from_utf8_lossy(stdout);
+            let text = text.trim();
+            let mut result = HookResult::success();
+            if !text.is_empty() {
+                result.additional_context.push(text.to_string());
+            }
+            result
+        }
+        HookOutputMode::Strict => match parse_strict_stdout_json(stdout) {
+            Ok(Some(stdout_json)) => apply_stdout_json(event_key, stdout_json),
+            Ok(None) => HookResult::success(),
+            Err(error) => HookResult {
+                error: Some(error),
+                ..HookResult::success()
+            },
+        },
+    }
 }
 
 fn result_from_worktree_create_stdout(stdout: &[u8]) -> HookResult {
@@ -1048,6 +1374,26 @@ fn result_from_worktree_create_stdout(stdout: &[u8]) -> HookResult {
             };
         }
     };
+    if let Some(Value::Object(obj)) = parse_stdout_json(stdout) {
+        let Some(path_str) = obj
+            .get("worktreePath")
+            .or_else(|| obj.get("worktree_path"))
+            .and_then(Value::as_str)
+        else {
+            return HookResult {
+                error: Some("worktree_create hook JSON output is missing worktreePath".to_string()),
+                ..HookResult::success()
+            };
+        };
+        let mut result = result_from_worktree_path(path_str);
+        if result.error.is_none()
+            && let Some(env) = obj.get("env")
+        {
+            result.env = env_from_json(env);
+        }
+        return result;
+    }
+
     let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
     let Some(path_str) = lines.next() else {
         return HookResult {
@@ -1062,6 +1408,10 @@ fn result_from_worktree_create_stdout(stdout: &[u8]) -> HookResult {
         };
     }
 
+    result_from_worktree_path(path_str)
+}
+
+fn result_from_worktree_path(path_str: &str) -> HookResult {
     let path = PathBuf::from(path_str);
     if !path.is_absolute() {
         return HookResult {
@@ -1096,13 +1446,10 @@ fn apply_stdout_json(event_key: HookEventKey, stdout_json: Value) -> HookResult
     if let Some(additional_context) = obj
         .get("additionalContext")
         .or_else(|| obj.get("additional_context"))
-        .and_then(Value::as_str)
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
     {
         result
             .additional_context
-            .push(additional_context.to_string());
+            .extend(additional_context_entries(additional_context));
     }
 
     let hook_specific = obj
@@ -1110,17 +1457,28 @@ fn apply_stdout_json(event_key: HookEventKey, stdout_json: Value) -> HookResult
         .or_else(|| obj.get("hook_specific_output"))
         .and_then(Value::as_object);
 
-    if let Some(hook_specific) = hook_specific
-        && let Some(additional_context) = hook_specific
+    if let Some(additional_context) = hook_specific.and_then(|hook_specific| {
+        hook_specific
             .get("additionalContext")
             .or_else(|| hook_specific.get("additional_context"))
-            .and_then(Value::as_str)
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-    {
+    }) {
         result
             .additional_context
-            .push(additional_context.to_string());
+            .extend(additional_context_entries(additional_context));
+    }
+
+    result.error = obj
+        .get("error")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string);
+
+    if let Some(env) = obj
+        .get("env")
+        .or_else(|| hook_specific.and_then(|hook_specific| hook_specific.get("env")))
+    {
+        result.env = env_from_json(env);
     }
 
     result.updated_input = obj
@@ -1153,10 +1511,54 @@ fn apply_stdout_json(event_key: HookEventKey, stdout_json: Value) -> HookResult
         return result;
     }
 
+    if obj
+        .get("control")
+        .and_then(Value::as_str)
+        .is_some_and(|control| control.trim().eq_ignore_ascii_case("block"))
+    {
+        let reason = decision_reason(obj, hook_specific)
+            .unwrap_or_else(|| "hook blocked operation".to_string());
+        result.control = HookResultControl::Block { reason };
+        return result;
+    }
+
     apply_decisions(event_key, obj, hook_specific, &mut result);
     result
 }
 
+/// `additionalContext` is either a string or a list whose entries are strings or
+/// `{"text": "..."}` objects. Blank entries are dropped.
+fn additional_context_entries(value: &Value) -> Vec<String> {
+    let entries = match value {
+        Value::Array(entries) => entries.iter().collect::<Vec<_>>(),
+        value => vec![value],
+    };
+    entries
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Value::String(text) => Some(text.as_str()),
+            Value::Object(entry) => entry.get("text").and_then(Value::as_str),
+            _ => None,
+        })
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn env_from_json(value: &Value) -> HashMap<String, String> {
+    value
+        .as_object()
+        .map(|env| {
+            env.iter()
+                .filter_map(|(key, value)| {
+                    value.as_str().map(|value| (key.clone(), value.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 fn apply_decisions(
     event_key: HookEventKey,
     obj: &serde_json::Map<String, Value>,
@@ -1373,6 +1775,26 @@ fn parse_stdout_json(stdout: &[u8]) -> Option<Value> {
     None
 }
 
+fn parse_strict_stdout_json(stdout: &[u8]) -> Result<Option<Value>, String> {
+    let text = String::from_utf8_lossy(stdout);
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+
+    match serde_json::from_str::<Value>(trimmed) {
+        Ok(value @ Value::Object(_)) => Ok(Some(value)),
+        Ok(_) => Err(format!(
+            "hook output must be a JSON object (output_mode = \"strict\"): {}",
+            preview_bytes(stdout)
+        )),
+        Err(error) => Err(format!(
+            "hook output is not valid JSON (output_mode = \"strict\"): {error}: {}",
+            preview_bytes(stdout)
+        )),
+    }
+}
+
 fn result_with_error(error: io::Error) -> HookResult {
     HookResult {
         error: Some(error.to_string()),
@@ -1469,6 +1891,99 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn callback_hook_receives_payload_and_can_block() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen_by_callback = Arc::clone(&seen);
+        let mut hooks = Hooks::default();
+        hooks.add_callback_hook(CallbackHook::new(
+            "gui",
+            Box::new(move |payload: HookPayload| {
+                let HookEvent::WorktreeCreate { name } = payload.hook_event else {
+                    return HookResult::success();
+                };
+                seen_by_callback
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .push(name.clone());
+                HookResult {
+                    control: HookResultControl::Block {
+                        reason: format!("{name} is managed by the app"),
+                    },
+                    ..HookResult::success()
+                }
+            }),
+        ));
+
+        let outcomes = hooks
+            .dispatch(payload(
+                dir.path(),
+                HookEvent::WorktreeCreate {
+                    name: "wt-1".to_string(),
+                },
+            ))
+            .await;
+
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].hook_name, "gui");
+        assert_eq!(
+            outcomes[0].result.control,
+            HookResultControl::Block {
+                reason: "wt-1 is managed by the app".to_string()
+            }
+        );
+        assert_eq!(
+            *seen
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+            vec!["wt-1".to_string()]
+        );
+    }
+
+    #[test]
+    fn has_hooks_for_reports_configured_scoped_and_callback_hooks() {
+        let worktree_create = HookEvent::WorktreeCreate {
+            name: "wt-1".to_string(),
+        };
+        let worktree_remove = HookEvent::WorktreeRemove {
+            worktree_path: PathBuf::from("/tmp/wt-1"),
+        };
+        let hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                worktree_create: vec![CommandHookConfig {
+                    command: echo_command(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        });
+        assert!(hooks.has_hooks_for(&worktree_create));
+        assert!(!hooks.has_hooks_for(&worktree_remove));
+
+        hooks.insert_scoped_command_hooks(
+            "scope".to_string(),
+            CommandHooksConfig {
+                worktree_remove: vec![CommandHookConfig {
+                    command: echo_command(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+        assert!(hooks.has_hooks_for(&worktree_remove));
+        hooks.remove_scoped_hooks("scope");
+        assert!(!hooks.has_hooks_for(&worktree_remove));
+
+        let mut hooks = Hooks::default();
+        assert!(!hooks.has_hooks_for(&worktree_create));
+        hooks.add_callback_hook(CallbackHook::new(
+            "gui",
+            Box::new(|_payload: HookPayload| HookResult::success()),
+        ));
+        assert!(hooks.has_hooks_for(&worktree_create));
+    }
+
     #[tokio::test]
     async fn exit_2_blocks_for_blockable_event() {
         let dir = tempfile::tempdir().expect("tempdir");
@@ -1832,6 +2347,51 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn exit_2_blocks_pre_compact() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                pre_compact: vec![CommandHookConfig {
+                    command: exit_with_stderr_command(2, "archive first"),
+                    matcher: HookMatcherConfig {
+                        matcher: Some("^auto_compact$".to_string()),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        });
+
+        let outcomes = hooks
+            .dispatch(payload(
+                dir.path(),
+                HookEvent::PreCompact {
+                    trigger: "auto_compact".to_string(),
+                    custom_instructions: None,
+                },
+            ))
+            .await;
+
+        assert_eq!(outcomes.len(), 1);
+        assert!(matches!(
+            &outcomes[0].result.control,
+            HookResultControl::Block { reason } if reason == "archive first"
+        ));
+
+        let manual_outcomes = hooks
+            .dispatch(payload(
+                dir.path(),
+                HookEvent::PreCompact {
+                    trigger: "manual_compact".to_string(),
+                    custom_instructions: None,
+                },
+            ))
+            .await;
+        assert!(manual_outcomes.is_empty());
+    }
+
     #[tokio::test]
     async fn exit_2_denies_permission_request() {
         let dir = tempfile::tempdir().expect("tempdir");
@@ -2158,6 +2718,170 @@ mod tests {
         assert_eq!(outcomes.len(), 1);
     }
 
+    #[tokio::test]
+    async fn matcher_command_and_path_regex_filter_tool_input() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                pre_tool_use: vec![
+                    CommandHookConfig {
+                        name: Some("git-push".to_string()),
+                        command: echo_command(),
+                        matcher: HookMatcherConfig {
+                            command_regex: Some(r"^git\s+push".to_string()),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    CommandHookConfig {
+                        name: Some("src-files".to_string()),
+                        command: echo_command(),
+                        matcher: HookMatcherConfig {
+                            path_regex: Some("^src/".to_string()),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        });
+
+        let push = hooks
+            .dispatch(payload(
+                dir.path(),
+                HookEvent::PreToolUse {
+                    tool_name: "shell".to_string(),
+                    tool_input: json!({"command":["git","push","origin"]}),
+                    tool_use_id: "call-1".to_string(),
+                },
+            ))
+            .await;
+        let read = hooks
+            .dispatch(payload(
+                dir.path(),
+                HookEvent::PreToolUse {
+                    tool_name: "read_file".to_string(),
+                    tool_input: json!({"file_path":"src/lib.rs"}),
+                    tool_use_id: "call-2".to_string(),
+                },
+            ))
+            .await;
+        let status = hooks
+            .dispatch(payload(
+                dir.path(),
+                HookEvent::PreToolUse {
+                    tool_name: "shell".to_string(),
+                    tool_input: json!({"command":"git status"}),
+                    tool_use_id: "call-3".to_string(),
+                },
+            ))
+            .await;
+
+        assert_eq!(
+            push.iter()
+                .map(|o| o.hook_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["git-push"]
+        );
+        assert_eq!(
+            read.iter()
+                .map(|o| o.hook_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["src-files"]
+        );
+        assert_eq!(status.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn invalid_command_regex_surfaces_error_at_dispatch() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                pre_tool_use: vec![CommandHookConfig {
+                    command: echo_command(),
+                    matcher: HookMatcherConfig {
+                        command_regex: Some("(".to_string()),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        });
+
+        let outcomes = hooks
+            .dispatch(payload(
+                dir.path(),
+                HookEvent::PreToolUse {
+                    tool_name: "shell".to_string(),
+                    tool_input: json!({"command":["echo","hi"]}),
+                    tool_use_id: "call-1".to_string(),
+                },
+            ))
+            .await;
+
+        assert_eq!(outcomes.len(), 1);
+        assert!(
+            outcomes[0]
+                .result
+                .error
+                .as_deref()
+                .unwrap_or_default()
+                .contains("invalid command_regex"),
+            "expected invalid regex error, got: {:?}",
+            outcomes[0].result.error
+        );
+    }
+
+    #[tokio::test]
+    async fn config_error_is_reported_instead_of_running_the_hook() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let marker = dir.path().join("ran");
+        let hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                stop: vec![
+                    CommandHookConfig {
+                        name: Some("broken".to_string()),
+                        command: vec![
+                            "sh".to_string(),
+                            "-c".to_string(),
+                            format!("touch {}", marker.display()),
+                        ],
+                        config_error: Some("set either `url` or `command`, not both".to_string()),
+                        ..Default::default()
+                    },
+                    CommandHookConfig {
+                        name: Some("sibling".to_string()),
+                        command: echo_command(),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        });
+
+        let outcomes = hooks
+            .dispatch(payload(
+                dir.path(),
+                HookEvent::Stop {
+                    stop_hook_active: false,
+                    last_assistant_message: None,
+                },
+            ))
+            .await;
+
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].hook_name, "broken");
+        assert_eq!(
+            outcomes[0].result.error.as_deref(),
+            Some("set either `url` or `command`, not both")
+        );
+        assert_eq!(outcomes[1].hook_name, "sibling");
+        assert_eq!(outcomes[1].result.error, None);
+        assert!(!marker.exists());
+    }
+
     #[tokio::test]
     async fn user_prompt_submit_does_not_support_matchers() {
         let dir = tempfile::tempdir().expect("tempdir");
@@ -2485,6 +3209,139 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn apply_stdout_json_collects_string_env_values() {
+        let result = apply_stdout_json(
+            HookEventKey::SubagentStart,
+            json!({"env": {"FOO": "bar", "IGNORED": 1}}),
+        );
+
+        assert_eq!(
+            result.env,
+            HashMap::from([("FOO".to_string(), "bar".to_string())])
+        );
+    }
+
+    #[test]
+    fn worktree_create_stdout_accepts_json_with_env() {
+        let result = result_from_worktree_create_stdout(
+            br#"{"worktreePath":"/tmp/wt","env":{"DATABASE_URL":"postgres://wt"}}"#,
+        );
+
+        assert_eq!(result.error, None);
+        assert_eq!(result.worktree_path, Some(PathBuf::from("/tmp/wt")));
+        assert_eq!(
+            result.env,
+            HashMap::from([("DATABASE_URL".to_string(), "postgres://wt".to_string())])
+        );
+    }
+
+    #[test]
+    fn apply_stdout_json_accepts_typed_context_entries_and_error() {
+        let result = apply_stdout_json(
+            HookEventKey::SessionStart,
+            json!({
+                "additionalContext": ["first", {"text": " second "}, "  ", 3],
+                "hookSpecificOutput": {"additionalContext": [{"text": "third"}]},
+                "error": " lint cache missing ",
+            }),
+        );
+
+        assert_eq!(
+            result.additional_context,
+            vec![
+                "first".to_string(),
+                "second".to_string(),
+                "third".to_string()
+            ]
+        );
+        assert_eq!(result.error, Some("lint cache missing".to_string()));
+        assert_eq!(result.control, HookResultControl::Continue);
+    }
+
+    #[test]
+    fn apply_stdout_json_blocks_on_control_block() {
+        let result = apply_stdout_json(
+            HookEventKey::SessionStart,
+            json!({"control": "block", "reason": "not today"}),
+        );
+
+        assert_eq!(
+            result.control,
+            HookResultControl::Block {
+                reason: "not today".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn default_output_mode_ignores_plain_stdout() {
+        let result = result_from_stdout(
+            HookEventKey::UserPromptSubmit,
+            HookOutputMode::default(),
+            b"  starting lint run \n",
+        );
+        assert!(result.additional_context.is_empty());
+        assert_eq!(result.error, None);
+
+        let result = result_from_stdout(
+            HookEventKey::UserPromptSubmit,
+            HookOutputMode::default(),
+            b"starting lint run\n{\"additionalContext\": \"ctx\"}\n",
+        );
+        assert_eq!(result.additional_context, vec!["ctx".to_string()]);
+    }
+
+    #[test]
+    fn lenient_output_treats_plain_stdout_as_context() {
+        let result = result_from_stdout(
+            HookEventKey::UserPromptSubmit,
+            HookOutputMode::Lenient,
+            b"  remember the style guide \n",
+        );
+
+        assert_eq!(
+            result.additional_context,
+            vec!["remember the style guide".to_string()]
+        );
+        assert_eq!(result.error, None);
+    }
+
+    #[test]
+    fn strict_output_reports_malformed_json() {
+        let result = result_from_stdout(
+            HookEventKey::UserPromptSubmit,
+            HookOutputMode::Strict,
+            b"log line\n{\"additionalContext\": \"ctx\"}\n",
+        );
+
+        assert!(result.additional_context.is_empty());
+        let error = result.error.expect("strict mode should report an error");
+        assert!(
+            error.starts_with("hook output is not valid JSON"),
+            "{error}"
+        );
+
+        let result = result_from_stdout(
+            HookEventKey::UserPromptSubmit,
+            HookOutputMode::Strict,
+            b"[1, 2]",
+        );
+        assert!(
+            result
+                .error
+                .is_some_and(|error| error.starts_with("hook output must be a JSON object"))
+        );
+
+        let result = result_from_stdout(
+            HookEventKey::UserPromptSubmit,
+            HookOutputMode::Strict,
+            b"{\"additionalContext\": \"ctx\"}",
+        );
+        assert_eq!(result.additional_context, vec!["ctx".to_string()]);
+        assert_eq!(result.error, None);
+    }
+
     #[test]
     fn apply_stdout_json_non_object_is_ignored() {
         let result = apply_stdout_json(HookEventKey::PreToolUse, json!(["not", "an", "object"]));
@@ -2634,4 +3491,196 @@ mod tests {
             HookResultControl::Block { .. }
         ));
     }
+
+    #[tokio::test]
+    async fn http_webhook_hook_posts_payload_and_applies_decision() {
+        use wiremock::Mock;
+        use wiremock::MockServer;
+        use wiremock::ResponseTemplate;
+        use wiremock::matchers::header;
+        use wiremock::matchers::method;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header(HOOK_WEBHOOK_SECRET_HEADER, "s3cret"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"decision":"block","reason":"nope"}"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                pre_tool_use: vec![CommandHookConfig {
+                    handler_type: HookHandlerType::HttpWebhook,
+                    url: Some(server.uri()),
+                    secret: Some("s3cret".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        });
+
+        let outcomes = hooks
+            .dispatch(payload(
+                dir.path(),
+                HookEvent::PreToolUse {
+                    tool_name: "shell".to_string(),
+                    tool_input: json!({"command":["echo","hi"]}),
+                    tool_use_id: "call-1".to_string(),
+                },
+            ))
+            .await;
+
+        assert_eq!(outcomes.len(), 1);
+        assert!(matches!(
+            outcomes[0].result.control,
+            HookResultControl::Block { .. }
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn timed_out_command_hook_reports_error_without_blocking_other_hooks() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                pre_tool_use: vec![
+                    CommandHookConfig {
+                        command: vec!["sleep".to_string(), "5".to_string()],
+                        timeout_ms: Some(100),
+                        ..Default::default()
+                    },
+                    CommandHookConfig {
+                        command: echo_command(),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        });
+
+        let outcomes = tokio::time::timeout(
+            Duration::from_secs(3),
+            hooks.dispatch(payload(
+                dir.path(),
+                HookEvent::PreToolUse {
+                    tool_name: "shell".to_string(),
+                    tool_input: json!({"command":["echo","hi"]}),
+                    tool_use_id: "call-1".to_string(),
+                },
+            )),
+        )
+        .await
+        .expect("timed-out hook should not stall dispatch");
+
+        assert_eq!(outcomes.len(), 2);
+        let errors: Vec<_> = outcomes
+            .iter()
+            .filter_map(|outcome| outcome.result.error.as_deref())
+            .collect();
+        assert_eq!(errors, vec!["hook command timed out"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn max_concurrency_limits_parallel_hooks() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let log_path = dir.path().join("hooks.log");
+        let hook = |name: &str| CommandHookConfig {
+            command: vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!(
+                    "echo start >> {log}; sleep 0.2; echo end-{name} >> {log}",
+                    log = log_path.display()
+                ),
+            ],
+            ..Default::default()
+        };
+        let hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                pre_tool_use: vec![hook("a"), hook("b")],
+                max_concurrency: Some(1),
+                ..Default::default()
+            },
+        });
+
+        let outcomes = hooks
+            .dispatch(payload(
+                dir.path(),
+                HookEvent::PreToolUse {
+                    tool_name: "shell".to_string(),
+                    tool_input: json!({"command":["echo","hi"]}),
+                    tool_use_id: "call-1".to_string(),
+                },
+            ))
+            .await;
+
+        assert_eq!(outcomes.len(), 2);
+        let log = std::fs::read_to_string(&log_path).expect("read hook log");
+        let lines: Vec<_> = log.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "start");
+        assert!(lines[1].starts_with("end-"), "hooks overlapped: {lines:?}");
+        assert_eq!(lines[2], "start");
+        assert!(lines[3].starts_with("end-"), "hooks overlapped: {lines:?}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn max_concurrency_applies_per_dispatch() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let log_path = dir.path().join("hooks.log");
+        let hook = |name: &str| CommandHookConfig {
+            command: vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!(
+                    "echo start >> {log}; sleep 0.3; echo end-{name} >> {log}",
+                    log = log_path.display()
+                ),
+            ],
+            ..Default::default()
+        };
+        let hooks = Hooks::new(HooksConfig {
+            command_hooks: CommandHooksConfig {
+                pre_tool_use: vec![hook("pre")],
+                stop: vec![hook("stop")],
+                max_concurrency: Some(1),
+                ..Default::default()
+            },
+        });
+
+        let (pre_tool_use, stop) = tokio::join!(
+            hooks.dispatch(payload(
+                dir.path(),
+                HookEvent::PreToolUse {
+                    tool_name: "shell".to_string(),
+                    tool_input: json!({"command":["echo","hi"]}),
+                    tool_use_id: "call-1".to_string(),
+                },
+            )),
+            hooks.dispatch(payload(
+                dir.path(),
+                HookEvent::Stop {
+                    stop_hook_active: false,
+                    last_assistant_message: None,
+                },
+            )),
+        );
+
+        assert_eq!(pre_tool_use.len(), 1);
+        assert_eq!(stop.len(), 1);
+        let log = std::fs::read_to_string(&log_path).expect("read hook log");
+        let lines: Vec<_> = log.lines().collect();
+        assert_eq!(
+            &lines[..2],
+            &["start", "start"],
+            "dispatches of different events should not share permits: {lines:?}"
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use codex_protocol::ThreadId;
@@ -92,6 +93,11 @@ pub enum HookEvent {
         trigger: String,
         custom_instructions: Option<String>,
     },
+    Compact {
+        trigger: String,
+        before_tokens: i64,
+        after_tokens: i64,
+    },
     WorktreeCreate {
         name: String,
     },
@@ -114,7 +120,9 @@ impl HookEvent {
             } => Some(notification_type),
             HookEvent::SubagentStart { agent_type, .. }
             | HookEvent::SubagentStop { agent_type, .. } => Some(agent_type),
-            HookEvent::PreCompact { trigger, .. } => Some(trigger),
+            HookEvent::PreCompact { trigger, .. } | HookEvent::Compact { trigger, .. } => {
+                Some(trigger)
+            }
             HookEvent::ConfigChange { source, .. } => Some(source),
             _ => None,
         }
@@ -130,6 +138,43 @@ impl HookEvent {
         }
     }
 
+    /// Shell command carried in a tool event's input (`command` as a string or argv, or `cmd`).
+    pub fn command_for_matcher(&self) -> Option<String> {
+        let tool_input = self.tool_input_for_matcher()?;
+        match tool_input
+            .get("command")
+            .or_else(|| tool_input.get("cmd"))?
+        {
+            Value::String(command) => Some(command.clone()),
+            Value::Array(argv) => Some(
+                argv.iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            _ => None,
+        }
+    }
+
+    /// File path carried in a tool event's input (`path` or `file_path`).
+    pub fn path_for_matcher(&self) -> Option<&str> {
+        let tool_input = self.tool_input_for_matcher()?;
+        tool_input
+            .get("path")
+            .or_else(|| tool_input.get("file_path"))
+            .and_then(Value::as_str)
+    }
+
+    fn tool_input_for_matcher(&self) -> Option<&Value> {
+        match self {
+            HookEvent::PreToolUse { tool_input, .. }
+            | HookEvent::PermissionRequest { tool_input, .. }
+            | HookEvent::PostToolUse { tool_input, .. }
+            | HookEvent::PostToolUseFailure { tool_input, .. } => Some(tool_input),
+            _ => None,
+        }
+    }
+
     pub fn user_prompt_for_matcher(&self) -> Option<&str> {
         match self {
             HookEvent::UserPromptSubmit { prompt } => Some(prompt),
@@ -159,6 +204,9 @@ pub struct HookResult {
     pub updated_input: Option<Value>,
     pub worktree_path: Option<PathBuf>,
     pub additional_context: Vec<String>,
+    /// Environment variables exported to the spawned agent by `worktree_create` and
+    /// `subagent_start` hooks.
+    pub env: HashMap<String, String>,
     pub error: Option<String>,
 }
 
@@ -171,6 +219,7 @@ impl HookResult {
             updated_input: None,
             worktree_path: None,
             additional_context: Vec::new(),
+            env: HashMap::new(),
             error: None,
         }
     }
@@ -354,5 +403,14 @@ mod tests {
             .user_prompt_for_matcher(),
             None
         );
+        assert_eq!(
+            HookEvent::Compact {
+                trigger: "auto_compact".to_string(),
+                before_tokens: 120_000,
+                after_tokens: 8_000,
+            }
+            .matcher_text_for_matcher(),
+            Some("auto_compact")
+        );
     }
 }
@@ -2103,6 +2103,10 @@ pub enum SubAgentSource {
         agent_nickname: Option<String>,
         #[serde(default, alias = "agent_type")]
         agent_role: Option<String>,
+        // Tool call (e.g. `spawn_agent` or `create_team`) in the parent's turn that spawned this
+        // agent; unset for agents spawned before it was recorded.
+        #[serde(default)]
+        call_id: Option<String>,
     },
     MemoryConsolidation,
     Other(String),
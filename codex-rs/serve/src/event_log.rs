@@ -0,0 +1,189 @@
+use codex_protocol::protocol::Event;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Number of protocol events kept for `since` replay on `/ws`.
+pub(crate) const PROTOCOL_EVENT_LOG_CAPACITY: usize = 4096;
+
+/// One protocol event as pushed to `/ws` clients.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SequencedEvent {
+    /// Server-wide, strictly increasing cursor; clients reconnect with `since=<seq>`.
+    pub(crate) seq: u64,
+    pub(crate) session_id: String,
+    pub(crate) event: JsonValue,
+}
+
+/// What a new subscriber should send before following [`ProtocolEventLog::subscribe_since`]'s
+/// receiver.
+#[derive(Debug)]
+pub(crate) struct Subscription {
+    /// Buffered events newer than the requested cursor, oldest first.
+    pub(crate) backlog: Vec<SequencedEvent>,
+    /// Set when events after the requested cursor were already evicted from the buffer.
+    pub(crate) missed: bool,
+    /// Cursor of the newest event at subscription time; resume from here after lagging.
+    pub(crate) last_seq: u64,
+    pub(crate) rx: broadcast::Receiver<SequencedEvent>,
+}
+
+/// Bounded, sequence-numbered history of protocol events from every served session.
+///
+/// Appending and subscribing share one lock, so a subscriber's backlog and live receiver
+/// never overlap or leave a gap.
+#[derive(Debug)]
+pub(crate) struct ProtocolEventLog {
+    capacity: usize,
+    inner: Mutex<LogInner>,
+    tx: broadcast::Sender<SequencedEvent>,
+}
+
+#[derive(Debug, Default)]
+struct LogInner {
+    next_seq: u64,
+    events: VecDeque<SequencedEvent>,
+}
+
+impl ProtocolEventLog {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (tx, _) = broadcast::channel(capacity);
+        Self {
+            capacity,
+            inner: Mutex::new(LogInner {
+                next_seq: 1,
+                events: VecDeque::new(),
+            }),
+            tx,
+        }
+    }
+
+    /// Records `event` for `session_id` and pushes it to live subscribers.
+    pub(crate) fn append(&self, session_id: &str, event: &Event) {
+        let event = match serde_json::to_value(event) {
+            Ok(event) => event,
+            Err(err) => {
+                warn!(session_id, "failed to serialize protocol event: {err}");
+                return;
+            }
+        };
+        let mut inner = self.lock();
+        let sequenced = SequencedEvent {
+            seq: inner.next_seq,
+            session_id: session_id.to_string(),
+            event,
+        };
+        inner.next_seq += 1;
+        if inner.events.len() == self.capacity {
+            inner.events.pop_front();
+        }
+        inner.events.push_back(sequenced.clone());
+        let _ = self.tx.send(sequenced);
+    }
+
+    /// Returns the buffered events after `since` together with a receiver for everything
+    /// appended afterwards. Without a cursor only new events are delivered.
+    pub(crate) fn subscribe_since(&self, since: Option<u64>) -> Subscription {
+        let inner = self.lock();
+        let rx = self.tx.subscribe();
+        let last_seq = inner.next_seq - 1;
+        let Some(since) = since else {
+            return Subscription {
+                backlog: Vec::new(),
+                missed: false,
+                last_seq,
+                rx,
+            };
+        };
+        let oldest = inner
+            .events
+            .front()
+            .map_or(inner.next_seq, |event| event.seq);
+        let backlog = inner
+            .events
+            .iter()
+            .filter(|event| event.seq > since)
+            .cloned()
+            .collect();
+        Subscription {
+            backlog,
+            missed: since.saturating_add(1) < oldest,
+            last_seq,
+            rx,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LogInner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::WarningEvent;
+
+    fn warning(id: &str) -> Event {
+        Event {
+            id: id.to_string(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: id.to_string(),
+            }),
+        }
+    }
+
+    fn seqs(events: &[SequencedEvent]) -> Vec<u64> {
+        events.iter().map(|event| event.seq).collect()
+    }
+
+    #[tokio::test]
+    async fn subscribe_since_replays_buffered_events_then_follows_live() {
+        let log = ProtocolEventLog::new(8);
+        log.append("a", &warning("1"));
+        log.append("b", &warning("2"));
+        log.append("a", &warning("3"));
+
+        let mut subscription = log.subscribe_since(Some(1));
+        assert_eq!(seqs(&subscription.backlog), vec![2, 3]);
+        assert!(!subscription.missed);
+        assert_eq!(subscription.backlog[0].session_id, "b");
+
+        log.append("a", &warning("4"));
+        let live = subscription.rx.recv().await.expect("live event");
+        assert_eq!(live.seq, 4);
+        assert_eq!(live.event["id"], "4");
+    }
+
+    #[test]
+    fn subscribe_since_reports_evicted_events() {
+        let log = ProtocolEventLog::new(2);
+        for id in ["1", "2", "3", "4"] {
+            log.append("a", &warning(id));
+        }
+
+        let subscription = log.subscribe_since(Some(1));
+        assert_eq!(seqs(&subscription.backlog), vec![3, 4]);
+        assert!(subscription.missed);
+
+        let subscription = log.subscribe_since(Some(2));
+        assert_eq!(seqs(&subscription.backlog), vec![3, 4]);
+        assert!(!subscription.missed);
+
+        let subscription = log.subscribe_since(Some(4));
+        assert!(subscription.backlog.is_empty());
+        assert!(!subscription.missed);
+
+        let subscription = log.subscribe_since(None);
+        assert!(subscription.backlog.is_empty());
+        assert!(!subscription.missed);
+        assert_eq!(subscription.last_seq, 4);
+    }
+}
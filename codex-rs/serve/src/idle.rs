@@ -0,0 +1,116 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::info;
+
+/// Tracks in-flight requests and long-lived streams so `--idle-timeout` can tell when no
+/// client is connected.
+#[derive(Debug)]
+pub(crate) struct ActivityTracker {
+    active: AtomicUsize,
+    last_active: Mutex<Instant>,
+}
+
+impl Default for ActivityTracker {
+    fn default() -> Self {
+        Self {
+            active: AtomicUsize::new(0),
+            last_active: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+impl ActivityTracker {
+    /// Marks a request or stream as active until the returned guard is dropped.
+    pub(crate) fn start(self: &Arc<Self>) -> ActivityGuard {
+        self.active.fetch_add(1, Ordering::SeqCst);
+        self.touch();
+        ActivityGuard {
+            tracker: Arc::clone(self),
+        }
+    }
+
+    fn touch(&self) {
+        let mut last_active = self
+            .last_active
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        *last_active = Instant::now();
+    }
+
+    /// How long the server has had nothing in flight, or `None` while something is active.
+    fn idle_for(&self) -> Option<Duration> {
+        if self.active.load(Ordering::SeqCst) > 0 {
+            return None;
+        }
+        let last_active = self
+            .last_active
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Some(last_active.elapsed())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ActivityGuard {
+    tracker: Arc<ActivityTracker>,
+}
+
+impl Drop for ActivityGuard {
+    fn drop(&mut self) {
+        self.tracker.touch();
+        self.tracker.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Requests shutdown once `tracker` has been idle for `timeout`.
+pub(crate) fn spawn_idle_monitor(
+    tracker: Arc<ActivityTracker>,
+    timeout: Duration,
+    shutdown_tx: Arc<watch::Sender<bool>>,
+) {
+    let poll_interval = timeout.min(Duration::from_secs(1));
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(poll_interval).await;
+            if *shutdown_tx.borrow() {
+                return;
+            }
+            if tracker.idle_for().is_some_and(|idle| idle >= timeout) {
+                info!("no clients connected for {timeout:?}; shutting down");
+                let _ = shutdown_tx.send(true);
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_monitor_waits_for_active_guards_to_drop() {
+        let tracker = Arc::new(ActivityTracker::default());
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let guard = tracker.start();
+        spawn_idle_monitor(
+            Arc::clone(&tracker),
+            Duration::from_secs(5),
+            Arc::new(shutdown_tx),
+        );
+
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        assert!(!*shutdown_rx.borrow());
+
+        drop(guard);
+        tokio::time::sleep(Duration::from_secs(4)).await;
+        assert!(!*shutdown_rx.borrow());
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(*shutdown_rx.borrow());
+    }
+}
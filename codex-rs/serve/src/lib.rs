@@ -3,18 +3,28 @@ use codex_utils_cli::CliConfigOverrides;
 use std::net::IpAddr;
 use std::path::PathBuf;
 
+mod event_log;
+mod idle;
 mod kanban;
+mod rate_limit;
 mod server;
+mod tls;
 mod workspace;
 
+pub use rate_limit::AuthRateLimit;
+
+/// Default `--max-body-bytes`: room for a few-MB image upload after base64 encoding.
+const DEFAULT_MAX_BODY_BYTES: usize = 8 * 1024 * 1024;
+
 #[derive(Debug, Parser)]
 pub struct Cli {
     #[clap(flatten)]
     pub config_overrides: CliConfigOverrides,
 
-    /// Bind address (default: 127.0.0.1).
-    #[arg(long, default_value = "127.0.0.1")]
-    pub host: IpAddr,
+    /// Bind address (default: 127.0.0.1); repeat to listen on several (e.g. `127.0.0.1` and
+    /// `::1`) with the same port and token.
+    #[arg(long = "host", value_name = "ADDR", default_value = "127.0.0.1")]
+    pub hosts: Vec<IpAddr>,
 
     /// Listen port (default: 0, auto-assign).
     #[arg(long, default_value_t = 0)]
@@ -28,9 +38,70 @@ pub struct Cli {
     #[arg(long)]
     pub dev: bool,
 
-    /// Specify a server token (default: random).
+    /// Accept this server token; repeat to issue several (default: one random token).
+    #[arg(long = "token", value_name = "TOKEN")]
+    pub tokens: Vec<String>,
+
+    /// Read additional accepted tokens from a file, one per line (`#` starts a comment).
+    #[arg(long, value_name = "PATH")]
+    pub tokens_file: Option<PathBuf>,
+
+    /// PEM certificate chain used to serve HTTPS (requires --tls-key).
+    #[arg(long, value_name = "PATH", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key used to serve HTTPS (requires --tls-cert).
+    #[arg(long, value_name = "PATH", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Listen on a Unix domain socket at this path instead of a TCP port.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["hosts", "port", "tls_cert", "tls_key"]
+    )]
+    pub unix_socket: Option<PathBuf>,
+
+    /// Write the bound port and server token as JSON to this file once listening.
+    /// The file is removed on graceful shutdown.
+    #[arg(long, value_name = "PATH", conflicts_with = "unix_socket")]
+    pub port_file: Option<PathBuf>,
+
+    /// Allow cross-origin requests from this origin (e.g. `https://dash.example.com`);
+    /// repeat for several. No CORS headers are sent when unset.
+    #[arg(long = "cors-origin", value_name = "ORIGIN")]
+    pub cors_origins: Vec<String>,
+
+    /// Throttle failed token checks per client IP: after FAILURES within SECONDS, requests from
+    /// that IP get 429 until the budget refills, unless they carry a valid token in the
+    /// `Authorization` header or `token` query. `0` disables.
+    #[arg(long, value_name = "FAILURES/SECONDS", default_value = "20/60")]
+    pub auth_rate_limit: AuthRateLimit,
+
+    /// Reject any single request whose body exceeds this many bytes with 413 before it is
+    /// buffered.
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_BODY_BYTES)]
+    pub max_body_bytes: usize,
+
+    /// Reject mutating requests with 403: every non-GET/HEAD `/api` route, the terminal
+    /// WebSocket, and the GitHub webhook. Event streaming and GET endpoints keep working.
+    #[arg(long)]
+    pub read_only: bool,
+
+    /// Seconds to let in-flight requests drain after SIGINT/SIGTERM before exiting.
+    #[arg(long, value_name = "SECONDS", default_value_t = 10)]
+    pub shutdown_timeout: u64,
+
+    /// Shut down after this many seconds with no in-flight request, event stream, or
+    /// terminal WebSocket. 0 (the default) disables the idle timer.
+    #[arg(long, value_name = "SECONDS", default_value_t = 0)]
+    pub idle_timeout: u64,
+
+    /// Log one `codex_serve::access` tracing span per request with method, path, status,
+    /// latency, client IP, and the index (never the value) of the token used. Probes are
+    /// skipped.
     #[arg(long)]
-    pub token: Option<String>,
+    pub access_log: bool,
 }
 
 pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()> {
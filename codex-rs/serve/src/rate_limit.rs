@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Buckets kept before idle (fully refilled) entries are pruned.
+const MAX_TRACKED_CLIENTS: usize = 1024;
+
+/// `--auth-rate-limit` value: at most `max_failures` failed authentications per client IP
+/// within `window`. A limit of `0` disables throttling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthRateLimit {
+    pub max_failures: u32,
+    pub window: Duration,
+}
+
+impl AuthRateLimit {
+    pub(crate) fn is_disabled(&self) -> bool {
+        self.max_failures == 0 || self.window.is_zero()
+    }
+}
+
+impl FromStr for AuthRateLimit {
+    type Err = String;
+
+    /// Parses `FAILURES/SECONDS` (e.g. `20/60`), or `0` to disable.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if value == "0" {
+            return Ok(Self {
+                max_failures: 0,
+                window: Duration::ZERO,
+            });
+        }
+        let Some((failures, seconds)) = value.split_once('/') else {
+            return Err(format!(
+                "expected FAILURES/SECONDS (e.g. 20/60) or 0, got `{value}`"
+            ));
+        };
+        let max_failures = failures
+            .trim()
+            .parse::<u32>()
+            .map_err(|err| format!("invalid failure count `{failures}`: {err}"))?;
+        let seconds = seconds
+            .trim()
+            .parse::<u64>()
+            .map_err(|err| format!("invalid window `{seconds}`: {err}"))?;
+        if seconds == 0 && max_failures > 0 {
+            return Err("rate limit window must be at least one second".to_string());
+        }
+        Ok(Self {
+            max_failures,
+            window: Duration::from_secs(seconds),
+        })
+    }
+}
+
+impl fmt::Display for AuthRateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_disabled() {
+            return f.write_str("0");
+        }
+        write!(f, "{}/{}", self.max_failures, self.window.as_secs())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Per-IP token bucket charged only for failed authentications: each failure spends one
+/// token, and tokens refill continuously at `max_failures / window`.
+#[derive(Debug)]
+pub(crate) struct AuthRateLimiter {
+    limit: AuthRateLimit,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+impl AuthRateLimiter {
+    pub(crate) fn new(limit: AuthRateLimit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn disabled() -> Self {
+        Self::new(AuthRateLimit {
+            max_failures: 0,
+            window: Duration::ZERO,
+        })
+    }
+
+    /// Whether `ip` may still attempt to authenticate. Checked before the credentials are
+    /// evaluated, so an exhausted client learns nothing about its guesses.
+    pub(crate) fn has_budget(&self, ip: IpAddr) -> bool {
+        self.with_bucket(ip, |bucket| bucket.tokens >= 1.0)
+    }
+
+    /// Records a failed authentication from `ip`. Returns `false` once the client has
+    /// exhausted its budget and should receive 429 instead of 401.
+    pub(crate) fn record_failure(&self, ip: IpAddr) -> bool {
+        self.with_bucket(ip, |bucket| {
+            if bucket.tokens >= 1.0 {
+                bucket.tokens -= 1.0;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Runs `f` on `ip`'s bucket after refilling it. A disabled limit always has budget.
+    fn with_bucket(&self, ip: IpAddr, f: impl FnOnce(&mut Bucket) -> bool) -> bool {
+        if self.limit.is_disabled() {
+            return true;
+        }
+        let capacity = f64::from(self.limit.max_failures);
+        let refill_per_sec = capacity / self.limit.window.as_secs_f64();
+        let now = Instant::now();
+
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if buckets.len() >= MAX_TRACKED_CLIENTS {
+            buckets.retain(|_, bucket| {
+                let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+                bucket.tokens + elapsed * refill_per_sec < capacity
+            });
+        }
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.updated_at = now;
+        f(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn parses_failures_per_window_and_disabled() {
+        assert_eq!(
+            "20/60".parse::<AuthRateLimit>(),
+            Ok(AuthRateLimit {
+                max_failures: 20,
+                window: Duration::from_secs(60),
+            })
+        );
+        assert!("0".parse::<AuthRateLimit>().expect("parse").is_disabled());
+        assert!("20".parse::<AuthRateLimit>().is_err());
+        assert!("5/0".parse::<AuthRateLimit>().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn throttles_after_budget_and_refills_over_window() {
+        let limiter = AuthRateLimiter::new("2/10".parse().expect("parse"));
+        let client = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+        let other = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+
+        assert!(limiter.record_failure(client));
+        assert!(limiter.has_budget(client));
+        assert!(limiter.record_failure(client));
+        assert!(!limiter.has_budget(client));
+        assert!(!limiter.record_failure(client));
+        assert!(limiter.record_failure(other));
+
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert!(limiter.has_budget(client));
+        assert!(limiter.record_failure(client));
+        assert!(!limiter.record_failure(client));
+    }
+}
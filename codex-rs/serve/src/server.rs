@@ -1,5 +1,13 @@
 use crate::Cli;
+use crate::event_log::PROTOCOL_EVENT_LOG_CAPACITY;
+use crate::event_log::ProtocolEventLog;
+use crate::event_log::SequencedEvent;
+use crate::event_log::Subscription;
+use crate::idle;
+use crate::idle::ActivityTracker;
 use crate::kanban;
+use crate::rate_limit::AuthRateLimiter;
+use crate::tls;
 use crate::workspace;
 use anyhow::Context;
 use anyhow::bail;
@@ -7,6 +15,8 @@ use axum::Json;
 use axum::Router;
 use axum::body::Body;
 use axum::body::Bytes;
+use axum::extract::ConnectInfo;
+use axum::extract::DefaultBodyLimit;
 use axum::extract::Path;
 use axum::extract::Query;
 use axum::extract::State;
@@ -14,6 +24,7 @@ use axum::extract::ws::Message as WsMessage;
 use axum::extract::ws::WebSocket;
 use axum::extract::ws::WebSocketUpgrade;
 use axum::http::HeaderValue;
+use axum::http::Method;
 use axum::http::StatusCode;
 use axum::http::header;
 use axum::response::IntoResponse;
@@ -61,7 +72,9 @@ use codex_protocol::request_user_input::RequestUserInputAnswer;
 use codex_protocol::request_user_input::RequestUserInputResponse;
 use codex_protocol::user_input::UserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
+use futures::FutureExt;
 use futures::StreamExt;
+use futures::TryFutureExt;
 use futures::stream;
 use include_dir::Dir;
 use include_dir::include_dir;
@@ -72,10 +85,13 @@ use serde::Serialize;
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::net::IpAddr;
 use std::net::SocketAddr;
 use std::path::Path as FsPath;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 use tokio::io::AsyncReadExt;
 use tokio::io::AsyncSeekExt;
@@ -83,6 +99,9 @@ use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
 use tokio::sync::broadcast;
+use tokio::sync::watch;
+use tracing::Instrument;
+use tracing::debug;
 use tracing::warn;
 
 static WEB_ASSETS: Dir = include_dir!("$CARGO_MANIFEST_DIR/assets/web");
@@ -99,7 +118,22 @@ const WORKSPACE_KANBAN_FILE_NAME: &str = "kanban.json";
 
 #[derive(Clone)]
 struct AppState {
-    token: Arc<String>,
+    /// Accepted server tokens; the first one is advertised in the startup URL.
+    tokens: Arc<Vec<String>>,
+    /// Set by `--read-only`; see [`reject_mutations_when_read_only`].
+    read_only: bool,
+    /// Live requests and streams, consulted by `--idle-timeout`.
+    activity: Arc<ActivityTracker>,
+    /// Origins from `--cors-origin`; see [`apply_cors`].
+    cors_origins: Arc<Vec<String>>,
+    /// Per-request body cap from `--max-body-bytes`; see [`limit_request_bodies`].
+    max_body_bytes: usize,
+    /// Set by `--access-log`; see [`log_access`].
+    access_log: bool,
+    /// Per-IP budget for failed authentications; see [`throttle_auth_failures`].
+    auth_rate_limiter: Arc<AuthRateLimiter>,
+    /// Reported by `/readyz`: set once the listener is serving, cleared when shutdown begins.
+    ready: Arc<AtomicBool>,
     static_dir: Option<PathBuf>,
     config: Arc<Config>,
     cli_overrides: Vec<(String, toml::Value)>,
@@ -117,6 +151,27 @@ struct AppState {
     github_sync_lock: Arc<Mutex<()>>,
     workspace_kanban_locks: Arc<RwLock<HashMap<String, Arc<Mutex<()>>>>>,
     events_tx: broadcast::Sender<SyncEvent>,
+    /// Raw protocol events from every session, streamed over `/ws`.
+    protocol_events: Arc<ProtocolEventLog>,
+}
+
+impl AppState {
+    fn is_authorized(&self, candidate: &str) -> bool {
+        match authorized_token_index(&self.tokens, candidate) {
+            Some(index) => {
+                debug!(token_index = index, "serve request authenticated");
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn authorized_token_index(tokens: &[String], candidate: &str) -> Option<usize> {
+    if candidate.is_empty() {
+        return None;
+    }
+    tokens.iter().position(|token| token == candidate)
 }
 
 struct ActiveSession {
@@ -273,6 +328,12 @@ struct SessionsResponse {
     sessions: Vec<SessionSummary>,
 }
 
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TeamsResponse {
+    teams: Vec<codex_core::PersistedTeamConfig>,
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct SessionResponse {
@@ -642,12 +703,15 @@ enum AuthRequest {
 
 #[cfg(test)]
 mod tests {
+    use super::ActivityTracker;
     use super::AppState;
+    use super::AuthRateLimiter;
     use super::AuthRequest;
     use super::MessagePostRequest;
     use super::ReasoningSummaryConfig;
     use super::SpawnRequest;
     use super::WEB_ASSETS;
+    use super::bind_listeners;
     use super::build_router;
     use super::custom_prompts_to_slash_commands;
     use super::extract_reasoning_effort_from_history;
@@ -690,8 +754,12 @@ mod tests {
     use std::collections::HashMap;
     use std::collections::HashSet;
     use std::ffi::OsString;
+    use std::net::IpAddr;
     use std::path::PathBuf;
     use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
     use tokio::sync::RwLock;
     use tokio::sync::broadcast;
     use tower::util::ServiceExt;
@@ -772,6 +840,304 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn write_port_file_overwrites_existing_contents() {
+        let dir = temp_dir("port-file");
+        let path = dir.join("serve.json");
+        std::fs::write(&path, "stale contents that are longer than the new json").expect("seed");
+
+        super::write_port_file(&path, 4321, "tok")
+            .await
+            .expect("write");
+
+        let written: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).expect("read")).expect("json");
+        assert_eq!(written, serde_json::json!({"port": 4321, "token": "tok"}));
+    }
+
+    #[tokio::test]
+    async fn load_server_tokens_merges_flags_and_file() {
+        let dir = temp_dir("tokens-file");
+        let path = dir.join("tokens");
+        std::fs::write(&path, "# team tokens\nalice\n\n  bob  \ncli\n").expect("write");
+
+        let tokens = super::load_server_tokens(vec!["cli".to_string()], Some(&path))
+            .await
+            .expect("load tokens");
+
+        assert_eq!(tokens, vec!["cli", "alice", "bob"]);
+    }
+
+    #[test]
+    fn authorized_token_index_matches_any_configured_token() {
+        let tokens = vec!["first".to_string(), "second".to_string()];
+
+        assert_eq!(super::authorized_token_index(&tokens, "first"), Some(0));
+        assert_eq!(super::authorized_token_index(&tokens, "second"), Some(1));
+        assert_eq!(super::authorized_token_index(&tokens, "third"), None);
+        assert_eq!(super::authorized_token_index(&tokens, ""), None);
+    }
+
+    #[tokio::test]
+    async fn cors_headers_only_sent_for_configured_origins() {
+        let origins = Arc::new(super::normalize_cors_origins(&[
+            "https://dash.example.com/".to_string(),
+        ]));
+        let app = axum::Router::new()
+            .route("/api/sessions", axum::routing::get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                origins,
+                super::apply_cors,
+            ));
+
+        let preflight = Request::builder()
+            .method("OPTIONS")
+            .uri("/api/sessions")
+            .header("origin", "https://dash.example.com")
+            .header("access-control-request-method", "GET")
+            .body(Body::empty())
+            .unwrap();
+        let res = app.clone().oneshot(preflight).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            res.headers()["access-control-allow-origin"],
+            "https://dash.example.com"
+        );
+        assert_eq!(res.headers()["access-control-allow-credentials"], "true");
+
+        let allowed = Request::builder()
+            .uri("/api/sessions")
+            .header("origin", "https://dash.example.com")
+            .body(Body::empty())
+            .unwrap();
+        let res = app.clone().oneshot(allowed).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers()["access-control-allow-origin"],
+            "https://dash.example.com"
+        );
+
+        let other = Request::builder()
+            .uri("/api/sessions")
+            .header("origin", "https://evil.example.com")
+            .body(Body::empty())
+            .unwrap();
+        let res = app.oneshot(other).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!res.headers().contains_key("access-control-allow-origin"));
+    }
+
+    #[test]
+    fn request_token_index_identifies_token_without_exposing_it() {
+        let tokens = vec!["first".to_string(), "second".to_string()];
+        let request = |builder: axum::http::request::Builder| builder.body(Body::empty()).unwrap();
+
+        let bearer = request(
+            Request::builder()
+                .uri("/api/sessions")
+                .header("authorization", "Bearer second"),
+        );
+        assert_eq!(super::request_token_index(&tokens, &bearer), Some(1));
+
+        let query = request(Request::builder().uri("/ws?since=3&token=first"));
+        assert_eq!(super::request_token_index(&tokens, &query), Some(0));
+
+        let unknown = request(Request::builder().uri("/api/sessions?token=nope"));
+        assert_eq!(super::request_token_index(&tokens, &unknown), None);
+
+        let anonymous = request(Request::builder().uri("/healthz"));
+        assert_eq!(super::request_token_index(&tokens, &anonymous), None);
+    }
+
+    #[tokio::test]
+    async fn oversized_request_bodies_are_rejected_per_request() {
+        let app = super::limit_request_bodies(
+            axum::Router::new().route(
+                "/echo",
+                axum::routing::post(
+                    |Json(body): Json<serde_json::Value>| async move { Json(body) },
+                ),
+            ),
+            16,
+        );
+        let request = |body: &'static str, declare_len: bool| {
+            let builder = Request::builder()
+                .method("POST")
+                .uri("/echo")
+                .header("content-type", "application/json");
+            let builder = if declare_len {
+                builder.header("content-length", body.len())
+            } else {
+                builder
+            };
+            builder.body(Body::from(body)).unwrap()
+        };
+
+        for declare_len in [true, false] {
+            let res = app
+                .clone()
+                .oneshot(request(
+                    r#"{"text":"far too long for the cap"}"#,
+                    declare_len,
+                ))
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+            let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["error"], "payload_too_large");
+            assert_eq!(body["maxBodyBytes"], 16);
+        }
+
+        // The cap is per request: small requests keep succeeding after rejections.
+        for _ in 0..3 {
+            let res = app
+                .clone()
+                .oneshot(request(r#"{"a":1}"#, true))
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn repeated_auth_failures_are_throttled_per_client() {
+        let limiter = Arc::new(AuthRateLimiter::new("2/60".parse().expect("parse")));
+        let tokens = Arc::new(vec!["test-token".to_string()]);
+        let handler_calls = Arc::new(AtomicUsize::new(0));
+        let calls = Arc::clone(&handler_calls);
+        let app = axum::Router::new()
+            .route(
+                "/api/sessions",
+                axum::routing::get(move |headers: axum::http::HeaderMap| async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    if super::bearer_token(&headers).as_deref() == Some("test-token") {
+                        StatusCode::OK
+                    } else {
+                        StatusCode::UNAUTHORIZED
+                    }
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                (limiter, tokens),
+                super::throttle_auth_failures,
+            ))
+            .layer(axum::extract::connect_info::MockConnectInfo(
+                std::net::SocketAddr::from(([192, 0, 2, 1], 4000)),
+            ));
+        let request = |authorized: bool| {
+            let builder = Request::builder().uri("/api/sessions");
+            let builder = if authorized {
+                builder.header("authorization", "Bearer test-token")
+            } else {
+                builder
+            };
+            builder.body(Body::empty()).unwrap()
+        };
+
+        for _ in 0..2 {
+            let res = app.clone().oneshot(request(false)).await.unwrap();
+            assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        }
+        let res = app.clone().oneshot(request(false)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(handler_calls.load(Ordering::SeqCst), 2);
+
+        // A correct token bypasses the spent budget, so the legitimate user is not locked out.
+        for _ in 0..3 {
+            let res = app.clone().oneshot(request(true)).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+        assert_eq!(handler_calls.load(Ordering::SeqCst), 5);
+
+        let res = app.clone().oneshot(request(false)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn health_probes_are_not_throttled() {
+        let limiter = Arc::new(AuthRateLimiter::new("1/60".parse().expect("parse")));
+        let tokens = Arc::new(vec!["test-token".to_string()]);
+        let app = axum::Router::new()
+            .route("/healthz", axum::routing::get(super::handle_healthz))
+            .route(
+                "/api/sessions",
+                axum::routing::get(|| async { StatusCode::UNAUTHORIZED }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                (limiter, tokens),
+                super::throttle_auth_failures,
+            ))
+            .layer(axum::extract::connect_info::MockConnectInfo(
+                std::net::SocketAddr::from(([192, 0, 2, 1], 4000)),
+            ));
+        let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let res = app.clone().oneshot(get("/api/sessions")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        let res = app.clone().oneshot(get("/api/sessions")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let res = app.clone().oneshot(get("/healthz")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn unix_socket_conflicts_with_tcp_binding_args() {
+        use clap::Parser;
+
+        let parsed = crate::Cli::try_parse_from(["serve", "--unix-socket", "/tmp/codex.sock"])
+            .expect("parse");
+        assert_eq!(parsed.unix_socket, Some(PathBuf::from("/tmp/codex.sock")));
+
+        for conflicting in [["--port", "8080"], ["--host", "0.0.0.0"]] {
+            let mut args = vec!["serve", "--unix-socket", "/tmp/codex.sock"];
+            args.extend(conflicting);
+            assert!(
+                crate::Cli::try_parse_from(args).is_err(),
+                "--unix-socket should conflict with {conflicting:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn host_is_repeatable_and_defaults_to_loopback() {
+        use clap::Parser;
+
+        let parsed = crate::Cli::try_parse_from(["serve"]).expect("parse");
+        assert_eq!(parsed.hosts, vec![IpAddr::from([127, 0, 0, 1])]);
+
+        let parsed = crate::Cli::try_parse_from(["serve", "--host", "127.0.0.1", "--host", "::1"])
+            .expect("parse");
+        assert_eq!(
+            parsed.hosts,
+            vec![
+                IpAddr::from([127, 0, 0, 1]),
+                IpAddr::from(std::net::Ipv6Addr::LOCALHOST)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn bind_listeners_skips_failed_hosts_unless_none_bind() {
+        let loopback = IpAddr::from([127, 0, 0, 1]);
+        // 192.0.2.0/24 is reserved for documentation and never assigned to a local interface.
+        let unassigned = IpAddr::from([192, 0, 2, 1]);
+
+        let listeners = bind_listeners(&[unassigned, loopback, loopback], 0)
+            .await
+            .expect("loopback binds");
+        assert_eq!(listeners.len(), 1);
+        assert_ne!(listeners[0].local_addr().expect("local addr").port(), 0);
+
+        let err = bind_listeners(&[unassigned], 0)
+            .await
+            .expect_err("nothing binds");
+        assert!(err.to_string().contains("192.0.2.1"), "{err}");
+    }
+
     #[test]
     fn safe_join_rejects_parent_and_absolute_paths() {
         let root = PathBuf::from("/tmp/root");
@@ -1011,7 +1377,14 @@ mod tests {
         let kanban = crate::kanban::load_or_default(&config.codex_home).await;
 
         let state = AppState {
-            token: Arc::new("test-token".to_string()),
+            tokens: Arc::new(vec!["test-token".to_string()]),
+            read_only: false,
+            activity: Arc::new(ActivityTracker::default()),
+            cors_origins: Arc::new(Vec::new()),
+            max_body_bytes: crate::DEFAULT_MAX_BODY_BYTES,
+            access_log: false,
+            auth_rate_limiter: Arc::new(AuthRateLimiter::disabled()),
+            ready: Arc::new(AtomicBool::new(true)),
             static_dir: None,
             config: Arc::new(config),
             cli_overrides: Vec::new(),
@@ -1029,6 +1402,7 @@ mod tests {
             github_sync_lock: Arc::new(tokio::sync::Mutex::new(())),
             workspace_kanban_locks: Arc::new(RwLock::new(HashMap::new())),
             events_tx,
+            protocol_events: Arc::new(ProtocolEventLog::new(PROTOCOL_EVENT_LOG_CAPACITY)),
         };
 
         let prompts_dir = state.config.codex_home.join("prompts");
@@ -1164,7 +1538,14 @@ mod tests {
         let kanban = crate::kanban::load_or_default(&config.codex_home).await;
 
         let state = AppState {
-            token: Arc::new("test-token".to_string()),
+            tokens: Arc::new(vec!["test-token".to_string()]),
+            read_only: false,
+            activity: Arc::new(ActivityTracker::default()),
+            cors_origins: Arc::new(Vec::new()),
+            max_body_bytes: crate::DEFAULT_MAX_BODY_BYTES,
+            access_log: false,
+            auth_rate_limiter: Arc::new(AuthRateLimiter::disabled()),
+            ready: Arc::new(AtomicBool::new(true)),
             static_dir: None,
             config: Arc::new(config),
             cli_overrides: Vec::new(),
@@ -1182,6 +1563,7 @@ mod tests {
             github_sync_lock: Arc::new(tokio::sync::Mutex::new(())),
             workspace_kanban_locks: Arc::new(RwLock::new(HashMap::new())),
             events_tx,
+            protocol_events: Arc::new(ProtocolEventLog::new(PROTOCOL_EVENT_LOG_CAPACITY)),
         };
 
         let session_dir = temp_dir("session-cwd");
@@ -1246,6 +1628,113 @@ mod tests {
         let _ = state.thread_manager.remove_and_close_all_threads().await;
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn read_only_mode_rejects_mutations_but_allows_reads() {
+        let _lock = ENV_LOCK.lock().await;
+        codex_core::test_support::set_thread_manager_test_mode(true);
+
+        let codex_home = temp_dir("codex-home");
+        let _env = EnvVarGuard::set("CODEX_HOME", codex_home.as_path());
+
+        let base_overrides = ConfigOverrides {
+            cwd: Some(codex_home.clone()),
+            ..Default::default()
+        };
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            Vec::new(),
+            base_overrides.clone(),
+        )
+        .await
+        .expect("load config");
+
+        let auth_manager = AuthManager::shared(
+            config.codex_home.clone(),
+            false,
+            config.cli_auth_credentials_store_mode,
+        );
+        let thread_manager = Arc::new(ThreadManager::new(
+            config.codex_home.clone(),
+            auth_manager.clone(),
+            SessionSource::Cli,
+            config.model_catalog.clone(),
+            CollaborationModesConfig::default(),
+        ));
+        let (events_tx, _) = broadcast::channel(64);
+
+        let state = AppState {
+            tokens: Arc::new(vec!["test-token".to_string()]),
+            read_only: true,
+            activity: Arc::new(ActivityTracker::default()),
+            cors_origins: Arc::new(Vec::new()),
+            max_body_bytes: crate::DEFAULT_MAX_BODY_BYTES,
+            access_log: false,
+            auth_rate_limiter: Arc::new(AuthRateLimiter::disabled()),
+            ready: Arc::new(AtomicBool::new(true)),
+            static_dir: None,
+            config: Arc::new(config),
+            cli_overrides: Vec::new(),
+            base_overrides,
+            auth_manager,
+            thread_manager,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            kanban: Arc::new(RwLock::new(crate::kanban::KanbanConfig::default())),
+            workspaces: Arc::new(RwLock::new(crate::workspace::WorkspaceStore::default())),
+            github_webhook: None,
+            github_repos: Arc::new(RwLock::new(Vec::new())),
+            github_work_items: Arc::new(RwLock::new(super::GithubWorkItemsSnapshot::default())),
+            github_kanban: Arc::new(RwLock::new(crate::kanban::KanbanConfig::default())),
+            github_jobs: Arc::new(RwLock::new(HashMap::new())),
+            github_sync_lock: Arc::new(tokio::sync::Mutex::new(())),
+            workspace_kanban_locks: Arc::new(RwLock::new(HashMap::new())),
+            events_tx,
+            protocol_events: Arc::new(ProtocolEventLog::new(PROTOCOL_EVENT_LOG_CAPACITY)),
+        };
+        let app = build_router(state.clone());
+
+        let list_req = Request::builder()
+            .uri("/api/workspaces")
+            .header("authorization", "Bearer test-token")
+            .body(Body::empty())
+            .unwrap();
+        let res = app.clone().oneshot(list_req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let create_req = Request::builder()
+            .method("POST")
+            .uri("/api/workspaces")
+            .header("authorization", "Bearer test-token")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({ "name": "WS1", "repos": [] }).to_string(),
+            ))
+            .unwrap();
+        let res = app.clone().oneshot(create_req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        assert!(state.workspaces.read().await.list().is_empty());
+
+        let unauthenticated_req = Request::builder()
+            .method("POST")
+            .uri("/api/workspaces")
+            .body(Body::empty())
+            .unwrap();
+        let res = app.clone().oneshot(unauthenticated_req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        // Probes skip the token and the read-only gate.
+        for probe in ["/healthz", "/readyz"] {
+            let req = Request::builder().uri(probe).body(Body::empty()).unwrap();
+            let res = app.clone().oneshot(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK, "{probe}");
+        }
+        state.ready.store(false, Ordering::SeqCst);
+        let req = Request::builder()
+            .uri("/readyz")
+            .body(Body::empty())
+            .unwrap();
+        let res = app.oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn web_handlers_workspaces_crud_persists_across_reload() {
         let _lock = ENV_LOCK.lock().await;
@@ -1281,7 +1770,14 @@ mod tests {
         let kanban = crate::kanban::load_or_default(&config.codex_home).await;
 
         let state = AppState {
-            token: Arc::new("test-token".to_string()),
+            tokens: Arc::new(vec!["test-token".to_string()]),
+            read_only: false,
+            activity: Arc::new(ActivityTracker::default()),
+            cors_origins: Arc::new(Vec::new()),
+            max_body_bytes: crate::DEFAULT_MAX_BODY_BYTES,
+            access_log: false,
+            auth_rate_limiter: Arc::new(AuthRateLimiter::disabled()),
+            ready: Arc::new(AtomicBool::new(true)),
             static_dir: None,
             config: Arc::new(config),
             cli_overrides: Vec::new(),
@@ -1299,6 +1795,7 @@ mod tests {
             github_sync_lock: Arc::new(tokio::sync::Mutex::new(())),
             workspace_kanban_locks: Arc::new(RwLock::new(HashMap::new())),
             events_tx,
+            protocol_events: Arc::new(ProtocolEventLog::new(PROTOCOL_EVENT_LOG_CAPACITY)),
         };
 
         let app = build_router(state.clone());
@@ -1400,7 +1897,14 @@ mod tests {
         let kanban = crate::kanban::load_or_default(&config.codex_home).await;
 
         let state = AppState {
-            token: Arc::new("test-token".to_string()),
+            tokens: Arc::new(vec!["test-token".to_string()]),
+            read_only: false,
+            activity: Arc::new(ActivityTracker::default()),
+            cors_origins: Arc::new(Vec::new()),
+            max_body_bytes: crate::DEFAULT_MAX_BODY_BYTES,
+            access_log: false,
+            auth_rate_limiter: Arc::new(AuthRateLimiter::disabled()),
+            ready: Arc::new(AtomicBool::new(true)),
             static_dir: None,
             config: Arc::new(config),
             cli_overrides: Vec::new(),
@@ -1418,6 +1922,7 @@ mod tests {
             github_sync_lock: Arc::new(tokio::sync::Mutex::new(())),
             workspace_kanban_locks: Arc::new(RwLock::new(HashMap::new())),
             events_tx,
+            protocol_events: Arc::new(ProtocolEventLog::new(PROTOCOL_EVENT_LOG_CAPACITY)),
         };
 
         let app = build_router(state.clone());
@@ -1510,7 +2015,14 @@ mod tests {
         let kanban = crate::kanban::load_or_default(&config.codex_home).await;
 
         let state = AppState {
-            token: Arc::new("test-token".to_string()),
+            tokens: Arc::new(vec!["test-token".to_string()]),
+            read_only: false,
+            activity: Arc::new(ActivityTracker::default()),
+            cors_origins: Arc::new(Vec::new()),
+            max_body_bytes: crate::DEFAULT_MAX_BODY_BYTES,
+            access_log: false,
+            auth_rate_limiter: Arc::new(AuthRateLimiter::disabled()),
+            ready: Arc::new(AtomicBool::new(true)),
             static_dir: None,
             config: Arc::new(config),
             cli_overrides: Vec::new(),
@@ -1528,6 +2040,7 @@ mod tests {
             github_sync_lock: Arc::new(tokio::sync::Mutex::new(())),
             workspace_kanban_locks: Arc::new(RwLock::new(HashMap::new())),
             events_tx,
+            protocol_events: Arc::new(ProtocolEventLog::new(PROTOCOL_EVENT_LOG_CAPACITY)),
         };
 
         let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S").to_string();
@@ -2055,7 +2568,14 @@ mod tests {
         let kanban = crate::kanban::load_or_default(&config.codex_home).await;
 
         let state = AppState {
-            token: Arc::new("test-token".to_string()),
+            tokens: Arc::new(vec!["test-token".to_string()]),
+            read_only: false,
+            activity: Arc::new(ActivityTracker::default()),
+            cors_origins: Arc::new(Vec::new()),
+            max_body_bytes: crate::DEFAULT_MAX_BODY_BYTES,
+            access_log: false,
+            auth_rate_limiter: Arc::new(AuthRateLimiter::disabled()),
+            ready: Arc::new(AtomicBool::new(true)),
             static_dir: None,
             config: Arc::new(config),
             cli_overrides: Vec::new(),
@@ -2073,6 +2593,7 @@ mod tests {
             github_sync_lock: Arc::new(tokio::sync::Mutex::new(())),
             workspace_kanban_locks: Arc::new(RwLock::new(HashMap::new())),
             events_tx,
+            protocol_events: Arc::new(ProtocolEventLog::new(PROTOCOL_EVENT_LOG_CAPACITY)),
         };
 
         let app = build_router(state);
@@ -2210,6 +2731,14 @@ struct EventsQuery {
     session_id: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct EventsWsQuery {
+    token: Option<String>,
+    session_id: Option<String>,
+    /// Last `seq` the client received; buffered events after it are replayed first.
+    since: Option<u64>,
+}
+
 pub async fn run(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()> {
     let cli_overrides = cli
         .config_overrides
@@ -2262,7 +2791,14 @@ pub async fn run(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::
         None
     };
 
-    let token = cli.token.unwrap_or_else(generate_token);
+    let tls_acceptor = match (cli.tls_cert.as_deref(), cli.tls_key.as_deref()) {
+        (Some(cert), Some(key)) => Some(tls::load_tls_acceptor(cert, key)?),
+        (None, None) => None,
+        _ => bail!("--tls-cert and --tls-key must be supplied together"),
+    };
+
+    let tokens = load_server_tokens(cli.tokens, cli.tokens_file.as_deref()).await?;
+    let token = tokens[0].clone();
     let (events_tx, _) = broadcast::channel::<SyncEvent>(2048);
     let config_cwd = AbsolutePathBuf::current_dir().context("resolve config cwd")?;
     let config_toml = load_config_as_toml_with_cli_overrides(
@@ -2312,7 +2848,14 @@ pub async fn run(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::
         HashMap::new()
     };
     let state = AppState {
-        token: Arc::new(token.clone()),
+        tokens: Arc::new(tokens),
+        read_only: cli.read_only,
+        activity: Arc::new(ActivityTracker::default()),
+        cors_origins: Arc::new(normalize_cors_origins(&cli.cors_origins)),
+        max_body_bytes: cli.max_body_bytes,
+        access_log: cli.access_log,
+        auth_rate_limiter: Arc::new(AuthRateLimiter::new(cli.auth_rate_limit)),
+        ready: Arc::new(AtomicBool::new(false)),
         static_dir,
         config: Arc::clone(&config),
         cli_overrides,
@@ -2330,47 +2873,354 @@ pub async fn run(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::
         github_sync_lock: Arc::new(Mutex::new(())),
         workspace_kanban_locks: Arc::new(RwLock::new(HashMap::new())),
         events_tx,
+        protocol_events: Arc::new(ProtocolEventLog::new(PROTOCOL_EVENT_LOG_CAPACITY)),
     };
 
     if state.github_webhook.is_some() {
         tokio::spawn(github_sync_loop(state.clone()));
     }
 
-    let listener = TcpListener::bind(SocketAddr::new(cli.host, cli.port))
-        .await
-        .context("bind serve listener")?;
-    let local_addr = listener.local_addr().context("get local addr")?;
-
-    if cli.host.is_unspecified() || cli.host.is_multicast() {
-        warn!("listening on potentially unsafe host {}", cli.host);
+    let shutdown_timeout = Duration::from_secs(cli.shutdown_timeout);
+    let shutdown_rx = spawn_shutdown_listener(
+        Arc::clone(&state.activity),
+        Duration::from_secs(cli.idle_timeout),
+    );
+    if let Some(socket_path) = cli.unix_socket.as_deref() {
+        if tls_acceptor.is_some() {
+            bail!("--unix-socket cannot be combined with --tls-cert/--tls-key");
+        }
+        let ready = Arc::clone(&state.ready);
+        return serve_unix_socket(
+            socket_path,
+            build_router(state),
+            &token,
+            ready,
+            shutdown_rx,
+            shutdown_timeout,
+        )
+        .await;
     }
-    if cli.host.to_string() == "0.0.0.0" {
-        warn!("binding to 0.0.0.0 exposes Codex to your network");
+
+    let listeners = bind_listeners(&cli.hosts, cli.port).await?;
+    let local_addrs = listeners
+        .iter()
+        .map(TcpListener::local_addr)
+        .collect::<std::io::Result<Vec<_>>>()
+        .context("get local addr")?;
+
+    for local_addr in &local_addrs {
+        let host = local_addr.ip();
+        if host.is_unspecified() || host.is_multicast() {
+            warn!("listening on potentially unsafe host {host}");
+        }
+        if host.to_string() == "0.0.0.0" {
+            warn!("binding to 0.0.0.0 exposes Codex to your network");
+        }
     }
 
-    let url = format!(
-        "http://{}:{}?token={token}",
-        local_addr.ip(),
-        local_addr.port()
-    );
-    println!("Codex Web UI running at {url}");
+    let scheme = if tls_acceptor.is_some() {
+        "https"
+    } else {
+        "http"
+    };
+    let urls = local_addrs
+        .iter()
+        .map(|local_addr| format!("{scheme}://{local_addr}?token={token}"))
+        .collect::<Vec<_>>();
+    // Every listener shares the port of the first successful bind.
+    if let Some(port_file) = cli.port_file.as_deref() {
+        write_port_file(port_file, local_addrs[0].port(), &token).await?;
+    }
+    for url in &urls {
+        println!("Codex Web UI running at {url}");
+    }
+    let url = urls[0].clone();
     if !cli.no_open {
-        let _ = webbrowser::open(&url);
+        // Opening the browser can block; skip it if a shutdown signal already arrived.
+        let shutdown_rx = shutdown_rx.clone();
+        tokio::task::spawn_blocking(move || {
+            if !*shutdown_rx.borrow() {
+                let _ = webbrowser::open(&url);
+            }
+        });
     }
 
     let app = build_router(state.clone());
+    mark_ready_until_shutdown(&state.ready, shutdown_rx.clone());
 
-    axum::serve(listener, app.into_make_service())
-        .await
-        .context("http serve")?;
+    let servers = listeners
+        .into_iter()
+        .map(|listener| {
+            let app = app.clone();
+            let shutdown = wait_for_shutdown(shutdown_rx.clone());
+            match tls_acceptor.clone() {
+                Some(acceptor) => axum::serve(
+                    tls::TlsListener::new(listener, acceptor),
+                    app.into_make_service_with_connect_info::<SocketAddr>(),
+                )
+                .with_graceful_shutdown(shutdown)
+                .into_future()
+                .boxed(),
+                None => axum::serve(
+                    listener,
+                    app.into_make_service_with_connect_info::<SocketAddr>(),
+                )
+                .with_graceful_shutdown(shutdown)
+                .into_future()
+                .boxed(),
+            }
+        })
+        .collect::<Vec<_>>();
+    let result = drain_on_shutdown(
+        futures::future::try_join_all(servers).map_ok(|_| ()),
+        shutdown_rx,
+        shutdown_timeout,
+    )
+    .await
+    .context(if tls_acceptor.is_some() {
+        "https serve"
+    } else {
+        "http serve"
+    });
+    if let Some(port_file) = cli.port_file.as_deref()
+        && let Err(err) = tokio::fs::remove_file(port_file).await
+    {
+        warn!("failed to remove port file {}: {err}", port_file.display());
+    }
+    result
+}
+
+/// Binds every `--host` on `port`. With `port = 0` the first successful bind picks the port
+/// and the remaining hosts reuse it. Failed hosts are reported and skipped; it is an error
+/// only when nothing binds.
+async fn bind_listeners(hosts: &[IpAddr], port: u16) -> anyhow::Result<Vec<TcpListener>> {
+    let mut listeners: Vec<TcpListener> = Vec::new();
+    let mut failures = Vec::new();
+    let mut seen = HashSet::new();
+    for host in hosts.iter().filter(|host| seen.insert(**host)) {
+        let port = match listeners.first() {
+            Some(first) => first.local_addr().context("get local addr")?.port(),
+            None => port,
+        };
+        let addr = SocketAddr::new(*host, port);
+        match TcpListener::bind(addr).await {
+            Ok(listener) => listeners.push(listener),
+            Err(err) => {
+                warn!("failed to bind serve listener on {addr}: {err}");
+                failures.push(format!("{addr}: {err}"));
+            }
+        }
+    }
+    if listeners.is_empty() {
+        bail!("bind serve listener: {}", failures.join("; "));
+    }
+    Ok(listeners)
+}
+
+/// Combines `--token` values with `--tokens-file` entries, generating a random token when
+/// neither supplies one.
+async fn load_server_tokens(
+    mut tokens: Vec<String>,
+    tokens_file: Option<&FsPath>,
+) -> anyhow::Result<Vec<String>> {
+    if let Some(path) = tokens_file {
+        let contents = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("read tokens file {}", path.display()))?;
+        tokens.extend(parse_tokens_file(&contents));
+    }
+    tokens.retain(|token| !token.trim().is_empty());
+    let mut seen = HashSet::new();
+    tokens.retain(|token| seen.insert(token.clone()));
+    if tokens.is_empty() {
+        tokens.push(generate_token());
+    }
+    Ok(tokens)
+}
 
+fn parse_tokens_file(contents: &str) -> impl Iterator<Item = String> + '_ {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+}
+
+#[derive(Serialize)]
+struct PortFileContents<'a> {
+    port: u16,
+    token: &'a str,
+}
+
+/// Overwrites `path` with the bound port and token so launcher scripts need not scrape stdout.
+async fn write_port_file(path: &FsPath, port: u16, token: &str) -> anyhow::Result<()> {
+    let contents = serde_json::to_vec(&PortFileContents { port, token })?;
+    tokio::fs::write(path, contents)
+        .await
+        .with_context(|| format!("write port file {}", path.display()))?;
+    // The file carries the server token, so keep it private to the current user.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .await
+            .with_context(|| format!("restrict permissions on {}", path.display()))?;
+    }
     Ok(())
 }
 
+#[cfg(unix)]
+async fn serve_unix_socket(
+    socket_path: &FsPath,
+    app: Router,
+    token: &str,
+    ready: Arc<AtomicBool>,
+    shutdown_rx: watch::Receiver<bool>,
+    shutdown_timeout: Duration,
+) -> anyhow::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    // Only replace a stale socket left behind by a previous run; never clobber a regular file.
+    match tokio::fs::symlink_metadata(socket_path).await {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            tokio::fs::remove_file(socket_path)
+                .await
+                .with_context(|| format!("remove stale socket {}", socket_path.display()))?;
+        }
+        Ok(_) => bail!(
+            "--unix-socket path {} exists and is not a socket",
+            socket_path.display()
+        ),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => {
+            return Err(err).with_context(|| format!("inspect {}", socket_path.display()));
+        }
+    }
+
+    let listener = tokio::net::UnixListener::bind(socket_path)
+        .with_context(|| format!("bind unix socket {}", socket_path.display()))?;
+    tokio::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))
+        .await
+        .with_context(|| format!("restrict permissions on {}", socket_path.display()))?;
+    println!(
+        "Codex Web UI listening on unix socket {} (token: {token})",
+        socket_path.display()
+    );
+    mark_ready_until_shutdown(&ready, shutdown_rx.clone());
+
+    let result = drain_on_shutdown(
+        axum::serve(listener, app.into_make_service())
+            .with_graceful_shutdown(wait_for_shutdown(shutdown_rx.clone()))
+            .into_future(),
+        shutdown_rx,
+        shutdown_timeout,
+    )
+    .await
+    .context("http serve");
+    if let Err(err) = tokio::fs::remove_file(socket_path).await {
+        warn!(
+            "failed to remove unix socket {}: {err}",
+            socket_path.display()
+        );
+    }
+    result
+}
+
+#[cfg(not(unix))]
+async fn serve_unix_socket(
+    _socket_path: &FsPath,
+    _app: Router,
+    _token: &str,
+    _ready: Arc<AtomicBool>,
+    _shutdown_rx: watch::Receiver<bool>,
+    _shutdown_timeout: Duration,
+) -> anyhow::Result<()> {
+    bail!("--unix-socket is only supported on Unix platforms")
+}
+
+/// Flips the returned receiver to `true` on the first SIGINT/SIGTERM, or once `activity`
+/// has been idle for `idle_timeout` (a zero timeout disables the idle check).
+fn spawn_shutdown_listener(
+    activity: Arc<ActivityTracker>,
+    idle_timeout: Duration,
+) -> watch::Receiver<bool> {
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let shutdown_tx = Arc::new(shutdown_tx);
+    if !idle_timeout.is_zero() {
+        idle::spawn_idle_monitor(activity, idle_timeout, Arc::clone(&shutdown_tx));
+    }
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        let _ = shutdown_tx.send(true);
+    });
+    shutdown_rx
+}
+
+/// Flags the server ready for `/readyz`, then clears the flag as soon as shutdown starts so
+/// load balancers stop routing new traffic while connections drain.
+fn mark_ready_until_shutdown(ready: &Arc<AtomicBool>, shutdown_rx: watch::Receiver<bool>) {
+    ready.store(true, Ordering::SeqCst);
+    let ready = Arc::clone(ready);
+    tokio::spawn(async move {
+        wait_for_shutdown(shutdown_rx).await;
+        ready.store(false, Ordering::SeqCst);
+    });
+}
+
+async fn wait_for_shutdown(mut shutdown_rx: watch::Receiver<bool>) {
+    let _ = shutdown_rx.wait_for(|shutdown| *shutdown).await;
+}
+
+/// Runs `serve` until it exits on its own or, once shutdown is signalled, for at most
+/// `timeout` while open connections (SSE streams, terminals) drain.
+async fn drain_on_shutdown(
+    serve: impl Future<Output = std::io::Result<()>>,
+    mut shutdown_rx: watch::Receiver<bool>,
+    timeout: Duration,
+) -> std::io::Result<()> {
+    tokio::pin!(serve);
+    tokio::select! {
+        result = &mut serve => return result,
+        _ = shutdown_rx.wait_for(|shutdown| *shutdown) => {}
+    }
+    match tokio::time::timeout(timeout, serve).await {
+        Ok(result) => result,
+        Err(_) => {
+            warn!("shutdown timed out after {timeout:?}; closing remaining connections");
+            Ok(())
+        }
+    }
+}
+
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                Ok(terminate) => terminate,
+                Err(err) => {
+                    warn!("failed to install SIGTERM handler: {err}");
+                    let _ = tokio::signal::ctrl_c().await;
+                    return;
+                }
+            };
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
 fn build_router(state: AppState) -> Router {
     let authed = Router::new()
         .route("/events", get(handle_events))
         .route("/sessions", get(handle_sessions))
+        .route("/teams", get(handle_teams))
         .route("/kanban", get(handle_get_kanban))
         .route("/models/catalog", get(handle_models_catalog))
         .route("/kanban/cards/{session_id}", put(handle_move_kanban_card))
@@ -2487,35 +3337,309 @@ fn build_router(state: AppState) -> Router {
         )
         .route("/visibility", post(handle_visibility))
         .route("/voice/token", post(handle_voice_token))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            reject_mutations_when_read_only,
+        ))
         .layer(axum::middleware::from_fn_with_state(
             state.clone(),
             require_token,
         ));
 
-    Router::new()
+    let routes = Router::new()
+        .route("/healthz", get(handle_healthz))
+        .route("/readyz", get(handle_readyz))
         .route("/github/webhook", post(handle_github_webhook))
         .route("/api/auth", post(handle_auth))
         .route("/api/bind", post(handle_bind))
         .nest("/api", authed)
+        .route("/ws", get(handle_events_ws))
         .route(
             "/ws/terminal/{session_id}/{terminal_id}",
             get(handle_terminal_ws),
         )
-        .fallback(get(handle_static))
+        .fallback(get(handle_static));
+
+    limit_request_bodies(routes, state.max_body_bytes)
+        .layer(axum::middleware::from_fn_with_state(
+            (
+                Arc::clone(&state.auth_rate_limiter),
+                Arc::clone(&state.tokens),
+            ),
+            throttle_auth_failures,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            track_activity,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::clone(&state.cors_origins),
+            apply_cors,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            log_access,
+        ))
         .with_state(state)
 }
 
+/// Caps every request body at `max_body_bytes`. The limit applies to each request on its own:
+/// a declared `Content-Length` over the cap is refused before any of the body is read, and
+/// bodies without one are cut off by [`DefaultBodyLimit`] while extractors buffer them.
+fn limit_request_bodies<S>(router: Router<S>, max_body_bytes: usize) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router
+        .layer(DefaultBodyLimit::max(max_body_bytes))
+        .layer(axum::middleware::from_fn_with_state(
+            max_body_bytes,
+            reject_oversized_bodies,
+        ))
+}
+
+async fn reject_oversized_bodies(
+    State(max_body_bytes): State<usize>,
+    req: axum::http::Request<Body>,
+    next: axum::middleware::Next,
+) -> Response {
+    let declared_len = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if declared_len.is_some_and(|len| len > max_body_bytes as u64) {
+        return payload_too_large_response(max_body_bytes);
+    }
+    let response = next.run(req).await;
+    // Extractor rejections are plain text; give clients the same JSON body either way.
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE
+        && !response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .is_some_and(|value| value.as_bytes().starts_with(b"application/json"))
+    {
+        return payload_too_large_response(max_body_bytes);
+    }
+    response
+}
+
+fn payload_too_large_response(max_body_bytes: usize) -> Response {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(serde_json::json!({
+            "error": "payload_too_large",
+            "message": format!("request body exceeds the {max_body_bytes}-byte limit"),
+            "maxBodyBytes": max_body_bytes,
+        })),
+    )
+        .into_response()
+}
+
+fn normalize_cors_origins(origins: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for origin in origins {
+        let origin = origin.trim().trim_end_matches('/');
+        if !origin.is_empty() && !normalized.iter().any(|existing| existing == origin) {
+            normalized.push(origin.to_string());
+        }
+    }
+    normalized
+}
+
+/// Adds CORS headers for requests whose `Origin` is listed via `--cors-origin` and answers
+/// their preflights directly, since a preflight carries no token. The echoed origin plus
+/// `Access-Control-Allow-Credentials` lets browsers send the bearer token or cookies on
+/// the real request, which still goes through the normal token check.
+async fn apply_cors(
+    State(origins): State<Arc<Vec<String>>>,
+    req: axum::http::Request<Body>,
+    next: axum::middleware::Next,
+) -> Response {
+    let allowed_origin = req
+        .headers()
+        .get(header::ORIGIN)
+        .filter(|origin| {
+            origin
+                .to_str()
+                .is_ok_and(|origin| origins.iter().any(|allowed| allowed == origin))
+        })
+        .cloned();
+    let Some(origin) = allowed_origin else {
+        return next.run(req).await;
+    };
+
+    let is_preflight = req.method() == Method::OPTIONS
+        && req
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+    let mut response = if is_preflight {
+        let mut response = StatusCode::NO_CONTENT.into_response();
+        let headers = response.headers_mut();
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static("GET, HEAD, POST, PUT, PATCH, DELETE, OPTIONS"),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            HeaderValue::from_static("authorization, content-type"),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_MAX_AGE,
+            HeaderValue::from_static("600"),
+        );
+        response
+    } else {
+        next.run(req).await
+    };
+
+    let headers = response.headers_mut();
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+        HeaderValue::from_static("true"),
+    );
+    headers.append(header::VARY, HeaderValue::from_static("origin"));
+    response
+}
+
 async fn handle_github_webhook(
     State(state): State<AppState>,
     headers: axum::http::HeaderMap,
     body: Bytes,
 ) -> Response {
+    if state.read_only {
+        return read_only_response();
+    }
     let Some(webhook) = state.github_webhook.clone() else {
         return (StatusCode::NOT_FOUND, "not found").into_response();
     };
     webhook.handle_webhook(headers, body).await
 }
 
+/// Rejects clients whose [`AuthRateLimiter`] budget is spent with 429 before their request
+/// reaches the inner service, and charges every 401 against the budget afterwards. Requests
+/// presenting an accepted token (bearer header or `token` query) bypass the limiter entirely,
+/// so a throttled IP does not lock out the legitimate user. Health probes and Unix socket
+/// clients (no peer IP) are not throttled.
+async fn throttle_auth_failures(
+    State((limiter, tokens)): State<(Arc<AuthRateLimiter>, Arc<Vec<String>>)>,
+    req: axum::http::Request<Body>,
+    next: axum::middleware::Next,
+) -> Response {
+    if is_probe_path(req.uri().path()) || request_token_index(&tokens, &req).is_some() {
+        return next.run(req).await;
+    }
+    let client_ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if let Some(ip) = client_ip
+        && !limiter.has_budget(ip)
+    {
+        warn!("throttling failed authentication attempts from {ip}");
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json_error("too_many_auth_failures")),
+        )
+            .into_response();
+    }
+    let response = next.run(req).await;
+    if response.status() == StatusCode::UNAUTHORIZED
+        && let Some(ip) = client_ip
+    {
+        limiter.record_failure(ip);
+    }
+    response
+}
+
+/// With `--access-log`, wraps each request in a `codex_serve::access` span and logs its
+/// outcome. Only the path is recorded because the query string may carry the token; the
+/// token itself is reduced to its index in the accepted list.
+async fn log_access(
+    State(state): State<AppState>,
+    req: axum::http::Request<Body>,
+    next: axum::middleware::Next,
+) -> Response {
+    if !state.access_log || is_probe_path(req.uri().path()) {
+        return next.run(req).await;
+    }
+    let client_ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map_or_else(
+            || "-".to_string(),
+            |ConnectInfo(addr)| addr.ip().to_string(),
+        );
+    let span = tracing::info_span!(
+        target: "codex_serve::access",
+        "request",
+        method = %req.method(),
+        path = req.uri().path(),
+        client_ip = %client_ip,
+        token_index = request_token_index(&state.tokens, &req),
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+    );
+    let started = std::time::Instant::now();
+    let response = next.run(req).instrument(span.clone()).await;
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    span.record("status", response.status().as_u16());
+    span.record("latency_ms", latency_ms);
+    span.in_scope(|| {
+        tracing::info!(
+            target: "codex_serve::access",
+            status = response.status().as_u16(),
+            latency_ms,
+            "request completed"
+        );
+    });
+    response
+}
+
+/// Index of the accepted token presented by `req` (bearer header or `token` query), if any.
+fn request_token_index(tokens: &[String], req: &axum::http::Request<Body>) -> Option<usize> {
+    let token = bearer_token(req.headers()).or_else(|| token_from_query(req.uri().query()))?;
+    authorized_token_index(tokens, &token)
+}
+
+/// Keeps the idle timer from firing while a request is being handled. Long-lived SSE and
+/// WebSocket handlers take their own guard for the lifetime of the stream.
+async fn track_activity(
+    State(state): State<AppState>,
+    req: axum::http::Request<Body>,
+    next: axum::middleware::Next,
+) -> Response {
+    // Load balancer probes are not clients and must not keep an idle server alive.
+    if is_probe_path(req.uri().path()) {
+        return next.run(req).await;
+    }
+    let _activity = state.activity.start();
+    next.run(req).await
+}
+
+fn is_probe_path(path: &str) -> bool {
+    matches!(path, "/healthz" | "/readyz")
+}
+
+/// Liveness probe: answers 200 whenever the listener is accepting requests. No token required.
+async fn handle_healthz() -> Response {
+    Json(serde_json::json!({ "status": "ok" })).into_response()
+}
+
+/// Readiness probe: 200 once the server is serving, 503 before that and while shutting down.
+async fn handle_readyz(State(state): State<AppState>) -> Response {
+    if state.ready.load(Ordering::SeqCst) {
+        Json(serde_json::json!({ "status": "ready" })).into_response()
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "status": "not_ready" })),
+        )
+            .into_response()
+    }
+}
+
 async fn require_token(
     State(state): State<AppState>,
     req: axum::http::Request<Body>,
@@ -2524,12 +3648,30 @@ async fn require_token(
     let token = bearer_token(req.headers())
         .or_else(|| token_from_query(req.uri().query()))
         .unwrap_or_default();
-    if token != state.token.as_str() {
+    if !state.is_authorized(&token) {
         return (StatusCode::UNAUTHORIZED, Json(json_error("unauthorized"))).into_response();
     }
     next.run(req).await
 }
 
+/// In `--read-only` mode, treats every authenticated `/api` request other than GET/HEAD as
+/// mutating and rejects it with 403. The terminal WebSocket and GitHub webhook check
+/// `read_only` themselves since they live outside `/api`.
+async fn reject_mutations_when_read_only(
+    State(state): State<AppState>,
+    req: axum::http::Request<Body>,
+    next: axum::middleware::Next,
+) -> Response {
+    if state.read_only && !matches!(*req.method(), Method::GET | Method::HEAD) {
+        return read_only_response();
+    }
+    next.run(req).await
+}
+
+fn read_only_response() -> Response {
+    (StatusCode::FORBIDDEN, Json(json_error("read_only"))).into_response()
+}
+
 fn bearer_token(headers: &axum::http::HeaderMap) -> Option<String> {
     let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
     let value = value.trim();
@@ -2555,12 +3697,12 @@ fn token_from_query(query: Option<&str>) -> Option<String> {
 async fn handle_auth(State(state): State<AppState>, Json(body): Json<AuthRequest>) -> Response {
     match body {
         AuthRequest::AccessToken { access_token } => {
-            if access_token != state.token.as_str() {
+            if !state.is_authorized(&access_token) {
                 return (StatusCode::UNAUTHORIZED, Json(json_error("unauthorized")))
                     .into_response();
             }
             Json(AuthResponse {
-                token: state.token.as_str().to_string(),
+                token: access_token,
                 user: AuthUser {
                     id: 1,
                     username: Some("local".to_string()),
@@ -2594,7 +3736,7 @@ async fn handle_events(
     Query(query): Query<EventsQuery>,
 ) -> Response {
     let token = query.token.unwrap_or_default();
-    if token != state.token.as_str() {
+    if !state.is_authorized(&token) {
         return (StatusCode::UNAUTHORIZED, Json(json_error("unauthorized"))).into_response();
     }
 
@@ -2608,6 +3750,7 @@ async fn handle_events(
 
     let session_filter = query.session_id;
     let connect_event = sse_json(&connect);
+    let activity = state.activity.start();
     let stream = stream::once(
         async move { Ok::<SseEvent, std::convert::Infallible>(connect_event) },
     )
@@ -2644,11 +3787,110 @@ async fn handle_events(
                 }
             }
         },
-    ));
+    ))
+    .map(move |event| {
+        // The stream owns the guard so an open event subscription counts as a client.
+        let _activity = &activity;
+        event
+    });
 
     Sse::new(stream).into_response()
 }
 
+/// Frames sent on `/ws`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum EventsWsFrame<'a> {
+    Event(&'a SequencedEvent),
+    /// Events after the requested `since` were evicted; the client should refetch state.
+    EventsMissed,
+}
+
+/// Streams every protocol event (including collab events from multi-agent runs) as it is
+/// emitted. Unlike `/api/events`, which carries coarse UI notifications, frames here are the
+/// raw `Event`s tagged with a cursor, so a reconnecting client can pass `since` to resume.
+async fn handle_events_ws(
+    State(state): State<AppState>,
+    Query(query): Query<EventsWsQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let token = query.token.unwrap_or_default();
+    if !state.is_authorized(&token) {
+        return (StatusCode::UNAUTHORIZED, "unauthorized").into_response();
+    }
+    let activity = state.activity.start();
+    ws.on_upgrade(move |socket| async move {
+        let _activity = activity;
+        events_ws_loop(state, socket, query.session_id, query.since).await;
+    })
+}
+
+async fn events_ws_loop(
+    state: AppState,
+    mut socket: WebSocket,
+    session_filter: Option<String>,
+    mut since: Option<u64>,
+) {
+    loop {
+        let Subscription {
+            backlog,
+            missed,
+            last_seq,
+            mut rx,
+        } = state.protocol_events.subscribe_since(since);
+        if missed && !send_events_ws_frame(&mut socket, &EventsWsFrame::EventsMissed).await {
+            return;
+        }
+        for event in &backlog {
+            if !forward_protocol_event(&mut socket, event, session_filter.as_deref()).await {
+                return;
+            }
+        }
+        since = Some(last_seq);
+
+        loop {
+            tokio::select! {
+                msg = socket.recv() => match msg {
+                    Some(Ok(WsMessage::Close(_))) | Some(Err(_)) | None => return,
+                    Some(Ok(_)) => {}
+                },
+                event = rx.recv() => match event {
+                    Ok(event) => {
+                        if !forward_protocol_event(&mut socket, &event, session_filter.as_deref())
+                            .await
+                        {
+                            return;
+                        }
+                        since = Some(event.seq);
+                    }
+                    // Catch up from the buffer instead of silently dropping events.
+                    Err(broadcast::error::RecvError::Lagged(_)) => break,
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+            }
+        }
+    }
+}
+
+/// Sends `event` unless it belongs to another session; returns `false` once the socket is gone.
+async fn forward_protocol_event(
+    socket: &mut WebSocket,
+    event: &SequencedEvent,
+    session_filter: Option<&str>,
+) -> bool {
+    if session_filter.is_some_and(|wanted| wanted != event.session_id) {
+        return true;
+    }
+    send_events_ws_frame(socket, &EventsWsFrame::Event(event)).await
+}
+
+async fn send_events_ws_frame(socket: &mut WebSocket, frame: &EventsWsFrame<'_>) -> bool {
+    let Ok(text) = serde_json::to_string(frame) else {
+        return true;
+    };
+    socket.send(WsMessage::Text(text.into())).await.is_ok()
+}
+
 fn event_matches_session(event: &SyncEvent, session_id: &str) -> bool {
     match event {
         SyncEvent::SessionAdded { session_id: id, .. } => id == session_id,
@@ -3968,6 +5210,21 @@ async fn handle_move_github_kanban_card(
     Json(serde_json::json!({})).into_response()
 }
 
+/// Lists the multi-agent teams persisted under `codex_home/teams`.
+async fn handle_teams(State(state): State<AppState>) -> Response {
+    match codex_core::list_persisted_teams(&state.config.codex_home).await {
+        Ok(teams) => Json(TeamsResponse { teams }).into_response(),
+        Err(err) => {
+            warn!("failed to list persisted teams: {err}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json_error("teams_list_failed")),
+            )
+                .into_response()
+        }
+    }
+}
+
 async fn handle_sessions(State(state): State<AppState>) -> Response {
     let page = match codex_core::RolloutRecorder::list_threads(
         state.config.as_ref(),
@@ -5421,13 +6678,20 @@ async fn handle_terminal_ws(
     Query(query): Query<HashMap<String, String>>,
 ) -> Response {
     let token = query.get("token").cloned().unwrap_or_default();
-    if token != state.token.as_str() {
+    if !state.is_authorized(&token) {
         return (StatusCode::UNAUTHORIZED, "unauthorized").into_response();
     }
+    if state.read_only {
+        return read_only_response();
+    }
     if !state.sessions.read().await.contains_key(&session_id) {
         return (StatusCode::NOT_FOUND, "session not found").into_response();
     }
-    ws.on_upgrade(move |socket| terminal_ws_loop(state, socket, session_id, terminal_id))
+    let activity = state.activity.start();
+    ws.on_upgrade(move |socket| async move {
+        let _activity = activity;
+        terminal_ws_loop(state, socket, session_id, terminal_id).await;
+    })
 }
 
 async fn terminal_ws_loop(
@@ -5627,6 +6891,7 @@ async fn session_event_loop(state: AppState, session_id: String, session: Arc<Ac
             Ok(event) => event,
             Err(_) => break,
         };
+        state.protocol_events.append(&session_id, &event);
         match event.msg {
             EventMsg::TurnStarted(_) => {
                 let now = now_ms();
@@ -0,0 +1,100 @@
+use anyhow::Context;
+use anyhow::bail;
+use axum::serve::Listener;
+use rustls::ServerConfig;
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::PrivateKeyDer;
+use rustls::pki_types::pem::PemObject;
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::server::TlsStream;
+use tracing::debug;
+
+/// Upper bound on a single TLS handshake so a stalled client cannot hold up the accept loop.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Builds a TLS acceptor from a PEM certificate chain and a PEM private key.
+pub(crate) fn load_tls_acceptor(cert_path: &Path, key_path: &Path) -> anyhow::Result<TlsAcceptor> {
+    codex_utils_rustls_provider::ensure_rustls_crypto_provider();
+
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .with_context(|| format!("read TLS certificate {}", cert_path.display()))?
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("parse TLS certificate {}", cert_path.display()))?;
+    if certs.is_empty() {
+        bail!(
+            "no PEM certificates found in TLS certificate {}",
+            cert_path.display()
+        );
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .with_context(|| format!("parse TLS private key {}", key_path.display()))?;
+
+    let mut config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("TLS certificate and private key do not form a valid pair")?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// TCP listener that completes a TLS handshake before handing connections to axum.
+pub(crate) struct TlsListener {
+    inner: TcpListener,
+    acceptor: TlsAcceptor,
+}
+
+impl TlsListener {
+    pub(crate) fn new(inner: TcpListener, acceptor: TlsAcceptor) -> Self {
+        Self { inner, acceptor }
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            let (stream, addr) = Listener::accept(&mut self.inner).await;
+            match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, self.acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => return (stream, addr),
+                Ok(Err(err)) => debug!("TLS handshake with {addr} failed: {err}"),
+                Err(_) => debug!("TLS handshake with {addr} timed out"),
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.inner.local_addr()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_tls_acceptor_rejects_files_without_pem_certificates() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cert = dir.path().join("cert.pem");
+        let key = dir.path().join("key.pem");
+        std::fs::write(&cert, "not a certificate").expect("write cert");
+        std::fs::write(&key, "not a key").expect("write key");
+
+        let err = load_tls_acceptor(&cert, &key)
+            .err()
+            .expect("garbage cert should fail");
+
+        assert!(
+            err.to_string().contains("no PEM certificates found"),
+            "unexpected error: {err:#}"
+        );
+    }
+}